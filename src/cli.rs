@@ -14,19 +14,55 @@
 //! cli.start();
 //! ```
 
-use crate::devices::{door_lock::*, light::*, thermostat::*};
+use crate::error::PulseHomeError;
+use crate::factory::{DeviceFactory, DeviceType};
 use crate::mediator::HomeHub;
-use crate::models::event::EventType;
+use crate::models::argument::{Credential, Track};
+use crate::models::capability::Capabilities;
+use crate::models::color::Color;
+use crate::models::command::Command;
+use crate::models::device::DeviceSnapshot;
+use crate::models::energy::EnergyReport;
+use crate::models::event::Event;
+use crate::models::group::GroupResult;
+use crate::models::reading::SensorReading;
+use crate::models::rule::{Action, Rule};
+use crate::models::scene;
+use crate::models::state::{AttributeValue, Unit};
+use crate::models::temperature::{Temperature, TemperatureUnit};
+use crate::scheduler::sun::Location;
+use crate::scheduler::{Job, Schedule, Scheduler};
+use chrono::{DateTime, Local};
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often [`CLI::start`] advances the hub and runs due schedules while
+/// waiting for input.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Prefix that addresses every device in a room instead of a single device.
+const ROOM_PREFIX: &str = "room:";
+/// Prefix that addresses every member of a group instead of a single device.
+const GROUP_PREFIX: &str = "group:";
 
 /// Represents the command-line interface for interacting with the smart home system.
 pub struct CLI {
     hub: HomeHub,
+    factory: DeviceFactory,
+    last_tick: Instant,
+    unit: TemperatureUnit, // session unit for entering and showing temperatures
+    scene_file: Option<PathBuf>,
+    scheduler: Scheduler,
 }
 
 impl CLI {
     /// Creates a new CLI instance with the given `HomeHub`.
     ///
+    /// The session's temperature unit starts out as the hub's display unit.
+    ///
     /// # Arguments
     /// * `hub` - The `HomeHub` mediator used to manage devices and observers.
     ///
@@ -39,68 +75,353 @@ impl CLI {
     /// let cli = CLI::new(hub);
     /// ```
     pub fn new(hub: HomeHub) -> Self {
-        Self { hub }
+        Self::with_factory(hub, DeviceFactory::with_builtins())
+    }
+
+    /// Creates a new CLI instance that builds devices with the given factory.
+    ///
+    /// Use this to make device types registered by other crates available to
+    /// `add`, `replace` and `load`.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::cli::CLI;
+    /// use pulsehome::devices::light::Light;
+    /// use pulsehome::factory::{DeviceFactory, DeviceType};
+    /// use pulsehome::mediator::HomeHub;
+    ///
+    /// let mut factory = DeviceFactory::with_builtins();
+    /// factory.register(DeviceType::new("nightlight", |name, _| Ok(Box::new(Light::new(name)))));
+    /// let cli = CLI::with_factory(HomeHub::new(), factory);
+    /// ```
+    pub fn with_factory(hub: HomeHub, factory: DeviceFactory) -> Self {
+        Self {
+            unit: hub.temperature_unit(),
+            hub,
+            factory,
+            last_tick: Instant::now(),
+            scene_file: None,
+            scheduler: Scheduler::new(),
+        }
+    }
+
+    /// Returns the CLI with scenes kept in the given file.
+    ///
+    /// Scenes already in the file are loaded into the hub, and the file is
+    /// rewritten whenever a scene is saved or deleted, so scenes persist
+    /// between runs. A missing file is created on the first `scene save`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use pulsehome::cli::CLI;
+    /// use pulsehome::mediator::HomeHub;
+    ///
+    /// let cli = CLI::new(HomeHub::new()).with_scene_file("scenes.txt");
+    /// ```
+    pub fn with_scene_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if path.exists() {
+            match scene::load_file(&path) {
+                Ok(scenes) => {
+                    for scene in scenes {
+                        self.hub.add_scene(scene);
+                    }
+                }
+                Err(e) => self.display_error(&e),
+            }
+        }
+        self.scene_file = Some(path);
+        self
+    }
+
+    /// Returns the CLI with jobs scheduled by the given scheduler.
+    ///
+    /// Use this to start with a location for sunrise and sunset, or with a
+    /// scheduler on a different clock.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::cli::CLI;
+    /// use pulsehome::mediator::HomeHub;
+    /// use pulsehome::scheduler::sun::Location;
+    /// use pulsehome::scheduler::Scheduler;
+    ///
+    /// let mut scheduler = Scheduler::new();
+    /// scheduler.set_location(Location::new(51.5074, -0.1278).unwrap());
+    /// let cli = CLI::new(HomeHub::new()).with_scheduler(scheduler);
+    /// ```
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = scheduler;
+        self
     }
 
     /// Starts the interactive CLI loop.
     ///
-    /// The user can type commands to control devices or type `exit` to quit.
+    /// The user can type commands to control devices or type `exit` (or
+    /// close the input) to quit. Input is read on a separate thread, so every
+    /// [`TICK_INTERVAL`] while the prompt waits, and before each command, the
+    /// hub is advanced by the real time elapsed and scheduled jobs that have
+    /// come due are run.
     pub fn start(&mut self) {
         println!("Welcome to PulseHome Smart Home CLI!");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
         loop {
             print!("> ");
             io::stdout().flush().unwrap();
 
-            let mut input = String::new();
-            if io::stdin().read_line(&mut input).is_err() {
-                eprintln!("Failed to read input");
-                continue;
-            }
+            let input = loop {
+                match lines.recv_timeout(TICK_INTERVAL) {
+                    Ok(line) => break line,
+                    Err(RecvTimeoutError::Timeout) => self.advance_clock(),
+                    Err(RecvTimeoutError::Disconnected) => break "exit".to_string(),
+                }
+            };
 
+            let input = input.trim();
             if input.eq_ignore_ascii_case("help") {
-                Self::print_help();
+                self.print_help();
                 continue;
             }
 
-            let input = input.trim();
             if input.eq_ignore_ascii_case("exit") {
                 println!("Exiting CLI. Goodbye!");
                 break;
             }
 
+            self.advance_clock();
             self.parse_command(input);
         }
     }
 
-    fn print_help() {
+    /// Advances the hub by the real time elapsed since the last call and runs
+    /// the scheduled jobs that have come due.
+    fn advance_clock(&mut self) {
+        let now = Instant::now();
+        self.hub.tick(now - self.last_tick);
+        self.last_tick = now;
+        self.run_schedules();
+    }
+
+    fn print_help(&self) {
         println!("Available commands:");
-        println!("  add <device_type> <device_name> [initial_value] - Add a new device");
-        println!("     device_type: light | thermostat | doorlock");
+        println!("  add <device_type> <device_name> [key=value ...] - Add a new device");
+        let types: Vec<&DeviceType> = self.factory.types().collect();
+        for (row, chunk) in types.chunks(5).enumerate() {
+            let label = if row == 0 { "device_type:" } else { "" };
+            let names: Vec<String> = chunk
+                .iter()
+                .map(|t| {
+                    std::iter::once(&t.name)
+                        .chain(&t.aliases)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join("|")
+                })
+                .collect();
+            println!("     {:<12} {}", label, names.join(" | "));
+        }
+        for device_type in types.iter().filter(|t| !t.options.is_empty()) {
+            println!("     {}", device_type.usage());
+        }
+        println!(
+            "  load <config_file>          - Add the devices listed in a file, one `add` line each"
+        );
         println!("  turn_on <device_name>       - Turn on a light");
         println!("  turn_off <device_name>      - Turn off a light");
         println!("  lock <device_name>          - Lock a door");
-        println!("  unlock <device_name>        - Unlock a door");
-        println!("  set_temp <device_name> <value> - Set thermostat temperature");
+        println!("  unlock <device_name> [pin <code> | as <user>] - Unlock a door");
+        println!(
+            "  add_pin <lock_name> <label> <code> [for <duration>] - Enroll a PIN code (e.g. for 90m, 2h, 7d)"
+        );
+        println!("  revoke_pin <lock_name> <label> - Revoke a PIN code");
+        println!("  authorize <lock_name> <user> - Let a user unlock without a PIN code");
+        println!("  deauthorize <lock_name> <user> - Revoke a user's access");
+        println!("  auto_relock <lock_name> <secs|off> - Relock automatically after unlocking");
+        println!("  jam <lock_name>             - Simulate a jammed bolt");
+        println!("  clear_jam <lock_name>       - Clear a lock's jammed state");
+        println!(
+            "  open | close | stop <device_name> - Open, close or halt a window covering or garage door"
+        );
+        println!("  position <covering_name> <0-100> - Move a window covering to a position");
+        println!("  tilt <covering_name> <-90..90> - Tilt a window covering's slats (degrees)");
+        println!("  fan_speed <fan_name> <off|low|medium|high|0-100%> - Set a fan's speed");
+        println!("  oscillate <fan_name> <on|off> - Turn a standing fan's oscillation on or off");
+        println!("  fan_direction <fan_name> <forward|reverse> - Set a ceiling fan's direction");
+        println!(
+            "  run_zone <controller_name> <zone> <minutes> - Water a zone, after any queued runs"
+        );
+        println!(
+            "  rain_delay <controller_name> <hours|off> - Suspend watering for a number of hours"
+        );
+        println!(
+            "  play | pause | stop | next | previous <player_name> - Control a media player's playback"
+        );
+        println!("  volume <player_name> <0-100> - Set a media player's volume");
+        println!("  mute | unmute <player_name> - Mute or unmute a media player");
+        println!("  source <player_name> to <source> - Switch a media player's input source");
+        println!(
+            "  queue <player_name> track <title> [by <artist>] - Queue a track on a media player"
+        );
+        println!("  test <alarm_name>           - Run a smoke or CO alarm's self-test");
+        println!("  silence <alarm_name>        - Silence a sounding alarm for 10 minutes");
+        println!("  set_temp <device_name> <value>[C|F] - Set thermostat temperature");
+        println!("  mode <thermostat_name> <off|heat|cool|auto|fan_only> - Set HVAC mode");
+        println!("  setpoints <thermostat_name> <heat> <cool> - Set heating and cooling setpoints");
+        println!("  ambient <thermostat_name> <value> - Report the measured room temperature");
+        println!("  dim <light_name> <0-100> [over <secs>] - Set light brightness");
+        println!("  color_temp <light_name> <kelvin> [over <secs>] - Set white color temperature");
+        println!("  color <light_name> <#rrggbb|hsv(h,s,v)> [over <secs>] - Set light color");
+        println!("  report <device_name> <reading> <value> - Feed a sensor reading, e.g.");
+        println!(
+            "     motion on|off, contact open|closed, temperature 21.5C, humidity 45%, illuminance 300,"
+        );
+        println!("     power 1500W, voltage 230V, energy 12.5kWh, obstruction on|off,");
+        println!("     smoke clear|warning|alarm, co clear|warning|alarm");
+        println!(
+            "  energy [device_name] [over <minutes>] - Show power draw and energy used, optionally over the last N minutes"
+        );
+        println!("  remove <device_name>        - Remove a device");
+        println!("  rename <device_name> to <new_name> - Rename a device");
+        println!(
+            "  replace <device_type> <device_name> [key=value ...] - Replace a device, keeping its name"
+        );
+        println!("  room add <room> [on <floor>] - Add a room, optionally on a floor");
+        println!("  room remove <room>          - Remove a room (its devices are kept)");
+        println!("  room list                   - Show floors, rooms and the devices in them");
+        println!("  assign <device_name> to <room> - Place a device in a room");
+        println!("  unassign <device_name>      - Take a device out of its room");
+        println!("  group create <group> with <device>, <device>, ... - Create a group of devices");
+        println!("  group add <device_name> to <group> - Add a device to a group");
+        println!("  group remove <device_name> from <group> - Take a device out of a group");
+        println!("  group delete <group>        - Delete a group (its devices are kept)");
+        println!("  group list                  - Show each group and its members");
+        println!(
+            "     Device commands accept room:<room> or group:<group> in place of a device name,"
+        );
+        println!("     e.g. turn_off room:Bedroom, lock group:All Locks");
+        println!(
+            "  scene save <scene> [with <device>, ...] - Capture device states (all devices by default)"
+        );
+        println!("  scene activate <scene>      - Restore the states captured in a scene");
+        println!("  scene delete <scene>        - Delete a scene");
+        println!("  scene list                  - Show each scene and its target states");
+        println!(
+            "  rule add <rule>: when <trigger>; [if <condition>; ...] then <action>[; then ...] - Add an automation"
+        );
+        println!(
+            "     trigger:     <device> <attribute> becomes <value> | <device> <attribute> changes"
+        );
+        println!("                  | <device> raises <event> | at HH:MM");
+        println!(
+            "     condition:   <device> <attribute> is <value> | between HH:MM and HH:MM | mode is <mode>"
+        );
+        println!("     action:      <single-device command> | scene <scene> | notify <message>");
+        println!("  rule remove|enable|disable <rule> - Delete, resume or pause a rule");
+        println!("  rule test <rule>            - Check a rule's conditions without running it");
+        println!("  rule list                   - Show each rule and whether it is enabled");
+        println!("  home_mode [mode]            - Show or change the home mode (e.g. home, away)");
+        println!(
+            "  schedule add <name>: <schedule>; <command> - Run a single-device command on a schedule"
+        );
+        println!(
+            "     schedule:    at HH:MM [on <days>] | at sunrise|sunset [+|-<n>m|h] [on <days>]"
+        );
+        println!(
+            "                  | cron <min> <hour> <day> <month> <weekday> | once YYYY-MM-DD HH:MM | in <duration>"
+        );
+        println!("     days:        daily | weekdays | weekends | mon,wed,fri");
+        println!("  schedule remove <name>      - Delete a scheduled job");
+        println!("  schedule list               - Show each scheduled job and when it runs next");
+        println!(
+            "  location [<lat> <lon>]      - Show or set the location used for sunrise and sunset"
+        );
         println!("  list                        - List all registered devices");
+        println!(
+            "  status [device_name | room:<room> | group:<group>] - Show the state of one device, a room, a group or all devices"
+        );
+        println!("  unit [C|F]                  - Show or change this session's temperature unit");
         println!("  help                        - Show this help message");
+        println!(
+            "  help <device_name>          - Show the commands, readings and attributes of a device"
+        );
         println!("  exit                        - Exit the CLI");
     }
 
     /// Parses a user command and executes it via the `HomeHub`.
     ///
     /// Supported commands:
-    /// - `add <device_type> <device_name> [initial_value]`
+    /// - `add <device_type> <device_name> [key=value ...]`
+    /// - `load <config_file>`
     /// - `turn_on <device_name>`
     /// - `turn_off <device_name>`
     /// - `lock <device_name>`
-    /// - `unlock <device_name>`
+    /// - `unlock <device_name> [pin <code> | as <user>]`
+    /// - `add_pin <lock_name> <label> <code> [for <duration>]`
+    /// - `revoke_pin <lock_name> <label>`
+    /// - `authorize <lock_name> <user>`
+    /// - `deauthorize <lock_name> <user>`
+    /// - `auto_relock <lock_name> <secs|off>`
+    /// - `jam <lock_name>`
+    /// - `clear_jam <lock_name>`
+    /// - `open <device_name>` / `close <device_name>` / `stop <device_name>`
+    /// - `position <covering_name> <0-100>`
+    /// - `tilt <covering_name> <degrees>`
+    /// - `fan_speed <fan_name> <speed>`
+    /// - `oscillate <fan_name> <on|off>`
+    /// - `fan_direction <fan_name> <forward|reverse>`
+    /// - `run_zone <controller_name> <zone> <minutes>`
+    /// - `rain_delay <controller_name> <hours|off>`
+    /// - `play` / `pause` / `next` / `previous` / `mute` / `unmute <player_name>`
+    /// - `volume <player_name> <0-100>`
+    /// - `source <player_name> to <source>`
+    /// - `queue <player_name> track <title> [by <artist>]`
+    /// - `test <alarm_name>` / `silence <alarm_name>`
     /// - `set_temp <device_name> <value>`
+    /// - `mode <thermostat_name> <mode>`
+    /// - `setpoints <thermostat_name> <heat> <cool>`
+    /// - `ambient <thermostat_name> <value>`
+    /// - `dim <light_name> <level> [over <secs>]`
+    /// - `color_temp <light_name> <kelvin> [over <secs>]`
+    /// - `color <light_name> <#rrggbb|hsv(h,s,v)> [over <secs>]`
+    /// - `report <device_name> <reading> <value>`
+    /// - `energy [device_name] [over <minutes>]`
+    /// - `remove <device_name>`
+    /// - `rename <device_name> to <new_name>`
+    /// - `replace <device_type> <device_name> [key=value ...]`
+    /// - `room add <room> [on <floor>]` / `room remove <room>` / `room list`
+    /// - `assign <device_name> to <room>`
+    /// - `unassign <device_name>`
+    /// - `group create <group> with <device>, ...` / `group delete <group>` / `group list`
+    /// - `group add <device_name> to <group>` / `group remove <device_name> from <group>`
+    /// - `scene save <scene> [with <device>, ...]` / `scene activate <scene>`
+    /// - `scene delete <scene>` / `scene list`
+    /// - `rule add <rule>: when <trigger>[; if <condition> ...]; then <action>[; then <action> ...]`
+    /// - `rule remove <rule>` / `rule enable <rule>` / `rule disable <rule>`
+    /// - `rule test <rule>` / `rule list`
+    /// - `home_mode [mode]`
+    /// - `schedule add <name>: <schedule>; <command>` / `schedule remove <name>` / `schedule list`
+    /// - `location [<latitude> <longitude>]`
     /// - `list`
+    /// - `status [device_name | room:<room> | group:<group>]`
+    /// - `unit [C|F]`
+    /// - `help <device_name>`
+    ///
+    /// Commands that act on a device also accept `room:<room>` in place of the
+    /// device name, running the command on every device in the room that supports it,
+    /// or `group:<group>`, running it on every member of the group.
     ///
     /// # Examples
-    /// ```no_run
+    /// ```ignore
     /// cli.parse_command("add light Living Room Light");
     /// cli.parse_command("turn_on Living Room Light");
+    /// cli.parse_command("add thermostat Bedroom Thermostat target=21");
     /// cli.parse_command("set_temp Bedroom Thermostat 24");
     /// cli.parse_command("list");
     /// ```
@@ -121,79 +442,175 @@ impl CLI {
         match action.as_str() {
             "add" => {
                 if rest.len() < 2 {
-                    eprintln!("Usage: add <device_type> <device_name> [initial_value]");
+                    eprintln!("Usage: add <device_type> <device_name> [key=value ...]");
                     return;
                 }
-                let device_type = rest[0];
-                let device_name = rest[1..].join(" ");
-                match device_type.to_lowercase().as_str() {
-                    "light" => self.hub.register_device(Box::new(Light::new(&device_name))),
-                    "doorlock" => self
-                        .hub
-                        .register_device(Box::new(DoorLock::new(&device_name))),
-                    "thermostat" => {
-                        let temp = rest
-                            .get(2)
-                            .and_then(|v| v.parse::<i32>().ok())
-                            .unwrap_or(22);
-                        self.hub
-                            .register_device(Box::new(Thermostat::new(&device_name, temp)));
+                let device = match self.factory.parse_line(&rest.join(" "), self.unit) {
+                    Ok(device) => device,
+                    Err(e) => {
+                        self.display_error(&e);
+                        return;
                     }
-                    _ => {
-                        eprintln!("Unknown device type '{}'", device_type);
+                };
+                let device_name = device.get_name().to_string();
+                match self.hub.register_device(device) {
+                    Ok(id) => self.display_message(&format!(
+                        "Device '{}' of type '{}' added with ID {}.",
+                        device_name, rest[0], id
+                    )),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "load" => {
+                if rest.is_empty() {
+                    eprintln!("Usage: load <config_file>");
+                    return;
+                }
+                let path = rest.join(" ");
+                let devices = match self.factory.load_file(&path, self.unit) {
+                    Ok(devices) => devices,
+                    Err(e) => {
+                        self.display_error(&e);
                         return;
                     }
+                };
+                let mut added = 0;
+                for device in devices {
+                    match self.hub.register_device(device) {
+                        Ok(_) => added += 1,
+                        Err(e) => self.display_error(&e),
+                    }
                 }
-                self.display_message(&format!(
-                    "Device '{}' of type '{}' added.",
-                    device_name, device_type
-                ));
+                self.display_message(&format!("Loaded {} device(s) from '{}'.", added, path));
             }
-            "turn_on" | "turn_off" | "lock" | "unlock" => {
+            "remove" => {
                 if rest.is_empty() {
-                    eprintln!("Usage: {} <device_name>", action);
+                    eprintln!("Usage: remove <device_name>");
                     return;
                 }
                 let device_name = rest.join(" ");
-                let event_type = match action.as_str() {
-                    "turn_on" => EventType::TurnOn,
-                    "turn_off" => EventType::TurnOff,
-                    "lock" => EventType::Lock,
-                    "unlock" => EventType::Unlock,
-                    _ => unreachable!(),
+                match self.hub.remove_device(&device_name) {
+                    Ok(_) => self.display_message(&format!("Device '{}' removed.", device_name)),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "rename" => {
+                let Some(split) = rest.iter().position(|p| p.eq_ignore_ascii_case("to")) else {
+                    eprintln!("Usage: rename <device_name> to <new_name>");
+                    return;
                 };
-                match self.hub.execute_device_command(&device_name, event_type) {
-                    Ok(event) => self.display_message(&format!(
-                        "Executed command: {} on '{}'. New state: {}",
-                        action,
-                        device_name,
-                        event.payload.unwrap_or("unknown".to_string())
+                let old_name = rest[..split].join(" ");
+                let new_name = rest[split + 1..].join(" ");
+                if old_name.is_empty() || new_name.is_empty() {
+                    eprintln!("Usage: rename <device_name> to <new_name>");
+                    return;
+                }
+                match self.hub.rename_device(&old_name, &new_name) {
+                    Ok(()) => self.display_message(&format!(
+                        "Device '{}' renamed to '{}'.",
+                        old_name, new_name
                     )),
-                    Err(e) => eprintln!("Error: {}", e),
+                    Err(e) => self.display_error(&e),
                 }
             }
-            "set_temp" => {
+            "replace" => {
                 if rest.len() < 2 {
-                    eprintln!("Usage: set_temp <device_name> <temperature>");
+                    eprintln!("Usage: replace <device_type> <device_name> [key=value ...]");
                     return;
                 }
-                let temp_str = rest.last().unwrap();
-                let device_name = rest[..rest.len() - 1].join(" ");
-                match temp_str.parse::<i32>() {
-                    Ok(_temp) => {
+                let device = match self.factory.parse_line(&rest.join(" "), self.unit) {
+                    Ok(device) => device,
+                    Err(e) => {
+                        self.display_error(&e);
+                        return;
+                    }
+                };
+                let device_name = device.get_name().to_string();
+                match self.hub.replace_device(&device_name, device) {
+                    Ok(_) => self.display_message(&format!(
+                        "Device '{}' replaced with a new '{}'.",
+                        device_name, rest[0]
+                    )),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            action if Self::device_usage(action).is_some() => {
+                match self.parse_device_command(action, &rest) {
+                    Ok(Some((device_name, Command::SetTemp(temp))))
+                        if !device_name.starts_with(ROOM_PREFIX)
+                            && !device_name.starts_with(GROUP_PREFIX) =>
+                    {
                         match self
                             .hub
-                            .execute_device_command(&device_name, EventType::SetTemp)
+                            .execute_device_command(&device_name, Command::SetTemp(temp))
                         {
                             Ok(event) => self.display_message(&format!(
                                 "Set temperature for '{}' to {}",
                                 device_name,
-                                event.payload.unwrap_or("unknown".to_string())
+                                self.render_payload(&event)
                             )),
-                            Err(e) => eprintln!("Error: {}", e),
+                            Err(e) => self.display_error(&e),
+                        }
+                    }
+                    Ok(Some((device_name, command))) => {
+                        self.run_command(action, &device_name, command)
+                    }
+                    Ok(None) => eprintln!("Usage: {}", Self::device_usage(action).unwrap()),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "report" => {
+                if rest.len() < 3 {
+                    eprintln!("Usage: report <device_name> <reading> <value>");
+                    return;
+                }
+                let (name_parts, values) = rest.split_at(rest.len() - 2);
+                let device_name = name_parts.join(" ");
+                let reading = match Self::parse_reading(values[0], values[1], self.unit) {
+                    Ok(reading) => reading,
+                    Err(e) => {
+                        self.display_error(&e);
+                        return;
+                    }
+                };
+                match self.hub.report_reading(&device_name, reading) {
+                    Ok(Some(event)) => self.display_message(&format!(
+                        "Reading recorded for '{}'. New state: {}",
+                        device_name,
+                        self.render_payload(&event)
+                    )),
+                    Ok(None) => {
+                        self.display_message(&format!("Reading for '{}' unchanged.", device_name))
+                    }
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "energy" => {
+                let (name_parts, window) = match Self::split_window(&rest) {
+                    Ok(split) => split,
+                    Err(e) => {
+                        self.display_error(&e);
+                        return;
+                    }
+                };
+                let report = if name_parts.is_empty() {
+                    self.hub.energy_report(window)
+                } else {
+                    match self.hub.device_energy(&name_parts.join(" "), window) {
+                        Ok(device) => EnergyReport {
+                            window,
+                            devices: vec![device],
+                        },
+                        Err(e) => {
+                            self.display_error(&e);
+                            return;
                         }
                     }
-                    Err(_) => eprintln!("Invalid temperature '{}'", temp_str),
+                };
+                if report.devices.is_empty() {
+                    self.display_message("No metered devices registered.");
+                } else {
+                    self.display_message(&Self::format_energy_report(&report));
                 }
             }
             "list" => {
@@ -201,66 +618,2391 @@ impl CLI {
                 if devices.is_empty() {
                     self.display_message("No devices registered.");
                 } else {
-                    self.display_message(&format!("Registered devices: {:?}", devices));
+                    self.display_message(&format!("Registered devices: {}", devices.join(", ")));
+                }
+            }
+            "room" => self.parse_room_command(&rest),
+            "group" => self.parse_group_command(&rest),
+            "scene" => self.parse_scene_command(&rest),
+            "rule" => self.parse_rule_command(&rest),
+            "schedule" => self.parse_schedule_command(&rest),
+            "location" => {
+                if let [latitude, longitude] = rest[..] {
+                    let coordinate = |text: &str| {
+                        text.trim_end_matches(',').parse::<f64>().map_err(|_| {
+                            PulseHomeError::InvalidArgument(format!(
+                                "invalid coordinate '{}'",
+                                text
+                            ))
+                        })
+                    };
+                    match coordinate(latitude)
+                        .and_then(|lat| Location::new(lat, coordinate(longitude)?))
+                    {
+                        Ok(location) => self.scheduler.set_location(location),
+                        Err(e) => {
+                            self.display_error(&e);
+                            return;
+                        }
+                    }
+                } else if !rest.is_empty() {
+                    eprintln!("Usage: location [<latitude> <longitude>]");
+                    return;
+                }
+                match self.scheduler.location() {
+                    Some(location) => self.display_message(&format!("Location: {}", location)),
+                    None => self.display_message("No location set."),
+                }
+            }
+            "home_mode" => {
+                if !rest.is_empty() {
+                    self.hub.set_home_mode(&rest.join(" "));
+                }
+                self.display_message(&format!("Home mode: {}", self.hub.home_mode()));
+            }
+            "assign" => {
+                let Some(split) = rest.iter().position(|p| p.eq_ignore_ascii_case("to")) else {
+                    eprintln!("Usage: assign <device_name> to <room>");
+                    return;
+                };
+                let device_name = rest[..split].join(" ");
+                let room = rest[split + 1..].join(" ");
+                match self.hub.assign_device(&device_name, &room) {
+                    Ok(()) => self.display_message(&format!(
+                        "Device '{}' assigned to room '{}'.",
+                        device_name, room
+                    )),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "unassign" => {
+                if rest.is_empty() {
+                    eprintln!("Usage: unassign <device_name>");
+                    return;
+                }
+                let device_name = rest.join(" ");
+                match self.hub.unassign_device(&device_name) {
+                    Ok(Some(room)) => self.display_message(&format!(
+                        "Device '{}' removed from room '{}'.",
+                        device_name, room
+                    )),
+                    Ok(None) => {
+                        self.display_message(&format!("Device '{}' has no room.", device_name))
+                    }
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "status" => {
+                let target = rest.join(" ");
+                let snapshots = if rest.is_empty() {
+                    self.hub.snapshot()
+                } else if let Some(room) = target.strip_prefix(ROOM_PREFIX) {
+                    match self.hub.devices_in_room(room) {
+                        Ok(names) => names
+                            .iter()
+                            .filter_map(|name| self.hub.device_state(name).ok())
+                            .collect(),
+                        Err(e) => {
+                            self.display_error(&e);
+                            return;
+                        }
+                    }
+                } else if let Some(group) = target.strip_prefix(GROUP_PREFIX) {
+                    match self.hub.group_state(group) {
+                        Ok(state) => self.display_message(&format!(
+                            "Group '{}': {}",
+                            group,
+                            state.in_temperature_unit(self.unit)
+                        )),
+                        Err(e) => {
+                            self.display_error(&e);
+                            return;
+                        }
+                    }
+                    self.hub
+                        .group_members(group)
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|name| self.hub.device_state(name).ok())
+                        .collect()
+                } else {
+                    match self.hub.device_state(&rest.join(" ")) {
+                        Ok(snapshot) => vec![snapshot],
+                        Err(e) => {
+                            self.display_error(&e);
+                            return;
+                        }
+                    }
+                };
+                if snapshots.is_empty() {
+                    self.display_message("No devices registered.");
+                } else {
+                    let snapshots: Vec<DeviceSnapshot> = snapshots
+                        .into_iter()
+                        .map(|s| DeviceSnapshot {
+                            state: s.state.in_temperature_unit(self.unit),
+                            ..s
+                        })
+                        .collect();
+                    self.display_message(&Self::format_status_table(&snapshots));
                 }
             }
+            "help" => {
+                if rest.is_empty() {
+                    self.print_help();
+                    return;
+                }
+                let device_name = rest.join(" ");
+                let help = self.hub.device_state(&device_name).and_then(|snapshot| {
+                    self.hub.capabilities(&device_name).map(|capabilities| {
+                        Self::format_capabilities(
+                            &snapshot.name,
+                            &snapshot.device_type,
+                            &capabilities,
+                        )
+                    })
+                });
+                match help {
+                    Ok(help) => self.display_message(&help),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "unit" => match rest.first() {
+                None => self.display_message(&format!("Temperature unit: {}", self.unit)),
+                Some(value) => match value.parse::<TemperatureUnit>() {
+                    Ok(unit) => {
+                        self.unit = unit;
+                        self.display_message(&format!("Temperature unit set to {}", unit));
+                    }
+                    Err(e) => self.display_error(&e),
+                },
+            },
             _ => eprintln!("Unknown command '{}'", action),
         }
     }
 
-    /// Displays a message to the user.
-    ///
-    /// # Arguments
-    /// * `msg` - The message string to display.
-    fn display_message(&self, msg: &str) {
-        println!("{}", msg);
+    /// Executes a command on a device, on every device in a room for a
+    /// `room:<name>` target, or on every member of a group for a
+    /// `group:<name>` target, and reports the outcome.
+    fn run_command(&mut self, action: &str, target: &str, command: Command) {
+        let result = if let Some(room) = target.strip_prefix(ROOM_PREFIX) {
+            self.hub.execute_room_command(room, command)
+        } else if let Some(group) = target.strip_prefix(GROUP_PREFIX) {
+            self.hub.execute_group_command(group, command)
+        } else {
+            match self.hub.execute_device_command(target, command) {
+                Ok(event) => self.display_message(&format!(
+                    "Executed command: {} on '{}'. New state: {}",
+                    action,
+                    target,
+                    self.render_payload(&event)
+                )),
+                Err(e) => self.display_error(&e),
+            }
+            return;
+        };
+        match result {
+            Ok(result) => self.display_group_result(action, target, &result),
+            Err(e) => self.display_error(&e),
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::devices::{door_lock::DoorLock, light::Light, thermostat::Thermostat};
-    use crate::models::event::EventType;
+    /// Reports each member's outcome of a fanned-out command, followed by a
+    /// summary if some members failed.
+    fn display_group_result(&self, action: &str, target: &str, result: &GroupResult) {
+        if result.is_empty() {
+            let message = match target.starts_with(ROOM_PREFIX) {
+                true => format!(
+                    "No device in room '{}' supports '{}'.",
+                    result.target, action
+                ),
+                false => format!("Group '{}' has no members.", result.target),
+            };
+            self.display_message(&message);
+            return;
+        }
+        for (device_name, event) in result.succeeded() {
+            self.display_message(&format!(
+                "Executed command: {} on '{}'. New state: {}",
+                action,
+                device_name,
+                self.render_payload(event)
+            ));
+        }
+        for (device_name, e) in result.failed() {
+            eprintln!("Error on '{}': {}", device_name, e);
+            eprintln!("Hint: {}", Self::error_hint(e));
+        }
+        if !result.is_complete() {
+            self.display_message(&format!(
+                "{} of {} devices in '{}' executed '{}'.",
+                result.succeeded().count(),
+                result.outcomes.len(),
+                result.target,
+                action
+            ));
+        }
+    }
 
-    #[test]
-    fn test_add_command() {
-        let hub = HomeHub::new();
-        let mut cli = CLI::new(hub);
+    /// Handles `room add <room> [on <floor>]`, `room remove <room>` and `room list`.
+    fn parse_room_command(&mut self, args: &[&str]) {
+        const USAGE: &str = "Usage: room add <room> [on <floor>] | room remove <room> | room list";
+        let Some((subcommand, args)) = args.split_first() else {
+            eprintln!("{}", USAGE);
+            return;
+        };
+        match subcommand.to_lowercase().as_str() {
+            "add" if !args.is_empty() => {
+                let split = args.iter().position(|p| p.eq_ignore_ascii_case("on"));
+                let (room, floor) = match split {
+                    Some(split) => (args[..split].join(" "), Some(args[split + 1..].join(" "))),
+                    None => (args.join(" "), None),
+                };
+                match self.hub.add_room(&room, floor.as_deref()) {
+                    Ok(()) => self.display_message(&format!("Room '{}' added.", room)),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "remove" if !args.is_empty() => {
+                let room = args.join(" ");
+                match self.hub.remove_room(&room) {
+                    Ok(()) => self.display_message(&format!("Room '{}' removed.", room)),
+                    Err(e) => self.display_error(&e),
+                }
+            }
+            "list" => self.display_message(&self.format_topology()),
+            _ => eprintln!("{}", USAGE),
+        }
+    }
 
-        // Add devices
-        cli.parse_command("add light Living Room Light");
-        cli.parse_command("add thermostat Bedroom Thermostat 24");
-        cli.parse_command("add doorlock Front Door");
+    /// Handles `group create <group> with <device>, ...`, `group add <device> to <group>`,
+    /// `group remove <device> from <group>`, `group delete <group>` and `group list`.
+    fn parse_group_command(&mut self, args: &[&str]) {
+        const USAGE: &str = "Usage: group create <group> with <device>, <device>, ... | \
+            group add <device_name> to <group> | group remove <device_name> from <group> | \
+            group delete <group> | group list";
+        let Some((subcommand, args)) = args.split_first() else {
+            eprintln!("{}", USAGE);
+            return;
+        };
+        let split_at = |keyword: &str| {
+            args.iter()
+                .position(|p| p.eq_ignore_ascii_case(keyword))
+                .map(|i| (args[..i].join(" "), args[i + 1..].join(" ")))
+        };
+        let outcome = match subcommand.to_lowercase().as_str() {
+            "create" if !args.is_empty() => {
+                let (group, members) =
+                    split_at("with").unwrap_or_else(|| (args.join(" "), String::new()));
+                let members: Vec<&str> = members
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|m| !m.is_empty())
+                    .collect();
+                self.hub
+                    .create_group(&group, &members)
+                    .map(|()| format!("Group '{}' created with {} devices.", group, members.len()))
+            }
+            "add" => match split_at("to") {
+                Some((device_name, group)) => self
+                    .hub
+                    .add_to_group(&group, &device_name)
+                    .map(|()| format!("Device '{}' added to group '{}'.", device_name, group)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return;
+                }
+            },
+            "remove" => match split_at("from") {
+                Some((device_name, group)) => self
+                    .hub
+                    .remove_from_group(&group, &device_name)
+                    .map(|()| format!("Device '{}' removed from group '{}'.", device_name, group)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return;
+                }
+            },
+            "delete" if !args.is_empty() => {
+                let group = args.join(" ");
+                self.hub
+                    .delete_group(&group)
+                    .map(|()| format!("Group '{}' deleted.", group))
+            }
+            "list" => Ok(self.format_groups()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return;
+            }
+        };
+        match outcome {
+            Ok(message) => self.display_message(&message),
+            Err(e) => self.display_error(&e),
+        }
+    }
 
-        let devices = cli.hub.list_devices();
-        assert!(devices.contains(&"Living Room Light".to_string()));
-        assert!(devices.contains(&"Front Door".to_string()));
+    /// Handles `scene save <scene> [with <device>, ...]`, `scene activate <scene>`,
+    /// `scene delete <scene>` and `scene list`.
+    fn parse_scene_command(&mut self, args: &[&str]) {
+        const USAGE: &str = "Usage: scene save <scene> [with <device>, ...] | \
+            scene activate <scene> | scene delete <scene> | scene list";
+        let Some((subcommand, args)) = args.split_first() else {
+            eprintln!("{}", USAGE);
+            return;
+        };
+        let outcome = match subcommand.to_lowercase().as_str() {
+            "save" if !args.is_empty() => {
+                let split = args.iter().position(|p| p.eq_ignore_ascii_case("with"));
+                let (name, devices) = match split {
+                    Some(split) => (args[..split].join(" "), args[split + 1..].join(" ")),
+                    None => (args.join(" "), String::new()),
+                };
+                self.expand_targets(&devices)
+                    .and_then(|devices| {
+                        let devices: Vec<&str> = devices.iter().map(String::as_str).collect();
+                        self.hub.save_scene(&name, &devices).map(|scene| {
+                            format!(
+                                "Scene '{}' saved with {} devices.",
+                                scene.name,
+                                scene.targets.len()
+                            )
+                        })
+                    })
+                    .and_then(|message| self.save_scenes().map(|()| message))
+            }
+            "activate" if !args.is_empty() => match self.hub.activate_scene(&args.join(" ")) {
+                Ok(activation) => {
+                    for (device_name, e) in &activation.failures {
+                        eprintln!("Error on '{}': {}", device_name, e);
+                        eprintln!("Hint: {}", Self::error_hint(e));
+                    }
+                    Ok(activation.event.to_string())
+                }
+                Err(e) => Err(e),
+            },
+            "delete" if !args.is_empty() => {
+                let name = args.join(" ");
+                self.hub
+                    .delete_scene(&name)
+                    .and_then(|_| self.save_scenes())
+                    .map(|()| format!("Scene '{}' deleted.", name))
+            }
+            "list" => Ok(self.format_scenes()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return;
+            }
+        };
+        match outcome {
+            Ok(message) => self.display_message(&message),
+            Err(e) => self.display_error(&e),
+        }
     }
 
-    #[test]
-    fn test_list_command() {
-        let mut hub = HomeHub::new();
-        hub.register_device(Box::new(Light::new("Living Room Light")));
-        let mut cli = CLI::new(hub);
+    /// Handles `rule add <rule>: when <trigger>[; if <condition> ...]; then <action> ...`,
+    /// `rule remove <rule>`, `rule enable <rule>`, `rule disable <rule>`,
+    /// `rule test <rule>` and `rule list`.
+    fn parse_rule_command(&mut self, args: &[&str]) {
+        const USAGE: &str = "Usage: rule add <rule>: when <trigger>; [if <condition>; ...] \
+            then <action>[; then <action> ...] | rule remove|enable|disable|test <rule> | rule list";
+        let Some((subcommand, args)) = args.split_first() else {
+            eprintln!("{}", USAGE);
+            return;
+        };
+        let name = args.join(" ");
+        let outcome = match subcommand.to_lowercase().as_str() {
+            "add" if !args.is_empty() => self.parse_rule(&name).and_then(|rule| {
+                let message = format!("Rule '{}' added: {}", rule.name, rule);
+                self.hub.add_rule(rule).map(|()| message)
+            }),
+            "remove" if !args.is_empty() => self
+                .hub
+                .remove_rule(&name)
+                .map(|rule| format!("Rule '{}' removed.", rule.name)),
+            "enable" | "disable" if !args.is_empty() => {
+                let enabled = subcommand.eq_ignore_ascii_case("enable");
+                self.hub
+                    .set_rule_enabled(&name, enabled)
+                    .map(|()| format!("Rule '{}' {}d.", name, subcommand.to_lowercase()))
+            }
+            "test" if !args.is_empty() => self.hub.test_rule(&name).map(|check| {
+                let mut lines: Vec<String> = check
+                    .conditions
+                    .iter()
+                    .map(|(condition, holds)| match holds {
+                        true => format!("  {}: holds", condition),
+                        false => format!("  {}: does not hold", condition),
+                    })
+                    .collect();
+                lines.push(match check.passes() {
+                    true => format!("Rule '{}' would run if triggered now.", name),
+                    false => format!("Rule '{}' would not run if triggered now.", name),
+                });
+                lines.join("\n")
+            }),
+            "list" => Ok(self.format_rules()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return;
+            }
+        };
+        match outcome {
+            Ok(message) => self.display_message(&message),
+            Err(e) => self.display_error(&e),
+        }
+    }
 
-        // Just call list and ensure no panic
-        cli.parse_command("list");
+    /// Handles `schedule add <name>: <schedule>; <command>`, `schedule remove <name>`
+    /// and `schedule list`.
+    fn parse_schedule_command(&mut self, args: &[&str]) {
+        const USAGE: &str = "Usage: schedule add <name>: <schedule>; <command> | \
+            schedule remove <name> | schedule list";
+        let Some((subcommand, args)) = args.split_first() else {
+            eprintln!("{}", USAGE);
+            return;
+        };
+        let outcome = match subcommand.to_lowercase().as_str() {
+            "add" if !args.is_empty() => self.parse_job(&args.join(" ")).and_then(|job| {
+                let name = job.name.clone();
+                self.scheduler.add(job).map(|next_run| {
+                    format!(
+                        "Schedule '{}' added, next run at {}.",
+                        name,
+                        next_run.format("%Y-%m-%d %H:%M")
+                    )
+                })
+            }),
+            "remove" if !args.is_empty() => self
+                .scheduler
+                .remove(&args.join(" "))
+                .map(|job| format!("Schedule '{}' removed.", job.name)),
+            "list" => Ok(self.format_schedules()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return;
+            }
+        };
+        match outcome {
+            Ok(message) => self.display_message(&message),
+            Err(e) => self.display_error(&e),
+        }
     }
 
-    #[test]
-    fn test_turn_on_off_commands() {
-        let mut hub = HomeHub::new();
-        hub.register_device(Box::new(Light::new("Living Room Light")));
-        let mut cli = CLI::new(hub);
+    /// Builds a job from `<name>: <schedule>; <command>`, where the schedule may
+    /// also be `in <duration>` for a single run from now. The command must
+    /// address a single device, so a job cannot be added that fails every run.
+    fn parse_job(&self, definition: &str) -> Result<Job, PulseHomeError> {
+        let (name, rest) = definition.split_once(':').ok_or_else(|| {
+            PulseHomeError::InvalidArgument("expected '<name>: <schedule>; <command>'".to_string())
+        })?;
+        let (schedule, command) = rest.split_once(';').ok_or_else(|| {
+            PulseHomeError::InvalidArgument(format!(
+                "expected '; <command>' after the schedule '{}'",
+                rest.trim()
+            ))
+        })?;
+        let schedule = match schedule.trim().strip_prefix("in ") {
+            Some(duration) => {
+                let at = self
+                    .scheduler
+                    .now()
+                    .checked_add_signed(Self::parse_duration(duration.trim())?)
+                    .ok_or_else(|| {
+                        PulseHomeError::InvalidArgument(format!("invalid duration '{}'", duration))
+                    })?;
+                Schedule::Once(at)
+            }
+            None => schedule.parse()?,
+        };
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let (device, command) = self.parse_action_command(&words)?;
+        Ok(Job::new(name.trim(), schedule, device, command))
+    }
 
-        cli.parse_command("turn_on Living Room Light");
-        let device = cli
-            .hub
-            .list_devices()
-            .iter()
-            .find(|d| d.as_str() == "Living Room Light")
-            .unwrap();
+    /// Runs the scheduled jobs that are due, reporting the ones that fail.
+    fn run_schedules(&mut self) {
+        for (name, result) in self.scheduler.run_due(&mut self.hub) {
+            if let Err(e) = result {
+                eprintln!("Error in schedule '{}': {}", name, e);
+                eprintln!("Hint: {}", Self::error_hint(&e));
+            }
+        }
+    }
+
+    /// Lists each scheduled job with its next run.
+    fn format_schedules(&self) -> String {
+        let jobs = self.scheduler.jobs();
+        if jobs.is_empty() {
+            return "No schedules defined.".to_string();
+        }
+        jobs.iter()
+            .map(|job| match job.next_run() {
+                Some(at) => format!(
+                    "{}: {} (next: {})",
+                    job.name,
+                    job,
+                    at.format("%Y-%m-%d %H:%M")
+                ),
+                None => format!("{}: {}", job.name, job),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Builds a rule from `<rule>: when <trigger>[; if <condition> ...]; then <action> ...`.
+    fn parse_rule(&self, definition: &str) -> Result<Rule, PulseHomeError> {
+        let invalid = |message: &str| PulseHomeError::InvalidArgument(message.to_string());
+        let (name, clauses) = definition
+            .split_once(':')
+            .ok_or_else(|| invalid("expected '<rule>: when <trigger>; then <action>'"))?;
+        let mut clauses = clauses.split(';').map(str::trim).filter(|c| !c.is_empty());
+        let trigger = clauses
+            .next()
+            .and_then(|c| c.strip_prefix("when "))
+            .ok_or_else(|| invalid("a rule must start with 'when <trigger>'"))?;
+        let mut rule = Rule::new(name.trim(), trigger.parse()?);
+        for clause in clauses {
+            if let Some(condition) = clause.strip_prefix("if ") {
+                rule = rule.condition(condition.parse()?);
+            } else if let Some(action) = clause.strip_prefix("then ") {
+                rule = rule.action(self.parse_action(action)?);
+            } else {
+                return Err(PulseHomeError::InvalidArgument(format!(
+                    "expected 'if <condition>' or 'then <action>', found '{}'",
+                    clause
+                )));
+            }
+        }
+        if rule.actions.is_empty() {
+            return Err(invalid("a rule needs at least one 'then <action>'"));
+        }
+        Ok(rule)
+    }
+
+    /// Builds a rule action: `scene <scene>`, `notify <message>` or a device
+    /// command written as it would be typed at the prompt.
+    fn parse_action(&self, action: &str) -> Result<Action, PulseHomeError> {
+        let words: Vec<&str> = action.split_whitespace().collect();
+        match words.split_first() {
+            Some((&"scene", scene)) if !scene.is_empty() => Ok(Action::Scene(scene.join(" "))),
+            Some((&"notify", message)) if !message.is_empty() => {
+                Ok(Action::Notify(message.join(" ")))
+            }
+            _ => {
+                let (device, command) = self.parse_action_command(&words)?;
+                Ok(Action::Command { device, command })
+            }
+        }
+    }
+
+    /// Parses a device command in prompt syntax (`<action> <device_name> ...`)
+    /// into the device name and command, for rule actions and scheduled jobs.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if the command is malformed
+    /// or targets a `room:` or `group:` rather than a single device.
+    fn parse_action_command(&self, words: &[&str]) -> Result<(String, Command), PulseHomeError> {
+        let (action, args) = words.split_first().ok_or_else(|| {
+            PulseHomeError::InvalidArgument("expected a device command".to_string())
+        })?;
+        let action = action.to_lowercase();
+        let (device, command) = self.parse_device_command(&action, args)?.ok_or_else(|| {
+            PulseHomeError::InvalidArgument(format!(
+                "expected '{}'",
+                Self::device_usage(&action).unwrap_or_default()
+            ))
+        })?;
+        if device.starts_with(ROOM_PREFIX) || device.starts_with(GROUP_PREFIX) {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "'{}' is not a single device (actions take one device name)",
+                device
+            )));
+        }
+        Ok((device, command))
+    }
+
+    /// Parses the arguments of a device command into the target device name
+    /// and the command, or returns `None` if arguments are missing.
+    ///
+    /// This is the one verb table behind the prompt, rule actions and
+    /// scheduled jobs. Temperatures without a unit suffix are read in the
+    /// session unit.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if `action` is not a device
+    /// command or one of its values is invalid.
+    fn parse_device_command(
+        &self,
+        action: &str,
+        args: &[&str],
+    ) -> Result<Option<(String, Command)>, PulseHomeError> {
+        if Self::device_usage(action).is_none() {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "unknown device command '{}'",
+                action
+            )));
+        }
+        let (name_parts, command) = match action {
+            "source" | "queue" => {
+                let keyword = if action == "source" { "to" } else { "track" };
+                let Some(split) = args.iter().position(|p| p.eq_ignore_ascii_case(keyword)) else {
+                    return Ok(None);
+                };
+                let (name_parts, value) = (&args[..split], &args[split + 1..]);
+                if value.is_empty() {
+                    return Ok(None);
+                }
+                let command = if action == "source" {
+                    Command::SetSource(value.join(" "))
+                } else {
+                    Command::QueueTrack(Self::parse_track(value))
+                };
+                (name_parts, command)
+            }
+            _ => {
+                let (args, transition) = match action {
+                    "dim" | "color_temp" | "color" => Self::split_transition(args)?,
+                    _ => (args, None),
+                };
+                let (args, expires) = match action {
+                    "add_pin" | "revoke_pin" | "authorize" | "deauthorize" | "auto_relock"
+                    | "jam" | "clear_jam" => Self::split_expiry(args)?,
+                    _ => (args, None),
+                };
+                let (args, credential) = match action {
+                    "unlock" => Self::split_credential(args),
+                    _ => (args, None),
+                };
+                let arity = match action {
+                    "setpoints" | "add_pin" | "run_zone" => 2,
+                    "set_temp" | "mode" | "ambient" | "revoke_pin" | "authorize"
+                    | "deauthorize" | "auto_relock" | "position" | "tilt" | "fan_speed"
+                    | "oscillate" | "fan_direction" | "volume" | "rain_delay" | "dim"
+                    | "color_temp" | "color" => 1,
+                    _ => 0,
+                };
+                if args.len() < arity + 1 {
+                    return Ok(None);
+                }
+                let (name_parts, values) = args.split_at(args.len() - arity);
+                let command = match action {
+                    "set_temp" => Command::SetTemp(Temperature::parse(values[0], self.unit)?),
+                    "mode" | "setpoints" | "ambient" => {
+                        Self::parse_thermostat_command(action, values, self.unit)?
+                    }
+                    "add_pin" | "revoke_pin" | "authorize" | "deauthorize" | "auto_relock"
+                    | "jam" | "clear_jam" => Self::parse_lock_command(action, values, expires)?,
+                    "position" | "tilt" => Self::parse_covering_command(action, values[0])?,
+                    "fan_speed" | "oscillate" | "fan_direction" => {
+                        Self::parse_fan_command(action, values[0])?
+                    }
+                    "volume" => Command::SetVolume(
+                        values[0].trim_end_matches('%').parse().map_err(|_| {
+                            PulseHomeError::InvalidArgument(format!(
+                                "invalid volume '{}'",
+                                values[0]
+                            ))
+                        })?,
+                    ),
+                    "run_zone" | "rain_delay" => Self::parse_irrigation_command(action, values)?,
+                    "dim" | "color_temp" | "color" => {
+                        Self::parse_light_command(action, values[0], transition)?
+                    }
+                    _ => Self::simple_command(action, credential)
+                        .expect("every device command has a parser"),
+                };
+                (name_parts, command)
+            }
+        };
+        if name_parts.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some((name_parts.join(" "), command)))
+    }
+
+    /// Lists each rule with whether it is enabled.
+    fn format_rules(&self) -> String {
+        let rules = self.hub.rules();
+        if rules.is_empty() {
+            return "No rules defined.".to_string();
+        }
+        rules
+            .iter()
+            .map(|rule| {
+                let status = if rule.enabled { "enabled" } else { "disabled" };
+                format!("{} [{}]: {}", rule.name, status, rule)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Resolves a comma-separated list of devices, where `room:<room>` and
+    /// `group:<group>` stand for their devices, into device names.
+    fn expand_targets(&self, list: &str) -> Result<Vec<String>, PulseHomeError> {
+        let mut names: Vec<String> = Vec::new();
+        for target in list.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let expanded = if let Some(room) = target.strip_prefix(ROOM_PREFIX) {
+                self.hub.devices_in_room(room)?
+            } else if let Some(group) = target.strip_prefix(GROUP_PREFIX) {
+                self.hub.group_members(group)?
+            } else {
+                vec![target.to_string()]
+            };
+            for name in expanded {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Writes the hub's scenes to the scene file, if there is one.
+    fn save_scenes(&self) -> Result<(), PulseHomeError> {
+        match &self.scene_file {
+            Some(path) => scene::save_file(path, self.hub.scenes()),
+            None => Ok(()),
+        }
+    }
+
+    /// Lists each scene with the target state of every device in it.
+    fn format_scenes(&self) -> String {
+        let scenes = self.hub.scenes();
+        if scenes.is_empty() {
+            return "No scenes defined.".to_string();
+        }
+        let mut lines = Vec::new();
+        for scene in scenes {
+            lines.push(scene.name.clone());
+            for (device_name, state) in &scene.targets {
+                lines.push(format!(
+                    "  {}: {}",
+                    device_name,
+                    state.in_temperature_unit(self.unit)
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Lists each group with its members.
+    fn format_groups(&self) -> String {
+        let groups = self.hub.groups();
+        if groups.is_empty() {
+            return "No groups defined.".to_string();
+        }
+        groups
+            .iter()
+            .map(|group| {
+                let members = self.hub.group_members(group).unwrap_or_default();
+                match members.is_empty() {
+                    true => format!("{}: (empty)", group),
+                    false => format!("{}: {}", group, members.join(", ")),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Lists rooms grouped by floor with their devices, then any unassigned devices.
+    fn format_topology(&self) -> String {
+        let topology = self.hub.topology();
+        if topology.rooms().is_empty() {
+            return "No rooms defined.".to_string();
+        }
+        let room_line = |room: &str| {
+            let devices = self.hub.devices_in_room(room).unwrap_or_default();
+            match devices.is_empty() {
+                true => format!("  {}: (empty)", room),
+                false => format!("  {}: {}", room, devices.join(", ")),
+            }
+        };
+        let floors = topology
+            .floors()
+            .into_iter()
+            .map(Some)
+            .chain(std::iter::once(None));
+        let mut lines = Vec::new();
+        for floor in floors {
+            let rooms: Vec<_> = topology
+                .rooms()
+                .iter()
+                .filter(|r| r.floor.as_deref() == floor)
+                .collect();
+            if rooms.is_empty() {
+                continue;
+            }
+            lines.push(floor.unwrap_or("No floor").to_string());
+            lines.extend(rooms.iter().map(|r| room_line(&r.name)));
+        }
+        let unassigned: Vec<String> = self
+            .hub
+            .list_devices()
+            .into_iter()
+            .filter(|name| self.hub.room_of(name).is_none())
+            .collect();
+        if !unassigned.is_empty() {
+            lines.push(format!("Unassigned: {}", unassigned.join(", ")));
+        }
+        lines.join("\n")
+    }
+
+    /// Renders an event's payload, with temperatures in the session unit.
+    fn render_payload(&self, event: &Event) -> String {
+        event.payload.as_ref().map_or_else(
+            || "unknown".to_string(),
+            |p| p.in_temperature_unit(self.unit).to_string(),
+        )
+    }
+
+    /// Returns the usage line of a device command, or `None` if `action` is
+    /// not a device command.
+    fn device_usage(action: &str) -> Option<&'static str> {
+        Some(match action {
+            "turn_on" => "turn_on <device_name>",
+            "turn_off" => "turn_off <device_name>",
+            "lock" => "lock <lock_name>",
+            "unlock" => "unlock <lock_name> [pin <code> | as <user>]",
+            "open" => "open <device_name>",
+            "close" => "close <device_name>",
+            "stop" => "stop <device_name>",
+            "test" => "test <alarm_name>",
+            "silence" => "silence <alarm_name>",
+            "play" => "play <player_name>",
+            "pause" => "pause <player_name>",
+            "next" => "next <player_name>",
+            "previous" => "previous <player_name>",
+            "mute" => "mute <player_name>",
+            "unmute" => "unmute <player_name>",
+            "set_temp" => "set_temp <device_name> <temperature>",
+            "mode" => "mode <thermostat_name> <off|heat|cool|auto|fan_only>",
+            "setpoints" => "setpoints <thermostat_name> <heat> <cool>",
+            "ambient" => "ambient <thermostat_name> <temperature>",
+            "add_pin" => "add_pin <lock_name> <label> <code> [for <duration>]",
+            "revoke_pin" => "revoke_pin <lock_name> <label>",
+            "authorize" => "authorize <lock_name> <user>",
+            "deauthorize" => "deauthorize <lock_name> <user>",
+            "auto_relock" => "auto_relock <lock_name> <secs|off>",
+            "jam" => "jam <lock_name>",
+            "clear_jam" => "clear_jam <lock_name>",
+            "position" => "position <covering_name> <0-100>",
+            "tilt" => "tilt <covering_name> <-90..90>",
+            "fan_speed" => "fan_speed <fan_name> <off|low|medium|high|0-100%>",
+            "oscillate" => "oscillate <fan_name> <on|off>",
+            "fan_direction" => "fan_direction <fan_name> <forward|reverse>",
+            "volume" => "volume <player_name> <0-100>",
+            "source" => "source <player_name> to <source>",
+            "queue" => "queue <player_name> track <title> [by <artist>]",
+            "run_zone" => "run_zone <controller_name> <zone> <minutes>",
+            "rain_delay" => "rain_delay <controller_name> <hours|off>",
+            "dim" => "dim <light_name> <0-100> [over <secs>]",
+            "color_temp" => "color_temp <light_name> <kelvin> [over <secs>]",
+            "color" => "color <light_name> <#rrggbb|hsv(h,s,v)> [over <secs>]",
+            _ => return None,
+        })
+    }
+
+    /// Returns the command for an action that takes only a device name, if it is one.
+    fn simple_command(action: &str, credential: Option<Credential>) -> Option<Command> {
+        Some(match action {
+            "turn_on" => Command::TurnOn,
+            "turn_off" => Command::TurnOff,
+            "lock" => Command::Lock,
+            "unlock" => Command::Unlock(credential),
+            "open" => Command::Open,
+            "close" => Command::Close,
+            "stop" => Command::Stop,
+            "test" => Command::Test,
+            "silence" => Command::Silence,
+            "play" => Command::Play,
+            "pause" => Command::Pause,
+            "next" => Command::Next,
+            "previous" => Command::Previous,
+            "mute" => Command::SetMute(true),
+            "unmute" => Command::SetMute(false),
+            _ => return None,
+        })
+    }
+
+    /// Builds a `mode`, `setpoints` or `ambient` command from its value arguments.
+    ///
+    /// Temperatures without a unit suffix are read in `unit`.
+    fn parse_thermostat_command(
+        action: &str,
+        values: &[&str],
+        unit: TemperatureUnit,
+    ) -> Result<Command, PulseHomeError> {
+        let temp = |value: &str| Temperature::parse(value, unit);
+        match action {
+            "mode" => Ok(Command::SetHvacMode(values[0].parse()?)),
+            "setpoints" => Ok(Command::SetSetpoints {
+                heat: temp(values[0])?,
+                cool: temp(values[1])?,
+            }),
+            _ => Ok(Command::ReportAmbient(temp(values[0])?)),
+        }
+    }
+
+    /// Builds a `position` or `tilt` command from its value argument.
+    fn parse_covering_command(action: &str, value: &str) -> Result<Command, PulseHomeError> {
+        let invalid = || PulseHomeError::InvalidArgument(format!("invalid {} '{}'", action, value));
+        match action {
+            "position" => value
+                .trim_end_matches('%')
+                .parse()
+                .map(Command::SetPosition)
+                .map_err(|_| invalid()),
+            _ => value
+                .trim_end_matches('°')
+                .parse()
+                .map(Command::SetTilt)
+                .map_err(|_| invalid()),
+        }
+    }
+
+    /// Builds a `fan_speed`, `oscillate` or `fan_direction` command from its value argument.
+    fn parse_fan_command(action: &str, value: &str) -> Result<Command, PulseHomeError> {
+        match action {
+            "fan_speed" => Ok(Command::SetFanSpeed(value.parse()?)),
+            "oscillate" => match value.to_lowercase().as_str() {
+                "on" | "yes" => Ok(Command::SetOscillation(true)),
+                "off" | "no" => Ok(Command::SetOscillation(false)),
+                _ => Err(PulseHomeError::InvalidArgument(format!(
+                    "invalid oscillation '{}' (expected on or off)",
+                    value
+                ))),
+            },
+            _ => Ok(Command::SetFanDirection(value.parse()?)),
+        }
+    }
+
+    /// Builds a track from `<title> [by <artist>]`, splitting at the last `by`.
+    fn parse_track(words: &[&str]) -> Track {
+        match words.iter().rposition(|w| w.eq_ignore_ascii_case("by")) {
+            Some(split) if split > 0 && split + 1 < words.len() => {
+                Track::new(words[..split].join(" ")).by(words[split + 1..].join(" "))
+            }
+            _ => Track::new(words.join(" ")),
+        }
+    }
+
+    /// Builds a `run_zone` or `rain_delay` command from its value arguments.
+    ///
+    /// A bare number counts minutes for `run_zone` and hours for `rain_delay`;
+    /// an `s`, `m` or `h` suffix picks the unit explicitly.
+    fn parse_irrigation_command(action: &str, values: &[&str]) -> Result<Command, PulseHomeError> {
+        let secs = |value: &str, default_secs_per_unit: u64| {
+            let (amount, secs_per_unit) = match value.char_indices().last() {
+                Some((i, 's')) => (&value[..i], 1),
+                Some((i, 'm')) => (&value[..i], 60),
+                Some((i, 'h')) => (&value[..i], 3600),
+                _ => (value, default_secs_per_unit),
+            };
+            amount
+                .parse::<u64>()
+                .ok()
+                .and_then(|n| n.checked_mul(secs_per_unit))
+                .map(Duration::from_secs)
+                .ok_or_else(|| {
+                    PulseHomeError::InvalidArgument(format!("invalid value '{}'", value))
+                })
+        };
+        match action {
+            "run_zone" => Ok(Command::RunZone {
+                zone: values[0].parse().map_err(|_| {
+                    PulseHomeError::InvalidArgument(format!("invalid zone '{}'", values[0]))
+                })?,
+                duration: secs(values[1], 60)?,
+            }),
+            _ if values[0].eq_ignore_ascii_case("off") => Ok(Command::SetRainDelay(None)),
+            _ => Ok(Command::SetRainDelay(Some(secs(values[0], 3600)?))),
+        }
+    }
+
+    /// Builds a lock management command from its value arguments.
+    fn parse_lock_command(
+        action: &str,
+        values: &[&str],
+        expires: Option<DateTime<Local>>,
+    ) -> Result<Command, PulseHomeError> {
+        if expires.is_some() && action != "add_pin" {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "'for' is only valid with add_pin, not {}",
+                action
+            )));
+        }
+        match action {
+            "add_pin" => Ok(Command::AddPin {
+                label: values[0].to_string(),
+                pin: values[1].to_string(),
+                expires,
+            }),
+            "revoke_pin" => Ok(Command::RevokePin(values[0].to_string())),
+            "authorize" => Ok(Command::AuthorizeUser(values[0].to_string())),
+            "deauthorize" => Ok(Command::RevokeUser(values[0].to_string())),
+            "auto_relock" if values[0].eq_ignore_ascii_case("off") => {
+                Ok(Command::SetAutoRelock(None))
+            }
+            "auto_relock" => {
+                let secs = values[0]
+                    .trim_end_matches('s')
+                    .parse::<u64>()
+                    .map_err(|_| {
+                        PulseHomeError::InvalidArgument(format!("invalid delay '{}'", values[0]))
+                    })?;
+                Ok(Command::SetAutoRelock(Some(Duration::from_secs(secs))))
+            }
+            "jam" => Ok(Command::ReportJam),
+            _ => Ok(Command::ClearJam),
+        }
+    }
+
+    /// Splits a trailing `pin <code>` or `as <user>` credential off the arguments.
+    fn split_credential<'a>(args: &'a [&'a str]) -> (&'a [&'a str], Option<Credential>) {
+        match args {
+            [head @ .., keyword, value] if keyword.eq_ignore_ascii_case("pin") => {
+                (head, Some(Credential::Pin(value.to_string())))
+            }
+            [head @ .., keyword, value] if keyword.eq_ignore_ascii_case("as") => {
+                (head, Some(Credential::User(value.to_string())))
+            }
+            _ => (args, None),
+        }
+    }
+
+    /// Splits a trailing `for <duration>` clause off the arguments and turns
+    /// it into an expiry time.
+    ///
+    /// The duration is a number with an optional `m` (default), `h` or `d` suffix.
+    fn split_expiry<'a>(
+        args: &'a [&'a str],
+    ) -> Result<(&'a [&'a str], Option<DateTime<Local>>), PulseHomeError> {
+        match args {
+            [head @ .., keyword, duration] if keyword.eq_ignore_ascii_case("for") => {
+                let expires = Local::now()
+                    .checked_add_signed(Self::parse_duration(duration)?)
+                    .ok_or_else(|| {
+                        PulseHomeError::InvalidArgument(format!("invalid duration '{}'", duration))
+                    })?;
+                Ok((head, Some(expires)))
+            }
+            _ => Ok((args, None)),
+        }
+    }
+
+    /// Builds a sensor reading from its kind and value (e.g. `motion on`, `humidity 45%`).
+    ///
+    /// Temperatures without a unit suffix are read in `unit`.
+    fn parse_reading(
+        kind: &str,
+        value: &str,
+        unit: TemperatureUnit,
+    ) -> Result<SensorReading, PulseHomeError> {
+        let invalid =
+            || PulseHomeError::InvalidArgument(format!("invalid {} reading '{}'", kind, value));
+        let number = |suffix: &str| {
+            value
+                .trim_end_matches(suffix)
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(invalid)
+        };
+        match kind.to_lowercase().as_str() {
+            "motion" => match value.to_lowercase().as_str() {
+                "on" | "detected" | "yes" => Ok(SensorReading::Motion(true)),
+                "off" | "clear" | "no" => Ok(SensorReading::Motion(false)),
+                _ => Err(invalid()),
+            },
+            "contact" => match value.to_lowercase().as_str() {
+                "open" => Ok(SensorReading::Contact { open: true }),
+                "closed" | "close" => Ok(SensorReading::Contact { open: false }),
+                _ => Err(invalid()),
+            },
+            "temperature" | "temp" => {
+                Ok(SensorReading::Temperature(Temperature::parse(value, unit)?))
+            }
+            "humidity" => Ok(SensorReading::Humidity(number("%")?)),
+            "illuminance" | "lux" => Ok(SensorReading::Illuminance(number("lx")?)),
+            "power" => Ok(SensorReading::Power(number("W")?)),
+            "voltage" => Ok(SensorReading::Voltage(number("V")?)),
+            "energy" => Ok(SensorReading::Energy(number("kWh")?)),
+            "obstruction" => match value.to_lowercase().as_str() {
+                "on" | "detected" | "yes" => Ok(SensorReading::Obstruction(true)),
+                "off" | "clear" | "no" => Ok(SensorReading::Obstruction(false)),
+                _ => Err(invalid()),
+            },
+            "smoke" => Ok(SensorReading::Smoke(value.parse()?)),
+            "co" | "carbon_monoxide" => Ok(SensorReading::CarbonMonoxide(value.parse()?)),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "unknown reading '{}' (expected motion, contact, temperature, humidity, illuminance, power, voltage, energy, obstruction, smoke or co)",
+                kind
+            ))),
+        }
+    }
+
+    /// Parses a positive duration in minutes, or with a `m`, `h` or `d` suffix
+    /// (e.g. `90m`, `2h`, `7d`).
+    fn parse_duration(duration: &str) -> Result<chrono::Duration, PulseHomeError> {
+        let invalid =
+            || PulseHomeError::InvalidArgument(format!("invalid duration '{}'", duration));
+        let (number, minutes_per_unit) = match duration.chars().last() {
+            Some('d') => (&duration[..duration.len() - 1], 24 * 60),
+            Some('h') => (&duration[..duration.len() - 1], 60),
+            Some('m') => (&duration[..duration.len() - 1], 1),
+            _ => (duration, 1),
+        };
+        let amount = number
+            .parse::<i64>()
+            .ok()
+            .filter(|amount| *amount > 0)
+            .ok_or_else(invalid)?;
+        let minutes = amount.checked_mul(minutes_per_unit).ok_or_else(invalid)?;
+        chrono::Duration::try_minutes(minutes).ok_or_else(invalid)
+    }
+
+    /// Splits a trailing `over <secs>` clause off the arguments.
+    fn split_transition<'a>(
+        args: &'a [&'a str],
+    ) -> Result<(&'a [&'a str], Option<Duration>), PulseHomeError> {
+        match args {
+            [head @ .., over, secs] if over.eq_ignore_ascii_case("over") => {
+                let secs = secs
+                    .trim_end_matches('s')
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                    .ok_or_else(|| {
+                        PulseHomeError::InvalidArgument(format!("invalid duration '{}'", secs))
+                    })?;
+                Ok((head, Some(secs)))
+            }
+            _ => Ok((args, None)),
+        }
+    }
+
+    /// Splits a trailing `over <minutes>` clause off the arguments.
+    fn split_window<'a>(
+        args: &'a [&'a str],
+    ) -> Result<(&'a [&'a str], Option<Duration>), PulseHomeError> {
+        match args {
+            [head @ .., over, minutes] if over.eq_ignore_ascii_case("over") => {
+                let secs = minutes
+                    .trim_end_matches('m')
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|m| m.checked_mul(60))
+                    .ok_or_else(|| {
+                        PulseHomeError::InvalidArgument(format!("invalid duration '{}'", minutes))
+                    })?;
+                Ok((head, Some(Duration::from_secs(secs))))
+            }
+            _ => Ok((args, None)),
+        }
+    }
+
+    /// Builds a `dim`, `color_temp` or `color` command from its value argument.
+    fn parse_light_command(
+        action: &str,
+        value: &str,
+        transition: Option<Duration>,
+    ) -> Result<Command, PulseHomeError> {
+        let invalid = || PulseHomeError::InvalidArgument(format!("invalid value '{}'", value));
+        match action {
+            "dim" => Ok(Command::SetBrightness {
+                level: value.trim_end_matches('%').parse().map_err(|_| invalid())?,
+                transition,
+            }),
+            "color_temp" => Ok(Command::SetColorTemp {
+                kelvin: value
+                    .trim_end_matches(['K', 'k'])
+                    .parse()
+                    .map_err(|_| invalid())?,
+                transition,
+            }),
+            _ => Ok(Command::SetColor {
+                color: value.parse::<Color>()?,
+                transition,
+            }),
+        }
+    }
+
+    /// Renders a device's capabilities as per-device help.
+    fn format_capabilities(name: &str, device_type: &str, capabilities: &Capabilities) -> String {
+        let mut lines = vec![format!("{} ({})", name, device_type)];
+        if !capabilities.commands.is_empty() {
+            lines.push("Commands:".to_string());
+            for spec in &capabilities.commands {
+                let usage = std::iter::once(format!("{} {}", spec.name, name))
+                    .chain(spec.args.iter().map(|arg| arg.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                lines.push(format!("  {} - {}", usage, spec.description));
+            }
+        }
+        if !capabilities.readings.is_empty() {
+            lines.push(format!(
+                "Readings (report {} <reading> <value>): {}",
+                name,
+                capabilities.readings.join(", ")
+            ));
+        }
+        if !capabilities.attributes.is_empty() {
+            lines.push("Attributes:".to_string());
+            for attribute in &capabilities.attributes {
+                lines.push(format!("  {} ({})", attribute.name, attribute.kind));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Renders device snapshots as an aligned table with ID, name, type and state columns.
+    fn format_status_table(snapshots: &[DeviceSnapshot]) -> String {
+        let rows: Vec<[String; 4]> = snapshots
+            .iter()
+            .map(|s| {
+                [
+                    s.id.to_string(),
+                    s.name.clone(),
+                    s.device_type.clone(),
+                    s.state.to_string(),
+                ]
+            })
+            .collect();
+        Self::format_table(["ID", "Name", "Type", "State"], &rows)
+    }
+
+    /// Renders an energy report as a table of power draw and energy used per
+    /// device, heaviest consumer first, followed by a whole-home total.
+    fn format_energy_report(report: &EnergyReport) -> String {
+        let power = |w: f64| AttributeValue::Measure(w, Unit::Watt).to_string();
+        let energy = |kwh: f64| AttributeValue::Measure(kwh, Unit::KilowattHour).to_string();
+        let mut rows: Vec<[String; 4]> = report
+            .devices
+            .iter()
+            .map(|d| {
+                [
+                    d.id.to_string(),
+                    d.name.clone(),
+                    power(d.power_w),
+                    energy(d.energy_kwh),
+                ]
+            })
+            .collect();
+        rows.push([
+            String::new(),
+            "Total".to_string(),
+            power(report.total_power_w()),
+            energy(report.total_kwh()),
+        ]);
+
+        let period = match report.window {
+            Some(window) => format!("in the last {} min", window.as_secs() / 60),
+            None => "since start".to_string(),
+        };
+        format!(
+            "Energy use {}:\n{}",
+            period,
+            Self::format_table(["ID", "Name", "Power", "Energy"], &rows)
+        )
+    }
+
+    /// Renders rows as an aligned table with a header and separator line.
+    fn format_table<const N: usize>(headers: [&str; N], rows: &[[String; N]]) -> String {
+        let mut widths = headers.map(|h| h.chars().count());
+        for row in rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let format_row = |cells: [&str; N]| {
+            cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![format_row(headers)];
+        lines.push(
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("-+-"),
+        );
+        for row in rows {
+            lines.push(format_row(row.each_ref().map(String::as_str)));
+        }
+        lines.join("\n")
+    }
+
+    /// Displays a message to the user.
+    ///
+    /// # Arguments
+    /// * `msg` - The message string to display.
+    fn display_message(&self, msg: &str) {
+        println!("{}", msg);
+    }
+
+    /// Displays an error together with a hint on how to resolve it.
+    ///
+    /// # Arguments
+    /// * `err` - The error returned by the `HomeHub`.
+    fn display_error(&self, err: &PulseHomeError) {
+        eprintln!("Error: {}", err);
+        eprintln!("Hint: {}", Self::error_hint(err));
+    }
+
+    /// Returns a user-facing hint for the given error.
+    fn error_hint(err: &PulseHomeError) -> &'static str {
+        match err {
+            PulseHomeError::DeviceNotFound(_) => "use `list` to see registered devices",
+            PulseHomeError::UnsupportedCommand { .. } => {
+                "type `help <device_name>` to see which commands the device accepts"
+            }
+            PulseHomeError::InvalidArgument(_) => "check the value and try again",
+            PulseHomeError::DuplicateDevice(_) => "choose a different device name",
+            PulseHomeError::DeviceOffline(_) => "check that the device is powered and connected",
+            PulseHomeError::AccessDenied { .. } => {
+                "unlock with `pin <code>` or `as <user>` using an enrolled code or authorized user"
+            }
+            PulseHomeError::InvalidTransition { .. } => {
+                "check the device's current state with `status <device_name>`"
+            }
+            PulseHomeError::DeviceFault { .. } => {
+                "fix the device, then clear the fault (e.g. `clear_jam <lock_name>`)"
+            }
+            PulseHomeError::UnknownDeviceType(_) => "type `help` to see the available device types",
+            PulseHomeError::Config { .. } => "fix the line in the config file and load it again",
+            PulseHomeError::RoomNotFound(_) | PulseHomeError::FloorNotFound(_) => {
+                "use `room list` to see the rooms on each floor"
+            }
+            PulseHomeError::DuplicateRoom(_) => "choose a different room name",
+            PulseHomeError::GroupNotFound(_) => "use `group list` to see the defined groups",
+            PulseHomeError::DuplicateGroup(_) => "choose a different group name",
+            PulseHomeError::SceneNotFound(_) => "use `scene list` to see the saved scenes",
+            PulseHomeError::RuleNotFound(_) => "use `rule list` to see the defined rules",
+            PulseHomeError::DuplicateRule(_) => "choose a different rule name",
+            PulseHomeError::ScheduleNotFound(_) => "use `schedule list` to see the scheduled jobs",
+            PulseHomeError::DuplicateSchedule(_) => "choose a different schedule name",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::{
+        door_lock::DoorLock,
+        light::{Light, LightKind},
+        sensor::Sensor,
+        thermostat::Thermostat,
+    };
+    use crate::models::state::{AttributeValue, Unit};
+
+    #[test]
+    fn test_add_command() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+
+        // Add devices
+        cli.parse_command("add light Living Room Light");
+        cli.parse_command("add thermostat Bedroom Thermostat target=24");
+        cli.parse_command("add doorlock Front Door");
+
+        let devices = cli.hub.list_devices();
+        assert!(devices.contains(&"Living Room Light".to_string()));
+        assert!(devices.contains(&"Front Door".to_string()));
+        assert_eq!(
+            cli.hub
+                .device_state("Bedroom Thermostat")
+                .unwrap()
+                .state
+                .to_string(),
+            "heat (heat_setpoint=24°C, cool_setpoint=24°C, action=idle)"
+        );
+
+        // Unknown types and options are reported without adding anything
+        cli.parse_command("add toaster Kitchen Toaster");
+        cli.parse_command("add light Hall Light zones=2");
+        assert_eq!(cli.hub.list_devices().len(), 3);
+    }
+
+    #[test]
+    fn test_load_command() {
+        let path = std::env::temp_dir().join(format!("pulsehome-load-{}.conf", std::process::id()));
+        std::fs::write(
+            &path,
+            "# Ground floor\ndimmer Living Room Lamp\nsprinklers Front Lawn zones=4\n",
+        )
+        .unwrap();
+        let mut cli = CLI::new(HomeHub::new());
+        cli.parse_command(&format!("load {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            cli.hub.list_devices(),
+            vec!["Living Room Lamp".to_string(), "Front Lawn".to_string()]
+        );
+
+        cli.parse_command("load");
+        cli.parse_command(&format!("load {}", path.display()));
+        assert_eq!(cli.hub.list_devices().len(), 2);
+    }
+
+    #[test]
+    fn test_add_duplicate_device() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("add light Porch Light");
+        cli.parse_command("add light Porch Light");
+        assert_eq!(cli.hub.list_devices(), vec!["Porch Light".to_string()]);
+    }
+
+    #[test]
+    fn test_remove_rename_replace_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("add light Light 1");
+        cli.parse_command("add doorlock Front Door");
+
+        cli.parse_command("rename Light 1 to Kitchen Pendant");
+        assert_eq!(
+            cli.hub.list_devices(),
+            vec!["Kitchen Pendant".to_string(), "Front Door".to_string()]
+        );
+
+        let id = cli.hub.device_id("Front Door");
+        cli.parse_command("replace doorlock Front Door");
+        assert_eq!(cli.hub.device_id("Front Door"), id);
+
+        cli.parse_command("remove Kitchen Pendant");
+        assert_eq!(cli.hub.list_devices(), vec!["Front Door".to_string()]);
+
+        // Missing names and separators should print usage but not panic
+        cli.parse_command("remove");
+        cli.parse_command("rename Front Door");
+        cli.parse_command("remove Nonexistent");
+    }
+
+    #[test]
+    fn test_list_command() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Living Room Light")))
+            .unwrap();
+        let mut cli = CLI::new(hub);
+
+        // Just call list and ensure no panic
+        cli.parse_command("list");
+    }
+
+    #[test]
+    fn test_light_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add color_light Living Room Lamp");
+
+        cli.parse_command("dim Living Room Lamp 40");
+        cli.parse_command("color Living Room Lamp #ff8800");
+        assert_eq!(
+            cli.hub
+                .device_state("Living Room Lamp")
+                .unwrap()
+                .state
+                .to_string(),
+            "on (brightness=40%, color=#ff8800)"
+        );
+
+        cli.parse_command("color_temp Living Room Lamp 2700K over 10");
+        cli.hub.tick(Duration::from_secs(10));
+        assert_eq!(
+            cli.hub
+                .device_state("Living Room Lamp")
+                .unwrap()
+                .state
+                .to_string(),
+            "on (brightness=40%, color_temp=2700 K)"
+        );
+
+        // Invalid values should print an error but not panic
+        cli.parse_command("dim Living Room Lamp bright");
+        cli.parse_command("color Living Room Lamp orange");
+        cli.parse_command("dim Living Room Lamp 40 over soon");
+        cli.parse_command("dim Living Room Lamp");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(
+            CLI::parse_duration("90").unwrap(),
+            chrono::Duration::minutes(90)
+        );
+        assert_eq!(
+            CLI::parse_duration("2h").unwrap(),
+            chrono::Duration::hours(2)
+        );
+        assert_eq!(
+            CLI::parse_duration("7d").unwrap(),
+            chrono::Duration::days(7)
+        );
+
+        // A PIN must not expire before it is enrolled
+        for duration in ["0m", "-5m", "soon", &format!("{}d", i64::MAX)] {
+            assert!(CLI::parse_duration(duration).is_err(), "{}", duration);
+        }
+    }
+
+    #[test]
+    fn test_split_transition() {
+        let args = ["Lamp", "40", "over", "5s"];
+        let (rest, transition) = CLI::split_transition(&args).unwrap();
+        assert_eq!(rest, ["Lamp", "40"]);
+        assert_eq!(transition, Some(Duration::from_secs(5)));
+
+        let args = ["Lamp", "40"];
+        let (rest, transition) = CLI::split_transition(&args).unwrap();
+        assert_eq!(rest, ["Lamp", "40"]);
+        assert_eq!(transition, None);
+
+        // Negative, non-finite and overflowing durations are rejected, not panicked on
+        for secs in ["-1", "inf", "NaN", "1e30"] {
+            assert!(
+                matches!(
+                    CLI::split_transition(&["Lamp", "40", "over", secs]),
+                    Err(PulseHomeError::InvalidArgument(_))
+                ),
+                "{}",
+                secs
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_window() {
+        let args = ["Heater", "2000", "over", "30"];
+        let (rest, window) = CLI::split_window(&args).unwrap();
+        assert_eq!(rest, ["Heater", "2000"]);
+        assert_eq!(window, Some(Duration::from_secs(30 * 60)));
+
+        // A trailing number is part of the device name
+        let args = ["Heater", "2000"];
+        let (rest, window) = CLI::split_window(&args).unwrap();
+        assert_eq!(rest, ["Heater", "2000"]);
+        assert_eq!(window, None);
+
+        assert!(CLI::split_window(&["over", "soon"]).is_err());
+        assert!(CLI::split_window(&["over", &u64::MAX.to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_status_command() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("status");
+        cli.parse_command("status Porch Light");
+        cli.parse_command("status Garage");
+    }
+
+    #[test]
+    fn test_format_status_table() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+
+        let table = CLI::format_status_table(&hub.snapshot());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "ID | Name        | Type       | State");
+        assert_eq!(
+            lines[1],
+            "---+-------------+------------+-----------------------------------------------------------"
+        );
+        assert_eq!(lines[2], "#1 | Porch Light | Light      | off");
+        assert_eq!(
+            lines[3],
+            "#2 | Hall        | Thermostat | heat (heat_setpoint=21°C, cool_setpoint=21°C, action=idle)"
+        );
+    }
+
+    #[test]
+    fn test_turn_on_off_commands() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Living Room Light")))
+            .unwrap();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("turn_on Living Room Light");
+        assert_eq!(
+            cli.hub
+                .device_state("Living Room Light")
+                .unwrap()
+                .state
+                .to_string(),
+            "on"
+        );
+        cli.parse_command("turn_off Living Room Light");
+        assert_eq!(
+            cli.hub
+                .device_state("Living Room Light")
+                .unwrap()
+                .state
+                .to_string(),
+            "off"
+        );
+    }
+
+    #[test]
+    fn test_set_temp_command() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Thermostat::new(
+            "Bedroom Thermostat",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        let mut cli = CLI::new(hub);
+
+        // Valid and out-of-range values should both be handled without panicking
+        cli.parse_command("set_temp Bedroom Thermostat 24");
+        cli.parse_command("set_temp Bedroom Thermostat 90");
+        cli.parse_command("set_temp Bedroom Thermostat hot");
+        let state = cli.hub.device_state("Bedroom Thermostat").unwrap();
+        assert_eq!(
+            state.state.get("heat_setpoint"),
+            Some(&AttributeValue::Measure(24.0, Unit::Celsius))
+        );
+    }
+
+    #[test]
+    fn test_unit_command() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("set_temp Hall 72F");
+        cli.parse_command("unit F");
+        assert_eq!(cli.unit, TemperatureUnit::Fahrenheit);
+        cli.parse_command("ambient Hall 68");
+        cli.parse_command("unit kelvin");
+        assert_eq!(cli.unit, TemperatureUnit::Fahrenheit);
+
+        let state = cli.hub.device_state("Hall").unwrap().state;
+        let setpoint = Temperature::from_attribute(state.get("heat_setpoint").unwrap()).unwrap();
+        assert!((setpoint.fahrenheit() - 72.0).abs() < 1e-9);
+        assert_eq!(
+            state.get("ambient_temperature"),
+            Some(&AttributeValue::Measure(20.0, Unit::Celsius))
+        );
+        cli.parse_command("status Hall");
+    }
+
+    #[test]
+    fn test_thermostat_mode_commands() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        let mut cli = CLI::new(hub);
+
+        cli.parse_command("mode Hall auto");
+        cli.parse_command("setpoints Hall 19 24");
+        cli.parse_command("ambient Hall 26");
+        assert_eq!(
+            cli.hub.device_state("Hall").unwrap().state.to_string(),
+            "auto (heat_setpoint=19°C, cool_setpoint=24°C, ambient_temperature=26°C, action=cooling)"
+        );
+
+        // Invalid values should print an error but not panic
+        cli.parse_command("mode Hall dry");
+        cli.parse_command("setpoints Hall 24");
+        cli.parse_command("setpoints Hall 25 19");
+    }
+
+    #[test]
+    fn test_lock_access_commands() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(DoorLock::new("Front Door")))
+            .unwrap();
+        let mut cli = CLI::new(hub);
+        let lock_state = |cli: &CLI| {
+            cli.hub
+                .device_state("Front Door")
+                .unwrap()
+                .state
+                .get("lock")
+                .cloned()
+        };
+
+        cli.parse_command("add_pin Front Door guest 1234 for 2h");
+        cli.parse_command("authorize Front Door alice");
+        cli.parse_command("lock Front Door");
+        cli.parse_command("unlock Front Door");
+        assert_eq!(
+            lock_state(&cli),
+            Some(AttributeValue::Enum("locked".into()))
+        );
+        cli.parse_command("unlock Front Door pin 0000");
+        assert_eq!(
+            lock_state(&cli),
+            Some(AttributeValue::Enum("locked".into()))
+        );
+        cli.parse_command("unlock Front Door pin 1234");
+        assert_eq!(
+            lock_state(&cli),
+            Some(AttributeValue::Enum("unlocked".into()))
+        );
+
+        cli.parse_command("lock Front Door");
+        cli.parse_command("unlock Front Door as alice");
+        assert_eq!(
+            lock_state(&cli),
+            Some(AttributeValue::Enum("unlocked".into()))
+        );
+
+        cli.parse_command("auto_relock Front Door 30");
+        cli.parse_command("jam Front Door");
+        assert_eq!(
+            lock_state(&cli),
+            Some(AttributeValue::Enum("jammed".into()))
+        );
+        cli.parse_command("clear_jam Front Door");
+        cli.parse_command("revoke_pin Front Door guest");
+        cli.parse_command("deauthorize Front Door alice");
+        let state = cli.hub.device_state("Front Door").unwrap().state;
+        assert_eq!(state.to_string(), "unlocked (auto_relock=30 s)");
+    }
+
+    #[test]
+    fn test_split_credential_and_expiry() {
+        let args = ["Front", "Door", "pin", "1234"];
+        let (name, credential) = CLI::split_credential(&args);
+        assert_eq!(name, ["Front", "Door"]);
+        assert_eq!(credential, Some(Credential::Pin("1234".into())));
+
+        let args = ["Front", "Door", "as", "bob"];
+        assert_eq!(
+            CLI::split_credential(&args).1,
+            Some(Credential::User("bob".into()))
+        );
+
+        let args = ["Door", "guest", "1234", "for", "1d"];
+        let (rest, expires) = CLI::split_expiry(&args).unwrap();
+        assert_eq!(rest, ["Door", "guest", "1234"]);
+        let remaining = expires.unwrap() - Local::now();
+        assert!(remaining > chrono::Duration::hours(23));
+        assert!(CLI::split_expiry(&["Door", "for", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_report_command() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add climate_sensor Bathroom Sensor");
+        cli.parse_command("add motion_sensor Hall Motion");
+
+        cli.parse_command("unit F");
+        cli.parse_command("report Bathroom Sensor temperature 68");
+        cli.parse_command("report Bathroom Sensor humidity 55%");
+        cli.parse_command("report Bathroom Sensor humidity soggy");
+        cli.parse_command("report Hall Motion motion on");
+        cli.parse_command("report Hall Motion contact open");
+
+        let state = cli.hub.device_state("Bathroom Sensor").unwrap().state;
+        assert_eq!(state.to_string(), "20°C (humidity=55%)");
+        let state = cli.hub.device_state("Hall Motion").unwrap().state;
+        assert_eq!(state.to_string(), "detected");
+    }
+
+    #[test]
+    fn test_parse_reading() {
+        let unit = TemperatureUnit::Celsius;
+        assert_eq!(
+            CLI::parse_reading("contact", "closed", unit).unwrap(),
+            SensorReading::Contact { open: false }
+        );
+        assert_eq!(
+            CLI::parse_reading("lux", "300lx", unit).unwrap(),
+            SensorReading::Illuminance(300.0)
+        );
+        assert!(CLI::parse_reading("motion", "maybe", unit).is_err());
+        assert!(CLI::parse_reading("pressure", "1013", unit).is_err());
+    }
+
+    #[test]
+    fn test_energy_command() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("energy");
+        cli.parse_command("add plug Space Heater");
+        cli.parse_command("add switch Lamp");
+        cli.parse_command("report Space Heater power 1500W");
+        cli.parse_command("report Lamp power 60");
+        cli.parse_command("turn_on Space Heater");
+        cli.parse_command("turn_on Lamp");
+        cli.hub.tick(Duration::from_secs(3600));
+
+        cli.parse_command("energy");
+        cli.parse_command("energy over 30");
+        cli.parse_command("energy Space Heater over 30");
+        cli.parse_command("energy over 99999999999999999999");
+        cli.parse_command("energy Nowhere");
+
+        let report = cli.hub.energy_report(None);
+        let table = CLI::format_energy_report(&report);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Energy use since start:");
+        assert_eq!(lines[1], "ID | Name         | Power  | Energy");
+        assert_eq!(lines[3], "#1 | Space Heater | 1500 W | 1.5 kWh");
+        assert_eq!(lines[4], "#2 | Lamp         | 60 W   | 0.06 kWh");
+        assert_eq!(lines[5], "   | Total        | 1560 W | 1.56 kWh");
+    }
+
+    #[test]
+    fn test_covering_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add blinds Office Blinds");
+        cli.parse_command("position Office Blinds 40%");
+        cli.parse_command("tilt Office Blinds -30");
+        cli.parse_command("tilt Office Blinds sideways");
+        cli.hub.tick(Duration::from_secs(60));
+        let state = cli.hub.device_state("Office Blinds").unwrap().state;
+        assert_eq!(state.to_string(), "open (position=40%, tilt=-30°)");
+
+        cli.parse_command("close Office Blinds");
+        cli.hub.tick(Duration::from_secs(2));
+        cli.parse_command("stop Office Blinds");
+        let state = cli.hub.device_state("Office Blinds").unwrap().state;
+        assert_eq!(state.to_string(), "open (position=30%, tilt=-30°)");
+    }
+
+    #[test]
+    fn test_garage_door_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add garage_door Garage");
+        cli.parse_command("close Garage");
+        cli.parse_command("open Garage");
+        cli.hub.tick(Duration::from_secs(6));
+        let state = cli.hub.device_state("Garage").unwrap().state;
+        assert_eq!(state.to_string(), "opening (position=50%)");
+
+        cli.hub.tick(Duration::from_secs(6));
+        cli.parse_command("close Garage");
+        cli.hub.tick(Duration::from_secs(3));
+        cli.parse_command("report Garage obstruction on");
+        let state = cli.hub.device_state("Garage").unwrap().state;
+        assert_eq!(state.to_string(), "opening (position=75%, obstruction=on)");
+    }
+
+    #[test]
+    fn test_fan_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add ceiling_fan Living Room Fan");
+        cli.parse_command("fan_speed Living Room Fan medium");
+        cli.parse_command("fan_direction Living Room Fan reverse");
+        cli.parse_command("oscillate Living Room Fan on");
+        cli.parse_command("fan_speed Living Room Fan gale");
+        let state = cli.hub.device_state("Living Room Fan").unwrap().state;
+        assert_eq!(
+            state.to_string(),
+            "on (speed=66%, preset=medium, direction=reverse)"
+        );
+
+        cli.parse_command("add fan Desk Fan");
+        cli.parse_command("fan_speed Desk Fan 40%");
+        cli.parse_command("oscillate Desk Fan on");
+        let state = cli.hub.device_state("Desk Fan").unwrap().state;
+        assert_eq!(
+            state.to_string(),
+            "on (speed=40%, preset=medium, oscillating=on)"
+        );
+    }
+
+    #[test]
+    fn test_irrigation_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add irrigation Front Lawn zones=4");
+        cli.parse_command("run_zone Front Lawn 2 10");
+        cli.parse_command("run_zone Front Lawn 3 5m");
+        cli.parse_command("run_zone Front Lawn 9 5");
+        let state = cli.hub.device_state("Front Lawn").unwrap().state;
+        assert_eq!(
+            state.to_string(),
+            "watering (zones=4, zone=2, remaining=10 min, queued=1)"
+        );
+
+        cli.parse_command("rain_delay Front Lawn 24h");
+        let state = cli.hub.device_state("Front Lawn").unwrap().state;
+        assert_eq!(
+            state.to_string(),
+            "rain_delay (zones=4, queued=2, rain_delay=24 h)"
+        );
+        cli.parse_command("rain_delay Front Lawn off");
+        cli.parse_command("stop Front Lawn");
+        let state = cli.hub.device_state("Front Lawn").unwrap().state;
+        assert_eq!(state.to_string(), "idle (zones=4, queued=0)");
+
+        // Any unit can be given explicitly
+        assert_eq!(
+            CLI::parse_irrigation_command("run_zone", &["1", "90s"]).unwrap(),
+            Command::RunZone {
+                zone: 1,
+                duration: Duration::from_secs(90)
+            }
+        );
+        assert_eq!(
+            CLI::parse_irrigation_command("rain_delay", &["30m"]).unwrap(),
+            Command::SetRainDelay(Some(Duration::from_secs(1800)))
+        );
+        cli.parse_command("run_zone Front Lawn 1 1h");
+        cli.parse_command("stop Front Lawn");
+        let state = cli.hub.device_state("Front Lawn").unwrap().state;
+        assert_eq!(state.to_string(), "idle (zones=4, queued=0)");
+
+        // Durations too long to represent are rejected, not wrapped
+        for values in [["1", "999999999999999999"], ["1", "999999999999999999m"]] {
+            assert!(matches!(
+                CLI::parse_irrigation_command("run_zone", &values),
+                Err(PulseHomeError::InvalidArgument(_))
+            ));
+        }
+        assert!(matches!(
+            CLI::parse_irrigation_command("rain_delay", &["9999999999999999h"]),
+            Err(PulseHomeError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_media_player_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add speaker Den Speaker");
+        cli.parse_command("queue Den Speaker track Stand by Me by Ben E. King");
+        cli.parse_command("queue Den Speaker track Blue in Green");
+        cli.parse_command("play Den Speaker");
+        cli.parse_command("next Den Speaker");
+        cli.parse_command("volume Den Speaker 45%");
+        cli.parse_command("volume Den Speaker loud");
+        cli.parse_command("source Den Speaker to Line In");
+        cli.parse_command("mute Den Speaker");
+        let state = cli.hub.device_state("Den Speaker").unwrap().state;
+        assert_eq!(
+            state.to_string(),
+            "playing (volume=45%, muted=on, source=Line In, track=Blue in Green)"
+        );
+
+        cli.parse_command("previous Den Speaker");
+        cli.parse_command("pause Den Speaker");
+        let state = cli.hub.device_state("Den Speaker").unwrap().state;
+        assert_eq!(
+            state.get("track"),
+            Some(&AttributeValue::Text("Stand by Me – Ben E. King".into()))
+        );
+        assert_eq!(
+            state.get("playback"),
+            Some(&AttributeValue::Enum("paused".into()))
+        );
+    }
+
+    #[test]
+    fn test_alarm_commands() {
+        let hub = HomeHub::new();
+        let mut cli = CLI::new(hub);
+        cli.parse_command("add smoke_alarm Hall Smoke");
+        cli.parse_command("test Hall Smoke");
+        cli.parse_command("report Hall Smoke smoke alarm");
+        cli.parse_command("silence Hall Smoke");
+        let state = cli.hub.device_state("Hall Smoke").unwrap().state;
+        assert_eq!(state.to_string(), "alarm (silenced=on)");
+
+        cli.parse_command("add co_alarm Garage CO");
+        cli.parse_command("report Garage CO co smoky");
+        cli.parse_command("report Garage CO co warning");
+        let state = cli.hub.device_state("Garage CO").unwrap().state;
+        assert_eq!(state.to_string(), "warning");
+    }
+
+    #[test]
+    fn test_help_for_device() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::with_kind("Desk Lamp", LightKind::Dimmable)))
+            .unwrap();
+        hub.register_device(Box::new(Sensor::motion("Hall Motion")))
+            .unwrap();
+        let capabilities = hub.capabilities("Desk Lamp").unwrap();
+        assert_eq!(
+            CLI::format_capabilities("Desk Lamp", "Light", &capabilities),
+            "Desk Lamp (Light)\n\
+             Commands:\n  \
+             turn_on Desk Lamp - Turn the light on\n  \
+             turn_off Desk Lamp - Turn the light off\n  \
+             dim Desk Lamp <level:0-100%> [over <secs:s>] - Set the brightness\n\
+             Attributes:\n  \
+             power (on|off)\n  \
+             brightness (%)\n  \
+             transition (s)"
+        );
+        let capabilities = hub.capabilities("Hall Motion").unwrap();
+        assert!(
+            CLI::format_capabilities("Hall Motion", "MotionSensor", &capabilities)
+                .contains("Readings (report Hall Motion <reading> <value>): motion")
+        );
+
+        let mut cli = CLI::new(hub);
+        cli.parse_command("help Desk Lamp");
+        cli.parse_command("help Garage");
+        cli.parse_command("help");
+    }
+
+    #[test]
+    fn test_room_commands() {
+        let mut cli = CLI::new(HomeHub::new());
+        cli.parse_command("add light Bedside Lamp");
+        cli.parse_command("add dimmer Ceiling Light");
+        cli.parse_command("add thermostat Bedroom Thermostat");
+        cli.parse_command("add plug Kettle");
+        cli.parse_command("room add Master Bedroom on Upstairs");
+        cli.parse_command("room add Garage");
+        cli.parse_command("assign Bedside Lamp to Master Bedroom");
+        cli.parse_command("assign Ceiling Light to Master Bedroom");
+        cli.parse_command("assign Bedroom Thermostat to Master Bedroom");
+        assert_eq!(
+            cli.format_topology(),
+            "Upstairs\n  \
+             Master Bedroom: Bedside Lamp, Ceiling Light, Bedroom Thermostat\n\
+             No floor\n  \
+             Garage: (empty)\n\
+             Unassigned: Kettle"
+        );
+
+        cli.parse_command("turn_on room:Master Bedroom");
+        cli.parse_command("dim room:Master Bedroom 40");
+        cli.parse_command("set_temp room:Master Bedroom 19");
+        let state = |cli: &CLI, name: &str| cli.hub.device_state(name).unwrap().state.to_string();
+        assert_eq!(state(&cli, "Bedside Lamp"), "on");
+        assert_eq!(state(&cli, "Ceiling Light"), "on (brightness=40%)");
+        assert!(state(&cli, "Bedroom Thermostat").starts_with("heat (heat_setpoint=19°C"));
+        assert_eq!(
+            state(&cli, "Kettle"),
+            "off (power_usage=0 W, energy=0 kWh, voltage=230 V)"
+        );
+
+        cli.parse_command("status room:Master Bedroom");
+        cli.parse_command("unassign Ceiling Light");
+        assert_eq!(cli.hub.room_of("Ceiling Light"), None);
+        cli.parse_command("room remove Master Bedroom");
+        assert!(cli.hub.topology().rooms().len() == 1);
+
+        // Unknown rooms and malformed commands should print errors but not panic
+        cli.parse_command("turn_off room:Attic");
+        cli.parse_command("status room:Attic");
+        cli.parse_command("assign Kettle to Attic");
+        cli.parse_command("assign Kettle");
+        cli.parse_command("room");
+        cli.parse_command("room rename Garage");
+    }
+
+    #[test]
+    fn test_group_commands() {
+        let mut cli = CLI::new(HomeHub::new());
+        cli.parse_command("add doorlock Front Door");
+        cli.parse_command("add doorlock Back Door");
+        cli.parse_command("add light Porch Light");
+        cli.parse_command("group create All Locks with Front Door, Back Door");
+        assert_eq!(cli.format_groups(), "All Locks: Front Door, Back Door");
+
+        cli.parse_command("lock group:All Locks");
+        let lock = |cli: &CLI| {
+            cli.hub
+                .group_state("All Locks")
+                .unwrap()
+                .get("lock")
+                .cloned()
+        };
+        assert_eq!(lock(&cli), Some(AttributeValue::Enum("all locked".into())));
+        cli.parse_command("unlock Back Door");
+        assert_eq!(
+            lock(&cli),
+            Some(AttributeValue::Enum("any unlocked".into()))
+        );
+        cli.parse_command("status group:All Locks");
+
+        // The light cannot lock, but the doors are still locked
+        cli.parse_command("group add Porch Light to All Locks");
+        cli.parse_command("lock group:All Locks");
+        assert_eq!(lock(&cli), None);
+        assert_eq!(
+            cli.hub.device_state("Back Door").unwrap().state.get("lock"),
+            Some(&AttributeValue::Enum("locked".into()))
+        );
+
+        cli.parse_command("group remove Porch Light from All Locks");
+        cli.parse_command("group create Empty");
+        cli.parse_command("turn_on group:Empty");
+        assert_eq!(
+            cli.format_groups(),
+            "All Locks: Front Door, Back Door\nEmpty: (empty)"
+        );
+        cli.parse_command("group delete Empty");
+        assert_eq!(cli.hub.groups(), vec!["All Locks".to_string()]);
+
+        // Unknown groups and malformed commands should print errors but not panic
+        cli.parse_command("turn_on group:Attic");
+        cli.parse_command("status group:Attic");
+        cli.parse_command("group create All Locks with Front Door");
+        cli.parse_command("group add Porch Light");
+        cli.parse_command("group");
+    }
+
+    #[test]
+    fn test_scene_commands_persist() {
+        let path =
+            std::env::temp_dir().join(format!("pulsehome-scenes-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut cli = CLI::new(HomeHub::new()).with_scene_file(&path);
+        cli.parse_command("add dimmer Sofa Lamp");
+        cli.parse_command("add dimmer Hall Light");
+        cli.parse_command("add thermostat Thermostat target=21");
+        cli.parse_command("group create Lounge with Sofa Lamp");
+        cli.parse_command("dim Sofa Lamp 20");
+        cli.parse_command("turn_on Sofa Lamp");
+        cli.parse_command("scene save Movie Night with group:Lounge, Thermostat");
+        assert_eq!(
+            cli.format_scenes(),
+            "Movie Night\n  \
+             Sofa Lamp: on (brightness=20%)\n  \
+             Thermostat: heat (heat_setpoint=21°C, cool_setpoint=21°C)"
+        );
+
+        // A new session reads the scene back and can activate it
+        let mut cli = CLI::new(HomeHub::new()).with_scene_file(&path);
+        cli.parse_command("add dimmer Sofa Lamp");
+        cli.parse_command("add thermostat Thermostat");
+        cli.parse_command("scene activate Movie Night");
+        assert_eq!(
+            cli.hub.device_state("Sofa Lamp").unwrap().state.to_string(),
+            "on (brightness=20%)"
+        );
+        cli.parse_command("scene delete Movie Night");
+        assert_eq!(cli.format_scenes(), "No scenes defined.");
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!saved.contains("Movie Night"));
+
+        // Unknown scenes and malformed commands should print errors but not panic
+        cli.parse_command("scene activate Party");
+        cli.parse_command("scene save Party with Porch Light");
+        cli.parse_command("scene save Party with room:Attic");
+        cli.parse_command("scene");
+    }
+
+    #[test]
+    fn test_rule_commands() {
+        let mut cli = CLI::new(HomeHub::new());
+        cli.parse_command("add motion_sensor Hall Motion");
+        cli.parse_command("add dimmer Hall Light");
+        cli.parse_command(
+            "rule add Hall Lights: when Hall Motion motion becomes detected; \
+             if mode is home; then dim Hall Light 40; then notify welcome home",
+        );
+        assert_eq!(
+            cli.format_rules(),
+            "Hall Lights [enabled]: when Hall Motion motion becomes detected; \
+             if mode is home; then dim Hall Light 40; then notify welcome home"
+        );
+
+        cli.parse_command("report Hall Motion motion detected");
+        assert_eq!(
+            cli.hub
+                .device_state("Hall Light")
+                .unwrap()
+                .state
+                .to_string(),
+            "on (brightness=40%)"
+        );
+
+        cli.parse_command("home_mode away");
+        assert_eq!(cli.hub.home_mode(), "away");
+        cli.parse_command("rule test Hall Lights");
+        assert!(!cli.hub.test_rule("Hall Lights").unwrap().passes());
+
+        cli.parse_command("rule disable Hall Lights");
+        assert!(cli.format_rules().starts_with("Hall Lights [disabled]"));
+        cli.parse_command("rule enable Hall Lights");
+        assert!(cli.hub.rule("Hall Lights").unwrap().enabled);
+        cli.parse_command("rule remove Hall Lights");
+        assert_eq!(cli.format_rules(), "No rules defined.");
+
+        // Malformed rules and unknown names should print errors but not panic
+        cli.parse_command("rule add Broken: when Hall Motion motion becomes detected");
+        cli.parse_command("rule add Broken: if mode is home; then turn_on Hall Light");
+        cli.parse_command("rule add Broken: when at noon; then turn_on Hall Light");
+        cli.parse_command("rule add Broken: when at 07:00; then rename Hall Light");
+        cli.parse_command("rule add Broken: when at 07:00; then turn_on room:Hall");
+        cli.parse_command("rule test Missing");
+        cli.parse_command("rule");
+        assert!(cli.hub.rules().is_empty());
+    }
+
+    #[test]
+    fn test_parse_action_command() {
+        let cli = CLI::new(HomeHub::new());
+        let parse = |action: &str| {
+            let words: Vec<&str> = action.split_whitespace().collect();
+            cli.parse_action_command(&words)
+        };
+        assert_eq!(
+            parse("position Bedroom Blinds 40").unwrap(),
+            ("Bedroom Blinds".to_string(), Command::SetPosition(40))
+        );
+        assert_eq!(
+            parse("volume Kitchen Speaker 25%").unwrap(),
+            ("Kitchen Speaker".to_string(), Command::SetVolume(25))
+        );
+        assert_eq!(
+            parse("run_zone Garden 2 15").unwrap(),
+            (
+                "Garden".to_string(),
+                Command::RunZone {
+                    zone: 2,
+                    duration: Duration::from_secs(15 * 60)
+                }
+            )
+        );
+        assert_eq!(
+            parse("rain_delay Garden off").unwrap().1,
+            Command::SetRainDelay(None)
+        );
+        // Listed irrigation arguments parse back to the same command
+        for command in [
+            Command::RunZone {
+                zone: 2,
+                duration: Duration::from_secs(90),
+            },
+            Command::SetRainDelay(Some(Duration::from_secs(1800))),
+        ] {
+            let action = format!("{} Garden {}", command.name(), command.argument().unwrap());
+            assert_eq!(parse(&action).unwrap().1, command);
+        }
+        assert_eq!(
+            parse("unlock Front Door pin 1234").unwrap(),
+            (
+                "Front Door".to_string(),
+                Command::Unlock(Some(Credential::Pin("1234".to_string())))
+            )
+        );
+        assert_eq!(
+            parse("revoke_pin Front Door cleaner").unwrap().1,
+            Command::RevokePin("cleaner".to_string())
+        );
+        assert_eq!(
+            parse("source Living Room TV to hdmi 2").unwrap().1,
+            Command::SetSource("hdmi 2".to_string())
+        );
+
+        assert!(matches!(
+            parse("volume Speaker"),
+            Err(PulseHomeError::InvalidArgument(message)) if message.contains("<0-100>")
+        ));
+        assert!(parse("rename Lamp to Light").is_err());
+        assert!(parse("position Bedroom Blinds high").is_err());
+        assert!(parse("turn_off room:Kitchen").is_err());
+        assert!(parse("lock group:All Locks").is_err());
+    }
+
+    #[test]
+    fn test_schedule_commands() {
+        use crate::clock::ManualClock;
+        use chrono::TimeDelta;
+
+        // Friday noon in London summer time
+        let clock =
+            ManualClock::new(DateTime::parse_from_rfc3339("2024-06-21T12:00:00+01:00").unwrap());
+        let mut cli = CLI::new(HomeHub::new()).with_scheduler(Scheduler::with_clock(clock.clone()));
+        cli.parse_command("add light Porch Light");
+        cli.parse_command("add thermostat Thermostat target=21");
+
+        // Sunset needs a location first
+        cli.parse_command("schedule add Porch: at sunset; turn_on Porch Light");
+        assert_eq!(cli.format_schedules(), "No schedules defined.");
+        cli.parse_command("location 51.5074 -0.1278");
+        cli.parse_command("schedule add Porch: at sunset; turn_on Porch Light");
+        cli.parse_command("schedule add Night Heat: at 23:00 on weekdays; set_temp Thermostat 18");
+        cli.parse_command("schedule add Porch Off: in 14h; turn_off Porch Light");
+        assert_eq!(
+            cli.format_schedules(),
+            "Porch: at sunset; turn_on Porch Light (next: 2024-06-21 21:21)\n\
+             Night Heat: at 23:00 on weekdays; set_temp Thermostat 18°C (next: 2024-06-21 23:00)\n\
+             Porch Off: once 2024-06-22 02:00; turn_off Porch Light (next: 2024-06-22 02:00)"
+        );
+
+        clock.advance(TimeDelta::hours(10));
+        cli.run_schedules();
+        assert_eq!(
+            cli.hub
+                .device_state("Porch Light")
+                .unwrap()
+                .state
+                .to_string(),
+            "on"
+        );
+        clock.advance(TimeDelta::hours(4));
+        cli.run_schedules();
+        assert_eq!(
+            cli.hub
+                .device_state("Porch Light")
+                .unwrap()
+                .state
+                .to_string(),
+            "off"
+        );
+        assert_eq!(cli.scheduler.jobs().len(), 2);
+
+        cli.parse_command("schedule remove Porch");
+        cli.parse_command("remove Thermostat");
+        clock.advance(TimeDelta::days(3));
+        cli.run_schedules();
+
+        // Malformed schedules and unknown names should print errors but not panic
+        cli.parse_command("schedule add Broken: at noon; turn_on Porch Light");
+        cli.parse_command("schedule add Broken: at 07:00 turn_on Porch Light");
+        cli.parse_command("schedule add Broken: once 2020-01-01 00:00; turn_on Porch Light");
+        cli.parse_command("schedule add Night Heat: at 06:00; turn_on Porch Light");
+        cli.parse_command("schedule add Kitchen Off: at 23:00; turn_off room:Kitchen");
+        cli.parse_command("schedule add Lock Up: at 23:00; lock group:All Locks");
+        cli.parse_command("schedule remove Missing");
+        cli.parse_command("location 95 0");
+        cli.parse_command("schedule");
+        assert_eq!(cli.scheduler.jobs().len(), 1);
+    }
+
+    #[test]
+    fn test_error_hints() {
+        let hint = CLI::error_hint(&PulseHomeError::DeviceNotFound("Porch".into()));
+        assert!(hint.contains("list"));
+        let hint = CLI::error_hint(&PulseHomeError::unsupported("Light", &Command::Lock));
+        assert!(hint.contains("help"));
     }
 
     #[test]
@@ -0,0 +1,85 @@
+//! # Clock Module
+//!
+//! Defines the [`Clock`] that the [`HomeHub`](crate::mediator::HomeHub) runs
+//! time-of-day rules by and the [`Scheduler`](crate::scheduler::Scheduler)
+//! runs jobs by. The [`SystemClock`] follows the local time zone; a
+//! [`ManualClock`] only moves when told to, so time-based behavior can be
+//! tested deterministically.
+//!
+//! ## Example
+//! ```
+//! use chrono::{DateTime, TimeDelta};
+//! use pulsehome::clock::{Clock, ManualClock};
+//!
+//! let clock = ManualClock::new(DateTime::parse_from_rfc3339("2024-06-21T12:00:00+01:00").unwrap());
+//! let shared = clock.clone();
+//! clock.advance(TimeDelta::minutes(30));
+//! assert_eq!(shared.now().format("%H:%M").to_string(), "12:30");
+//! ```
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeDelta, TimeZone};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Where the current time is read from.
+pub trait Clock {
+    /// Returns the current local time, with its offset from UTC.
+    fn now(&self) -> DateTime<FixedOffset>;
+
+    /// Returns the offset from UTC of the clock's time zone at a UTC time.
+    ///
+    /// Defaults to the current offset, as for a zone without daylight saving
+    /// time.
+    fn offset_at(&self, utc: NaiveDateTime) -> FixedOffset {
+        let _ = utc;
+        *self.now().offset()
+    }
+}
+
+/// The system clock, in the local time zone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        Local::now().fixed_offset()
+    }
+
+    fn offset_at(&self, utc: NaiveDateTime) -> FixedOffset {
+        Local.offset_from_utc_datetime(&utc).fix()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one and hand the others to a
+/// [`HomeHub`](crate::mediator::HomeHub) and a
+/// [`Scheduler`](crate::scheduler::Scheduler).
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<DateTime<FixedOffset>>>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at `start`.
+    pub fn new(start: DateTime<FixedOffset>) -> Self {
+        Self {
+            now: Rc::new(Cell::new(start)),
+        }
+    }
+
+    /// Sets the clock to the given time.
+    pub fn set(&self, now: DateTime<FixedOffset>) {
+        self.now.set(now);
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, by: TimeDelta) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        self.now.get()
+    }
+}
@@ -1,3 +1,11 @@
 pub mod door_lock;
+pub mod fan;
+pub mod garage_door;
+pub mod irrigation;
 pub mod light;
+pub mod media_player;
+pub mod safety_alarm;
+pub mod sensor;
+pub mod smart_plug;
 pub mod thermostat;
+pub mod window_covering;
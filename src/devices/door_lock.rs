@@ -3,35 +3,196 @@
 //! This module defines the `DoorLock` struct, a concrete implementation of the
 //! [`Device`] trait for smart locks in the PulseHome system.
 //!
-//! The `DoorLock` can be locked or unlocked, and generates an [`Event`] whenever its state changes.
+//! A `DoorLock` keeps its own access list: labelled PIN codes (optionally
+//! expiring) and authorized users. Once any code or user is enrolled, unlocking
+//! requires a matching [`Credential`]; a lock with an empty access list can be
+//! unlocked by anyone. Every event records the code or user that operated the
+//! lock in its `actor`, and rejected attempts raise an
+//! [`EventType::AccessDenied`] event for the audit trail.
+//!
+//! The lock can relock itself a configurable time after being unlocked, and
+//! refuses to move its bolt while jammed.
 
+use crate::error::PulseHomeError;
 use crate::models::{
+    argument::Credential,
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
     device::Device,
     event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
 };
+use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// Actor recorded on events raised by the auto-relock timer.
+pub const AUTO_RELOCK_ACTOR: &str = "auto-relock";
+
+/// A PIN code enrolled on a lock.
+#[derive(Debug, Clone)]
+struct PinCode {
+    pin: String,
+    expires: Option<DateTime<Local>>,
+}
+
+impl PinCode {
+    fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|expires| expires <= Local::now())
+    }
+}
 
 /// Represents a smart door lock device.
 #[derive(Debug)]
 pub struct DoorLock {
     name: String,
     locked: bool, // true = locked, false = unlocked
+    jammed: bool,
+    codes: BTreeMap<String, PinCode>, // keyed by label
+    users: BTreeSet<String>,
+    auto_relock: Option<Duration>,
+    relock_in: Option<Duration>, // time left before auto-relock
+    pending: Vec<Event>,
 }
 
 impl DoorLock {
-    /// Creates a new `DoorLock` with the given name, initially unlocked.
+    /// Creates a new `DoorLock` with the given name, initially unlocked and
+    /// with an empty access list.
     ///
     /// # Example
     /// ```
+    /// use pulsehome::models::device::Device;
     /// use pulsehome::devices::door_lock::DoorLock;
     ///
     /// let lock = DoorLock::new("Front Door");
     /// assert_eq!(lock.get_name(), "Front Door");
-    /// assert_eq!(lock.get_state(), "unlocked");
+    /// assert_eq!(lock.get_state().to_string(), "unlocked");
     /// ```
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             locked: false,
+            jammed: false,
+            codes: BTreeMap::new(),
+            users: BTreeSet::new(),
+            auto_relock: None,
+            relock_in: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the bolt is thrown.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Returns `true` if the lock has reported a jam that has not been cleared.
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    /// Returns `true` if unlocking requires a credential, i.e. at least one
+    /// PIN code or user is enrolled.
+    pub fn requires_credential(&self) -> bool {
+        !self.codes.is_empty() || !self.users.is_empty()
+    }
+
+    /// Checks a credential against the access list.
+    ///
+    /// Returns the actor to record on success, or the reason for rejection.
+    fn authenticate(&self, credential: Option<&Credential>) -> Result<Option<String>, String> {
+        if !self.requires_credential() {
+            return Ok(match credential {
+                Some(Credential::User(user)) => Some(format!("user:{}", user)),
+                _ => None,
+            });
+        }
+        match credential {
+            None => Err("a PIN code or authorized user is required".to_string()),
+            Some(Credential::Pin(pin)) => match self.codes.iter().find(|(_, c)| c.pin == *pin) {
+                Some((label, code)) if code.is_expired() => {
+                    Err(format!("PIN code '{}' has expired", label))
+                }
+                Some((label, _)) => Ok(Some(format!("code:{}", label))),
+                None => Err("invalid PIN code".to_string()),
+            },
+            Some(Credential::User(user)) if self.users.contains(user) => {
+                Ok(Some(format!("user:{}", user)))
+            }
+            Some(Credential::User(user)) => Err(format!("user '{}' is not authorized", user)),
+        }
+    }
+
+    /// Queues an [`EventType::AccessDenied`] event and returns the matching error.
+    fn deny(&mut self, credential: Option<&Credential>, reason: String) -> PulseHomeError {
+        let mut event = self.event(EventType::AccessDenied, None);
+        if let Some(credential) = credential {
+            event = event.with_actor(credential.to_string());
+        }
+        self.pending.push(event);
+        PulseHomeError::AccessDenied {
+            device: self.name.clone(),
+            reason,
+        }
+    }
+
+    /// Fails with [`PulseHomeError::DeviceFault`] if the bolt is jammed.
+    fn ensure_not_jammed(&self) -> Result<(), PulseHomeError> {
+        if self.jammed {
+            return Err(PulseHomeError::DeviceFault {
+                device: self.name.clone(),
+                fault: "bolt is jammed".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn add_pin(
+        &mut self,
+        label: &str,
+        pin: &str,
+        expires: Option<DateTime<Local>>,
+    ) -> Result<(), PulseHomeError> {
+        if label.trim().is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "PIN code label must not be empty".to_string(),
+            ));
+        }
+        if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PulseHomeError::InvalidArgument(
+                "PIN code must be 4 to 8 digits".to_string(),
+            ));
+        }
+        if expires.is_some_and(|expires| expires <= Local::now()) {
+            return Err(PulseHomeError::InvalidArgument(
+                "PIN code expiry must be in the future".to_string(),
+            ));
+        }
+        if self.codes.iter().any(|(l, c)| l != label && c.pin == pin) {
+            return Err(PulseHomeError::InvalidArgument(
+                "PIN code is already in use".to_string(),
+            ));
+        }
+        self.codes.insert(
+            label.to_string(),
+            PinCode {
+                pin: pin.to_string(),
+                expires,
+            },
+        );
+        Ok(())
+    }
+
+    fn event(&self, event_type: EventType, actor: Option<String>) -> Event {
+        let event = Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        );
+        match actor {
+            Some(actor) => event.with_actor(actor),
+            None => event,
         }
     }
 }
@@ -41,64 +202,368 @@ impl Device for DoorLock {
         &self.name
     }
 
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
     fn get_type(&self) -> &str {
         "DoorLock"
     }
 
-    fn execute_command(&mut self, command: EventType) -> Result<Event, Box<dyn std::error::Error>> {
-        match command {
-            EventType::Lock => self.locked = true,
-            EventType::Unlock => self.locked = false,
-            _ => return Err("DoorLock only supports Lock or Unlock commands".into()),
-        }
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        let actor = match &command {
+            Command::Lock => {
+                self.ensure_not_jammed()?;
+                self.locked = true;
+                self.relock_in = None;
+                None
+            }
+            Command::Unlock(credential) => {
+                let actor = self
+                    .authenticate(credential.as_ref())
+                    .map_err(|reason| self.deny(credential.as_ref(), reason))?;
+                self.ensure_not_jammed()?;
+                self.locked = false;
+                self.relock_in = self.auto_relock;
+                actor
+            }
+            Command::AddPin {
+                label,
+                pin,
+                expires,
+            } => {
+                self.add_pin(label, pin, *expires)?;
+                Some(format!("code:{}", label))
+            }
+            Command::RevokePin(label) => {
+                if self.codes.remove(label).is_none() {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "no PIN code labelled '{}'",
+                        label
+                    )));
+                }
+                Some(format!("code:{}", label))
+            }
+            Command::AuthorizeUser(user) => {
+                if user.trim().is_empty() {
+                    return Err(PulseHomeError::InvalidArgument(
+                        "user name must not be empty".to_string(),
+                    ));
+                }
+                self.users.insert(user.clone());
+                Some(format!("user:{}", user))
+            }
+            Command::RevokeUser(user) => {
+                if !self.users.remove(user) {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "user '{}' is not authorized",
+                        user
+                    )));
+                }
+                Some(format!("user:{}", user))
+            }
+            Command::SetAutoRelock(delay) => {
+                if *delay == Some(Duration::ZERO) {
+                    return Err(PulseHomeError::InvalidArgument(
+                        "auto-relock delay must be greater than zero".to_string(),
+                    ));
+                }
+                self.auto_relock = *delay;
+                if !self.locked {
+                    self.relock_in = *delay;
+                }
+                None
+            }
+            Command::ReportJam => {
+                self.jammed = true;
+                self.relock_in = None;
+                None
+            }
+            Command::ClearJam => {
+                self.jammed = false;
+                None
+            }
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        };
 
-        Ok(Event::new(
-            self.name.clone(),
-            self.get_type().to_string(),
-            command,
-            Some(self.get_state()),
-        ))
+        Ok(self.event(command.event_type(), actor))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(CommandSpec::new("lock", "Lock the door"))
+            .command(
+                CommandSpec::new("unlock", "Unlock the door, presenting a credential")
+                    .optional_arg("pin", "code", ArgKind::Text)
+                    .optional_arg("as", "user", ArgKind::Text),
+            )
+            .command(
+                CommandSpec::new("add_pin", "Enroll a 4–8 digit PIN code")
+                    .arg("label", ArgKind::Text)
+                    .arg("code", ArgKind::Text)
+                    .optional_arg("for", "expires", ArgKind::Duration(Unit::Minute)),
+            )
+            .command(
+                CommandSpec::new("revoke_pin", "Revoke a PIN code").arg("label", ArgKind::Text),
+            )
+            .command(
+                CommandSpec::new("authorize", "Let a user unlock without a PIN code")
+                    .arg("user", ArgKind::Text),
+            )
+            .command(
+                CommandSpec::new("deauthorize", "Revoke a user's access")
+                    .arg("user", ArgKind::Text),
+            )
+            .command(
+                CommandSpec::new(
+                    "auto_relock",
+                    "Relock automatically after unlocking (or off)",
+                )
+                .arg("delay", ArgKind::Duration(Unit::Second)),
+            )
+            .command(CommandSpec::new("jam", "Report a jammed bolt"))
+            .command(CommandSpec::new("clear_jam", "Clear a jammed bolt"))
+            .attribute(
+                "lock",
+                AttributeKind::Enum(&["locked", "unlocked", "jammed"]),
+            )
+            .attribute("auto_relock", AttributeKind::Measure(Unit::Second))
+            .attribute("codes", AttributeKind::Int)
+            .attribute("users", AttributeKind::Int)
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let lock = match (self.jammed, self.locked) {
+            (true, _) => "jammed",
+            (false, true) => "locked",
+            (false, false) => "unlocked",
+        };
+        let mut state = DeviceState::new().with("lock", AttributeValue::Enum(lock.to_string()));
+        if let Some(delay) = self.auto_relock {
+            state.set(
+                "auto_relock",
+                AttributeValue::Measure(delay.as_secs_f64(), Unit::Second),
+            );
+        }
+        let active_codes = self.codes.values().filter(|c| !c.is_expired()).count();
+        if active_codes > 0 {
+            state.set("codes", AttributeValue::Int(active_codes as i64));
+        }
+        if !self.users.is_empty() {
+            state.set("users", AttributeValue::Int(self.users.len() as i64));
+        }
+        state
     }
 
-    fn get_state(&self) -> String {
-        if self.locked {
-            "locked".to_string()
-        } else {
-            "unlocked".to_string()
+    /// Counts down the auto-relock timer and locks the door when it expires.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let Some(remaining) = self.relock_in else {
+            return Vec::new();
+        };
+        let remaining = remaining.saturating_sub(elapsed);
+        if !remaining.is_zero() {
+            self.relock_in = Some(remaining);
+            return Vec::new();
         }
+        self.locked = true;
+        self.relock_in = None;
+        vec![self.event(EventType::Lock, Some(AUTO_RELOCK_ACTOR.to_string()))]
+    }
+
+    fn take_pending_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.pending)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::event::EventType;
+    use crate::models::temperature::Temperature;
+
+    fn pin(code: &str) -> Option<Credential> {
+        Some(Credential::Pin(code.to_string()))
+    }
+
+    fn add_pin(label: &str, pin: &str) -> Command {
+        Command::AddPin {
+            label: label.to_string(),
+            pin: pin.to_string(),
+            expires: None,
+        }
+    }
 
     #[test]
     fn door_initial_state_unlocked() {
         let lock = DoorLock::new("Front Door");
         assert_eq!(lock.get_name(), "Front Door");
         assert_eq!(lock.get_type(), "DoorLock");
-        assert_eq!(lock.get_state(), "unlocked");
+        assert_eq!(lock.get_state().to_string(), "unlocked");
+        assert!(!lock.requires_credential());
     }
 
     #[test]
     fn door_lock_unlock() {
         let mut lock = DoorLock::new("Back Door");
 
-        let lock_event = lock.execute_command(EventType::Lock).unwrap();
-        assert_eq!(lock.get_state(), "locked");
-        assert_eq!(lock_event.payload.unwrap(), "locked");
+        let lock_event = lock.execute_command(Command::Lock).unwrap();
+        assert_eq!(lock.get_state().to_string(), "locked");
+        assert_eq!(
+            lock_event.payload.unwrap().get("lock"),
+            Some(&AttributeValue::Enum("locked".into()))
+        );
+
+        let unlock_event = lock.execute_command(Command::Unlock(None)).unwrap();
+        assert_eq!(lock.get_state().to_string(), "unlocked");
+        assert_eq!(unlock_event.payload.unwrap().to_string(), "unlocked");
+        assert_eq!(unlock_event.actor, None);
+    }
+
+    #[test]
+    fn door_unlock_requires_valid_pin() {
+        let mut lock = DoorLock::new("Front Door");
+        lock.execute_command(add_pin("guest", "1234")).unwrap();
+        lock.execute_command(Command::Lock).unwrap();
+
+        let result = lock.execute_command(Command::Unlock(None));
+        assert!(matches!(result, Err(PulseHomeError::AccessDenied { .. })));
+        let result = lock.execute_command(Command::Unlock(pin("9999")));
+        assert!(matches!(result, Err(PulseHomeError::AccessDenied { .. })));
+        assert!(lock.is_locked());
+
+        let denied = lock.take_pending_events();
+        assert_eq!(denied.len(), 2);
+        assert_eq!(denied[1].event_type, EventType::AccessDenied);
+        assert_eq!(denied[1].actor.as_deref(), Some("PIN code"));
+
+        let event = lock.execute_command(Command::Unlock(pin("1234"))).unwrap();
+        assert!(!lock.is_locked());
+        assert_eq!(event.actor.as_deref(), Some("code:guest"));
+        assert_eq!(lock.get_state().to_string(), "unlocked (codes=1)");
+    }
+
+    #[test]
+    fn door_revoked_and_expired_pins_are_rejected() {
+        let mut lock = DoorLock::new("Front Door");
+        lock.execute_command(add_pin("cleaner", "2468")).unwrap();
+        lock.execute_command(add_pin("owner", "1357")).unwrap();
+        lock.codes.get_mut("cleaner").unwrap().expires =
+            Some(Local::now() - chrono::Duration::minutes(1));
+
+        let result = lock.execute_command(Command::Unlock(pin("2468")));
+        assert!(
+            matches!(result, Err(PulseHomeError::AccessDenied { reason, .. }) if reason.contains("expired"))
+        );
+        assert_eq!(lock.get_state().get("codes"), Some(&AttributeValue::Int(1)));
+
+        let event = lock
+            .execute_command(Command::RevokePin("owner".into()))
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AccessRevoked);
+        assert!(lock.execute_command(Command::Unlock(pin("1357"))).is_err());
+        assert!(
+            lock.execute_command(Command::RevokePin("owner".into()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn door_rejects_malformed_pins() {
+        let mut lock = DoorLock::new("Front Door");
+        assert!(lock.execute_command(add_pin("kid", "12")).is_err());
+        assert!(lock.execute_command(add_pin("kid", "12ab")).is_err());
+        lock.execute_command(add_pin("kid", "4321")).unwrap();
+        assert!(lock.execute_command(add_pin("nanny", "4321")).is_err());
+        let expired = Command::AddPin {
+            label: "late".into(),
+            pin: "5555".into(),
+            expires: Some(Local::now() - chrono::Duration::hours(1)),
+        };
+        assert!(lock.execute_command(expired).is_err());
+    }
+
+    #[test]
+    fn door_authorized_users() {
+        let mut lock = DoorLock::new("Front Door");
+        let event = lock
+            .execute_command(Command::AuthorizeUser("alice".into()))
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AccessGranted);
+        lock.execute_command(Command::Lock).unwrap();
+
+        let user = |name: &str| Some(Credential::User(name.to_string()));
+        assert!(
+            lock.execute_command(Command::Unlock(user("mallory")))
+                .is_err()
+        );
+        assert_eq!(
+            lock.take_pending_events()[0].actor.as_deref(),
+            Some("user:mallory")
+        );
+
+        let event = lock
+            .execute_command(Command::Unlock(user("alice")))
+            .unwrap();
+        assert_eq!(event.actor.as_deref(), Some("user:alice"));
+
+        lock.execute_command(Command::RevokeUser("alice".into()))
+            .unwrap();
+        assert!(!lock.requires_credential());
+    }
+
+    #[test]
+    fn door_auto_relocks_after_delay() {
+        let mut lock = DoorLock::new("Front Door");
+        lock.execute_command(Command::SetAutoRelock(Some(Duration::from_secs(30))))
+            .unwrap();
+        lock.execute_command(Command::Unlock(None)).unwrap();
+
+        assert!(lock.tick(Duration::from_secs(20)).is_empty());
+        assert!(!lock.is_locked());
+
+        let events = lock.tick(Duration::from_secs(10));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::Lock);
+        assert_eq!(events[0].actor.as_deref(), Some(AUTO_RELOCK_ACTOR));
+        assert_eq!(lock.get_state().to_string(), "locked (auto_relock=30 s)");
+        assert!(lock.tick(Duration::from_secs(60)).is_empty());
+
+        assert!(
+            lock.execute_command(Command::SetAutoRelock(Some(Duration::ZERO)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn door_jam_blocks_bolt_until_cleared() {
+        let mut lock = DoorLock::new("Front Door");
+        lock.execute_command(Command::SetAutoRelock(Some(Duration::from_secs(5))))
+            .unwrap();
+        let event = lock.execute_command(Command::ReportJam).unwrap();
+        assert_eq!(event.event_type, EventType::LockJammed);
+        assert_eq!(
+            lock.get_state().get("lock"),
+            Some(&AttributeValue::Enum("jammed".into()))
+        );
+
+        assert!(matches!(
+            lock.execute_command(Command::Lock),
+            Err(PulseHomeError::DeviceFault { .. })
+        ));
+        assert!(lock.tick(Duration::from_secs(10)).is_empty());
 
-        let unlock_event = lock.execute_command(EventType::Unlock).unwrap();
-        assert_eq!(lock.get_state(), "unlocked");
-        assert_eq!(unlock_event.payload.unwrap(), "unlocked");
+        lock.execute_command(Command::ClearJam).unwrap();
+        assert!(!lock.is_jammed());
+        lock.execute_command(Command::Lock).unwrap();
+        assert!(lock.is_locked());
     }
 
     #[test]
     fn door_invalid_command() {
         let mut lock = DoorLock::new("Test Door");
-        let result = lock.execute_command(EventType::SetTemp);
-        assert!(result.is_err());
+        let result = lock.execute_command(Command::SetTemp(Temperature::from_celsius(22.0)));
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
     }
 }
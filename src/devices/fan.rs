@@ -0,0 +1,294 @@
+//! # Fan Device
+//!
+//! This module defines the `Fan` struct, a concrete implementation of the
+//! [`Device`] trait for standing fans and ceiling fans.
+//!
+//! A fan runs at a speed in percent, set either directly or through one of
+//! the [`FanSpeed`] presets (`low`, `medium`, `high`). Standing fans can
+//! oscillate; ceiling fans can reverse their direction to push warm air down
+//! in winter. Turning a fan on resumes the last speed it ran at.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::fan::Fan;
+//! use pulsehome::models::argument::FanSpeed;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//!
+//! let mut fan = Fan::new("Bedroom Fan");
+//! fan.execute_command(Command::SetFanSpeed(FanSpeed::Medium)).unwrap();
+//! assert_eq!(
+//!     fan.get_state().to_string(),
+//!     "on (speed=66%, preset=medium, oscillating=off)"
+//! );
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    argument::{FanDirection, FanSpeed},
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
+};
+
+/// The kind of fan, which decides the features it supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanKind {
+    /// A standing or desk fan that can oscillate.
+    Standing,
+    /// A ceiling fan that can reverse direction.
+    Ceiling,
+}
+
+/// Represents a standing fan or a ceiling fan.
+#[derive(Debug)]
+pub struct Fan {
+    name: String,
+    kind: FanKind,
+    speed: u8,      // percent; 0 while off
+    last_speed: u8, // speed resumed by `turn_on`
+    oscillating: bool,
+    direction: FanDirection,
+}
+
+impl Fan {
+    /// Creates a new standing fan with the given name, initially off.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::fan::Fan;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let fan = Fan::new("Office Fan");
+    /// assert_eq!(
+    ///     fan.get_state().to_string(),
+    ///     "off (speed=0%, preset=off, oscillating=off)"
+    /// );
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_kind(name, FanKind::Standing)
+    }
+
+    /// Creates a new fan of the given kind, initially off.
+    pub fn with_kind(name: impl Into<String>, kind: FanKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            speed: 0,
+            last_speed: FanSpeed::Low.percent(),
+            oscillating: false,
+            direction: FanDirection::Forward,
+        }
+    }
+
+    /// Returns the kind of fan.
+    pub fn kind(&self) -> FanKind {
+        self.kind
+    }
+
+    /// Returns the current speed in percent (0 while off).
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
+
+    fn set_speed(&mut self, speed: FanSpeed) -> Result<(), PulseHomeError> {
+        let percent = speed.percent();
+        if percent > 100 {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "fan speed {}% is outside 0–100%",
+                percent
+            )));
+        }
+        self.speed = percent;
+        if percent > 0 {
+            self.last_speed = percent;
+        }
+        Ok(())
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for Fan {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        match self.kind {
+            FanKind::Standing => "Fan",
+            FanKind::Ceiling => "CeilingFan",
+        }
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match (&command, self.kind) {
+            (Command::TurnOn, _) => self.speed = self.last_speed,
+            (Command::TurnOff, _) => self.speed = 0,
+            (Command::SetFanSpeed(speed), _) => self.set_speed(*speed)?,
+            (Command::SetOscillation(on), FanKind::Standing) => self.oscillating = *on,
+            (Command::SetFanDirection(direction), FanKind::Ceiling) => self.direction = *direction,
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let capabilities = Capabilities::new()
+            .command(CommandSpec::new("turn_on", "Run at the last speed"))
+            .command(CommandSpec::new("turn_off", "Stop the fan"))
+            .command(CommandSpec::new("fan_speed", "Set the speed").arg(
+                "speed",
+                ArgKind::Choice(&["off", "low", "medium", "high", "0-100%"]),
+            ))
+            .attribute("power", AttributeKind::Bool)
+            .attribute("speed", AttributeKind::Measure(Unit::Percent))
+            .attribute(
+                "preset",
+                AttributeKind::Enum(&["off", "low", "medium", "high"]),
+            );
+        match self.kind {
+            FanKind::Standing => capabilities
+                .command(
+                    CommandSpec::new("oscillate", "Turn oscillation on or off")
+                        .arg("oscillate", ArgKind::Choice(&["on", "off"])),
+                )
+                .attribute("oscillating", AttributeKind::Bool),
+            FanKind::Ceiling => capabilities
+                .command(
+                    CommandSpec::new("fan_direction", "Set the blade direction")
+                        .arg("direction", ArgKind::Choice(&["forward", "reverse"])),
+                )
+                .attribute("direction", AttributeKind::Enum(&["forward", "reverse"])),
+        }
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let state = DeviceState::new()
+            .with("power", AttributeValue::Bool(self.speed > 0))
+            .with(
+                "speed",
+                AttributeValue::Measure(self.speed as f64, Unit::Percent),
+            )
+            .with(
+                "preset",
+                AttributeValue::Enum(FanSpeed::preset_for(self.speed).to_string()),
+            );
+        match self.kind {
+            FanKind::Standing => state.with("oscillating", AttributeValue::Bool(self.oscillating)),
+            FanKind::Ceiling => state.with(
+                "direction",
+                AttributeValue::Enum(self.direction.to_string()),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fan_capabilities_follow_kind() {
+        let standing = Fan::new("Fan").capabilities();
+        assert!(standing.supports(&Command::SetOscillation(true)));
+        assert!(!standing.supports(&Command::SetFanDirection(FanDirection::Reverse)));
+
+        let ceiling = Fan::with_kind("Ceiling", FanKind::Ceiling).capabilities();
+        assert!(ceiling.supports(&Command::SetFanDirection(FanDirection::Reverse)));
+        assert_eq!(
+            ceiling.find("fan_direction").unwrap().usage(),
+            "fan_direction <forward|reverse>"
+        );
+    }
+
+    #[test]
+    fn fan_presets_and_percentages() {
+        let mut fan = Fan::new("Fan");
+        assert_eq!(fan.get_type(), "Fan");
+        let event = fan
+            .execute_command(Command::SetFanSpeed(FanSpeed::High))
+            .unwrap();
+        assert_eq!(event.event_type, EventType::SetFanSpeed);
+        assert_eq!(fan.speed(), 100);
+
+        fan.execute_command(Command::SetFanSpeed(FanSpeed::Percent(20)))
+            .unwrap();
+        assert_eq!(
+            fan.get_state().get("preset"),
+            Some(&AttributeValue::Enum("low".into()))
+        );
+        assert!(
+            fan.execute_command(Command::SetFanSpeed(FanSpeed::Percent(120)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn fan_resumes_last_speed() {
+        let mut fan = Fan::new("Fan");
+        fan.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(fan.speed(), 33);
+
+        fan.execute_command(Command::SetFanSpeed(FanSpeed::Percent(80)))
+            .unwrap();
+        fan.execute_command(Command::SetFanSpeed(FanSpeed::Off))
+            .unwrap();
+        assert_eq!(
+            fan.get_state().get("power"),
+            Some(&AttributeValue::Bool(false))
+        );
+        fan.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(fan.speed(), 80);
+    }
+
+    #[test]
+    fn fan_features_depend_on_kind() {
+        let mut fan = Fan::new("Desk Fan");
+        let event = fan.execute_command(Command::SetOscillation(true)).unwrap();
+        assert_eq!(event.event_type, EventType::SetOscillation);
+        assert!(matches!(
+            fan.execute_command(Command::SetFanDirection(FanDirection::Reverse)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+
+        let mut ceiling = Fan::with_kind("Living Room Fan", FanKind::Ceiling);
+        assert_eq!(ceiling.get_type(), "CeilingFan");
+        ceiling
+            .execute_command(Command::SetFanDirection(FanDirection::Reverse))
+            .unwrap();
+        assert_eq!(
+            ceiling.get_state().to_string(),
+            "off (speed=0%, preset=off, direction=reverse)"
+        );
+        assert!(matches!(
+            ceiling.execute_command(Command::SetOscillation(true)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn fan_speed_parsing() {
+        assert_eq!("Medium".parse::<FanSpeed>().unwrap(), FanSpeed::Medium);
+        assert_eq!("45%".parse::<FanSpeed>().unwrap(), FanSpeed::Percent(45));
+        assert!("101".parse::<FanSpeed>().is_err());
+        assert!("breezy".parse::<FanSpeed>().is_err());
+        assert_eq!(
+            "winter".parse::<FanDirection>().unwrap(),
+            FanDirection::Reverse
+        );
+    }
+}
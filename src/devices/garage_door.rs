@@ -0,0 +1,336 @@
+//! # Garage Door Device
+//!
+//! This module defines the `GarageDoor` struct, a concrete implementation of
+//! the [`Device`] trait for garage door openers.
+//!
+//! A garage door moves through a small state machine ([`GarageDoorState`]):
+//! `open` and `close` start the motor, which travels for the door's travel
+//! time (raising an [`EventType::PositionChanged`] event on every step) before
+//! settling in `open` or `closed`; `stop` halts it part way. Commands that make
+//! no sense in the current state are rejected with
+//! [`PulseHomeError::InvalidTransition`].
+//!
+//! The safety beam is reported as a [`SensorReading::Obstruction`]. An
+//! obstruction while closing reverses the door, and the door refuses to close
+//! until the beam is clear again.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::garage_door::{GarageDoor, GarageDoorState};
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//! use std::time::Duration;
+//!
+//! let mut door = GarageDoor::new("Garage");
+//! door.execute_command(Command::Open).unwrap();
+//! assert_eq!(door.door_state(), GarageDoorState::Opening);
+//!
+//! door.tick(Duration::from_secs(12));
+//! assert_eq!(door.door_state(), GarageDoorState::Open);
+//!
+//! // Opening an open door is not a valid transition.
+//! assert!(door.execute_command(Command::Open).is_err());
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    reading::SensorReading,
+    state::{AttributeValue, DeviceState, Unit},
+};
+use std::fmt;
+use std::time::Duration;
+
+/// Time a garage door takes to travel fully open or closed by default.
+pub const DEFAULT_TRAVEL_TIME: Duration = Duration::from_secs(12);
+
+/// Where a garage door is in its open/close cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GarageDoorState {
+    Closed,
+    Opening,
+    Open,
+    Closing,
+    /// Halted part way by a `stop` command.
+    Stopped,
+}
+
+impl fmt::Display for GarageDoorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GarageDoorState::Closed => "closed",
+            GarageDoorState::Opening => "opening",
+            GarageDoorState::Open => "open",
+            GarageDoorState::Closing => "closing",
+            GarageDoorState::Stopped => "stopped",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Represents a garage door opener.
+#[derive(Debug)]
+pub struct GarageDoor {
+    name: String,
+    state: GarageDoorState,
+    position: f64, // 0.0 = closed, 1.0 = open
+    obstructed: bool,
+    travel_time: Duration,
+}
+
+impl GarageDoor {
+    /// Creates a new `GarageDoor` with the given name, initially closed.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::garage_door::GarageDoor;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let door = GarageDoor::new("Garage");
+    /// assert_eq!(door.get_state().to_string(), "closed (position=0%)");
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            state: GarageDoorState::Closed,
+            position: 0.0,
+            obstructed: false,
+            travel_time: DEFAULT_TRAVEL_TIME,
+        }
+    }
+
+    /// Returns the door with a different full open/close travel time.
+    pub fn with_travel_time(mut self, travel_time: Duration) -> Self {
+        self.travel_time = travel_time;
+        self
+    }
+
+    /// Returns the door's current state.
+    pub fn door_state(&self) -> GarageDoorState {
+        self.state
+    }
+
+    /// Returns `true` while the safety beam reports an obstruction.
+    pub fn is_obstructed(&self) -> bool {
+        self.obstructed
+    }
+
+    /// Applies a command to the state machine, returning the next state.
+    fn transition(&self, command: &Command) -> Result<GarageDoorState, PulseHomeError> {
+        use GarageDoorState::*;
+        let next = match (command, self.state) {
+            (Command::Open, Closed | Closing | Stopped) => Opening,
+            (Command::Close, Open | Opening | Stopped) => Closing,
+            (Command::Stop, Opening | Closing) => Stopped,
+            (Command::Open | Command::Close | Command::Stop, _) => {
+                return Err(PulseHomeError::InvalidTransition {
+                    device: self.name.clone(),
+                    state: self.state.to_string(),
+                    command: command.name().to_string(),
+                });
+            }
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), command)),
+        };
+        if next == Closing && self.obstructed {
+            return Err(PulseHomeError::DeviceFault {
+                device: self.name.clone(),
+                fault: "the door is obstructed".to_string(),
+            });
+        }
+        Ok(next)
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for GarageDoor {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        "GarageDoor"
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        self.state = self.transition(&command)?;
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(CommandSpec::new("open", "Open the door"))
+            .command(CommandSpec::new("close", "Close the door"))
+            .command(CommandSpec::new("stop", "Halt a moving door"))
+            .reading("obstruction")
+            .attribute(
+                "door",
+                AttributeKind::Enum(&["closed", "opening", "open", "closing", "stopped"]),
+            )
+            .attribute("position", AttributeKind::Measure(Unit::Percent))
+            .attribute("obstruction", AttributeKind::Bool)
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let mut state = DeviceState::new()
+            .with("door", AttributeValue::Enum(self.state.to_string()))
+            .with(
+                "position",
+                AttributeValue::Measure((self.position * 100.0).round(), Unit::Percent),
+            );
+        if self.obstructed {
+            state.set("obstruction", AttributeValue::Bool(true));
+        }
+        state
+    }
+
+    /// Accepts safety beam readings; an obstruction while closing reverses the door.
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        let SensorReading::Obstruction(obstructed) = reading else {
+            return Err(PulseHomeError::unsupported_reading(
+                self.get_type(),
+                &reading,
+            ));
+        };
+        if obstructed == self.obstructed {
+            return Ok(None);
+        }
+        self.obstructed = obstructed;
+        if !obstructed {
+            return Ok(Some(self.event(EventType::ObstructionCleared)));
+        }
+        if self.state == GarageDoorState::Closing {
+            self.state = GarageDoorState::Opening;
+        }
+        Ok(Some(self.event(EventType::ObstructionDetected)))
+    }
+
+    /// Moves the door toward its end stop while opening or closing.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let step = elapsed.as_secs_f64() / self.travel_time.as_secs_f64();
+        match self.state {
+            GarageDoorState::Opening => {
+                self.position = (self.position + step).min(1.0);
+                if self.position >= 1.0 {
+                    self.state = GarageDoorState::Open;
+                }
+            }
+            GarageDoorState::Closing => {
+                self.position = (self.position - step).max(0.0);
+                if self.position <= 0.0 {
+                    self.state = GarageDoorState::Closed;
+                }
+            }
+            _ => return Vec::new(),
+        }
+        vec![self.event(EventType::PositionChanged)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn door() -> GarageDoor {
+        GarageDoor::new("Garage").with_travel_time(Duration::from_secs(10))
+    }
+
+    #[test]
+    fn garage_full_cycle() {
+        let mut door = door();
+        assert_eq!(door.get_type(), "GarageDoor");
+        let event = door.execute_command(Command::Open).unwrap();
+        assert_eq!(event.event_type, EventType::Open);
+
+        let events = door.tick(Duration::from_secs(4));
+        assert_eq!(events[0].event_type, EventType::PositionChanged);
+        assert_eq!(door.get_state().to_string(), "opening (position=40%)");
+
+        door.tick(Duration::from_secs(6));
+        assert_eq!(door.door_state(), GarageDoorState::Open);
+        assert!(door.tick(Duration::from_secs(1)).is_empty());
+
+        door.execute_command(Command::Close).unwrap();
+        door.tick(Duration::from_secs(10));
+        assert_eq!(door.door_state(), GarageDoorState::Closed);
+    }
+
+    #[test]
+    fn garage_stop_and_resume() {
+        let mut door = door();
+        door.execute_command(Command::Open).unwrap();
+        door.tick(Duration::from_secs(5));
+        door.execute_command(Command::Stop).unwrap();
+        assert_eq!(door.get_state().to_string(), "stopped (position=50%)");
+        assert!(door.tick(Duration::from_secs(5)).is_empty());
+
+        door.execute_command(Command::Close).unwrap();
+        door.tick(Duration::from_secs(5));
+        assert_eq!(door.door_state(), GarageDoorState::Closed);
+    }
+
+    #[test]
+    fn garage_rejects_invalid_transitions() {
+        let mut door = door();
+        for command in [Command::Close, Command::Stop] {
+            let result = door.execute_command(command);
+            assert!(matches!(
+                result,
+                Err(PulseHomeError::InvalidTransition { ref state, .. }) if state == "closed"
+            ));
+        }
+        assert!(matches!(
+            door.execute_command(Command::Lock),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+        door.execute_command(Command::Open).unwrap();
+        assert!(door.execute_command(Command::Open).is_err());
+    }
+
+    #[test]
+    fn garage_obstruction_reverses_door() {
+        let mut door = door();
+        door.execute_command(Command::Open).unwrap();
+        door.tick(Duration::from_secs(10));
+        door.execute_command(Command::Close).unwrap();
+        door.tick(Duration::from_secs(3));
+
+        let event = door
+            .report_reading(SensorReading::Obstruction(true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::ObstructionDetected);
+        assert_eq!(door.door_state(), GarageDoorState::Opening);
+        assert!(matches!(
+            door.execute_command(Command::Close),
+            Err(PulseHomeError::DeviceFault { .. })
+        ));
+
+        door.tick(Duration::from_secs(3));
+        assert_eq!(
+            door.get_state().to_string(),
+            "open (position=100%, obstruction=on)"
+        );
+
+        let event = door
+            .report_reading(SensorReading::Obstruction(false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::ObstructionCleared);
+        door.execute_command(Command::Close).unwrap();
+    }
+}
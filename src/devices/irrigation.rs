@@ -0,0 +1,411 @@
+//! # Irrigation Controller Device
+//!
+//! This module defines the `IrrigationController` struct, a concrete
+//! implementation of the [`Device`] trait for multi-zone sprinkler controllers.
+//!
+//! A controller waters one zone at a time. Asking it to run a zone while
+//! another is watering queues the run; each run stops by itself once its time
+//! is up, and the next queued run starts. A rain delay suspends watering: the
+//! running zone is put back at the front of the queue with its remaining time,
+//! and nothing runs until the delay ends or is cancelled.
+//!
+//! Every start and finish is raised as an event ([`EventType::ZoneStarted`],
+//! [`EventType::ZoneFinished`]) so the hub can pass it on to observers.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::irrigation::IrrigationController;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//! use std::time::Duration;
+//!
+//! let mut garden = IrrigationController::new("Garden", 4);
+//! let minutes = |m: u64| Duration::from_secs(m * 60);
+//! garden.execute_command(Command::RunZone { zone: 1, duration: minutes(10) }).unwrap();
+//! garden.execute_command(Command::RunZone { zone: 3, duration: minutes(5) }).unwrap();
+//! assert_eq!(garden.running_zone(), Some(1));
+//!
+//! garden.tick(minutes(10));
+//! assert_eq!(garden.running_zone(), Some(3));
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
+};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of zones a controller has unless told otherwise.
+pub const DEFAULT_ZONES: u8 = 6;
+
+/// Longest single watering run a controller accepts.
+pub const MAX_RUN_TIME: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Longest rain delay a controller accepts.
+pub const MAX_RAIN_DELAY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A zone waiting to water, or watering, for the given time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Run {
+    zone: u8,
+    remaining: Duration,
+}
+
+/// Represents a multi-zone irrigation controller.
+#[derive(Debug)]
+pub struct IrrigationController {
+    name: String,
+    zones: u8,
+    running: Option<Run>,
+    queue: VecDeque<Run>,
+    rain_delay: Option<Duration>, // remaining suspension
+    pending: Vec<Event>,
+}
+
+impl IrrigationController {
+    /// Creates a new idle controller with `zones` zones, numbered from 1.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::irrigation::IrrigationController;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let garden = IrrigationController::new("Garden", 6);
+    /// assert_eq!(garden.get_state().to_string(), "idle (zones=6, queued=0)");
+    /// ```
+    pub fn new(name: impl Into<String>, zones: u8) -> Self {
+        Self {
+            name: name.into(),
+            zones,
+            running: None,
+            queue: VecDeque::new(),
+            rain_delay: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the number of zones.
+    pub fn zones(&self) -> u8 {
+        self.zones
+    }
+
+    /// Returns the zone currently watering, if any.
+    pub fn running_zone(&self) -> Option<u8> {
+        self.running.map(|run| run.zone)
+    }
+
+    /// Returns the zones waiting to water, in the order they will run.
+    pub fn queued_zones(&self) -> Vec<u8> {
+        self.queue.iter().map(|run| run.zone).collect()
+    }
+
+    /// Returns `true` while a rain delay suspends watering.
+    pub fn is_rain_delayed(&self) -> bool {
+        self.rain_delay.is_some()
+    }
+
+    fn queue_run(&mut self, zone: u8, duration: Duration) -> Result<(), PulseHomeError> {
+        if zone == 0 || zone > self.zones {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "zone {} does not exist (expected 1–{})",
+                zone, self.zones
+            )));
+        }
+        if duration.is_zero() || duration > MAX_RUN_TIME {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "run time {} min is outside 1–{} min",
+                duration.as_secs() / 60,
+                MAX_RUN_TIME.as_secs() / 60
+            )));
+        }
+        self.queue.push_back(Run {
+            zone,
+            remaining: duration,
+        });
+        Ok(())
+    }
+
+    fn set_rain_delay(&mut self, delay: Option<Duration>) -> Result<(), PulseHomeError> {
+        match delay {
+            Some(delay) if delay.is_zero() || delay > MAX_RAIN_DELAY => {
+                Err(PulseHomeError::InvalidArgument(format!(
+                    "rain delay {} h is outside 1–{} h",
+                    delay.as_secs() / 3600,
+                    MAX_RAIN_DELAY.as_secs() / 3600
+                )))
+            }
+            Some(delay) => {
+                if let Some(run) = self.running.take() {
+                    self.queue.push_front(run);
+                }
+                self.rain_delay = Some(delay);
+                Ok(())
+            }
+            None => {
+                self.rain_delay = None;
+                Ok(())
+            }
+        }
+    }
+
+    /// Starts the next queued run if the controller is free to water.
+    fn start_next(&mut self) -> Option<Event> {
+        if self.running.is_some() || self.rain_delay.is_some() {
+            return None;
+        }
+        self.running = Some(self.queue.pop_front()?);
+        Some(self.event(EventType::ZoneStarted))
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for IrrigationController {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        "IrrigationController"
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match command {
+            Command::RunZone { zone, duration } => self.queue_run(zone, duration)?,
+            Command::SetRainDelay(delay) => self.set_rain_delay(delay)?,
+            Command::Stop => {
+                self.running = None;
+                self.queue.clear();
+            }
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        if let Some(started) = self.start_next() {
+            self.pending.push(started);
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(
+                CommandSpec::new("run_zone", "Water a zone, after any queued runs")
+                    .arg("zone", ArgKind::count(1, self.zones as i64))
+                    .arg(
+                        "minutes",
+                        ArgKind::integer(1, (MAX_RUN_TIME.as_secs() / 60) as i64, Unit::Minute),
+                    ),
+            )
+            .command(
+                CommandSpec::new("rain_delay", "Suspend watering (or off)").arg(
+                    "hours",
+                    ArgKind::integer(1, (MAX_RAIN_DELAY.as_secs() / 3600) as i64, Unit::Hour),
+                ),
+            )
+            .command(CommandSpec::new(
+                "stop",
+                "Stop watering and clear the queue",
+            ))
+            .attribute(
+                "irrigation",
+                AttributeKind::Enum(&["idle", "watering", "rain_delay"]),
+            )
+            .attribute("zones", AttributeKind::Int)
+            .attribute("zone", AttributeKind::Int)
+            .attribute("remaining", AttributeKind::Measure(Unit::Minute))
+            .attribute("queued", AttributeKind::Int)
+            .attribute("rain_delay", AttributeKind::Measure(Unit::Hour))
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let status = match (self.running, self.rain_delay) {
+            (Some(_), _) => "watering",
+            (None, Some(_)) => "rain_delay",
+            (None, None) => "idle",
+        };
+        let mut state = DeviceState::new()
+            .with("irrigation", AttributeValue::Enum(status.to_string()))
+            .with("zones", AttributeValue::Int(self.zones as i64));
+        if let Some(run) = self.running {
+            state.set("zone", AttributeValue::Int(run.zone as i64));
+            state.set(
+                "remaining",
+                AttributeValue::Measure((run.remaining.as_secs_f64() / 60.0).ceil(), Unit::Minute),
+            );
+        }
+        state.set("queued", AttributeValue::Int(self.queue.len() as i64));
+        if let Some(delay) = self.rain_delay {
+            state.set(
+                "rain_delay",
+                AttributeValue::Measure((delay.as_secs_f64() / 3600.0).ceil(), Unit::Hour),
+            );
+        }
+        state
+    }
+
+    /// Counts down the running zone and any rain delay, moving on to the next
+    /// queued run as each one finishes.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut left = elapsed;
+        while !left.is_zero() {
+            if let Some(delay) = self.rain_delay {
+                if delay > left {
+                    self.rain_delay = Some(delay - left);
+                    break;
+                }
+                left -= delay;
+                self.rain_delay = None;
+                events.push(self.event(EventType::RainDelayEnded));
+                events.extend(self.start_next());
+                continue;
+            }
+            let Some(run) = self.running.as_mut() else {
+                break;
+            };
+            if run.remaining > left {
+                run.remaining -= left;
+                break;
+            }
+            left -= run.remaining;
+            run.remaining = Duration::ZERO;
+            events.push(self.event(EventType::ZoneFinished));
+            self.running = None;
+            events.extend(self.start_next());
+        }
+        events
+    }
+
+    fn take_pending_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(m: u64) -> Duration {
+        Duration::from_secs(m * 60)
+    }
+
+    fn run(zone: u8, m: u64) -> Command {
+        Command::RunZone {
+            zone,
+            duration: minutes(m),
+        }
+    }
+
+    #[test]
+    fn runs_one_zone_at_a_time() {
+        let mut garden = IrrigationController::new("Garden", 4);
+        assert_eq!(garden.get_type(), "IrrigationController");
+        let event = garden.execute_command(run(2, 10)).unwrap();
+        assert_eq!(event.event_type, EventType::RunZone);
+        let started = garden.take_pending_events();
+        assert_eq!(started[0].event_type, EventType::ZoneStarted);
+
+        garden.execute_command(run(4, 5)).unwrap();
+        assert!(garden.take_pending_events().is_empty());
+        assert_eq!(
+            garden.get_state().to_string(),
+            "watering (zones=4, zone=2, remaining=10 min, queued=1)"
+        );
+
+        let events = garden.tick(minutes(12));
+        let types: Vec<_> = events.iter().map(|e| e.event_type.clone()).collect();
+        assert_eq!(types, [EventType::ZoneFinished, EventType::ZoneStarted]);
+        assert_eq!(garden.running_zone(), Some(4));
+        assert_eq!(
+            garden.get_state().get("remaining"),
+            Some(&AttributeValue::Measure(3.0, Unit::Minute))
+        );
+
+        garden.tick(minutes(3));
+        assert_eq!(garden.running_zone(), None);
+        assert_eq!(garden.get_state().to_string(), "idle (zones=4, queued=0)");
+    }
+
+    #[test]
+    fn rejects_unknown_zones_and_run_times() {
+        let mut garden = IrrigationController::new("Garden", 4);
+        assert!(garden.execute_command(run(0, 10)).is_err());
+        assert!(garden.execute_command(run(5, 10)).is_err());
+        assert!(garden.execute_command(run(1, 0)).is_err());
+        assert!(garden.execute_command(run(1, 5 * 60)).is_err());
+        assert!(matches!(
+            garden.execute_command(Command::TurnOn),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn stop_clears_the_queue() {
+        let mut garden = IrrigationController::new("Garden", 4);
+        garden.execute_command(run(1, 10)).unwrap();
+        garden.execute_command(run(2, 10)).unwrap();
+        garden.execute_command(Command::Stop).unwrap();
+        assert_eq!(garden.running_zone(), None);
+        assert!(garden.queued_zones().is_empty());
+        assert!(garden.tick(minutes(30)).is_empty());
+    }
+
+    #[test]
+    fn rain_delay_suspends_and_resumes_watering() {
+        let mut garden = IrrigationController::new("Garden", 4);
+        garden.execute_command(run(1, 10)).unwrap();
+        garden.tick(minutes(4));
+        garden
+            .execute_command(Command::SetRainDelay(Some(minutes(60))))
+            .unwrap();
+        assert_eq!(garden.running_zone(), None);
+        assert_eq!(garden.queued_zones(), [1]);
+
+        garden.execute_command(run(3, 5)).unwrap();
+        assert_eq!(
+            garden.get_state().to_string(),
+            "rain_delay (zones=4, queued=2, rain_delay=1 h)"
+        );
+
+        let events = garden.tick(minutes(62));
+        assert_eq!(events[0].event_type, EventType::RainDelayEnded);
+        assert_eq!(events[1].event_type, EventType::ZoneStarted);
+        assert_eq!(garden.running_zone(), Some(1));
+        assert_eq!(
+            garden.get_state().get("remaining"),
+            Some(&AttributeValue::Measure(4.0, Unit::Minute))
+        );
+    }
+
+    #[test]
+    fn cancelling_rain_delay_resumes_immediately() {
+        let mut garden = IrrigationController::new("Garden", 2);
+        garden
+            .execute_command(Command::SetRainDelay(Some(minutes(600))))
+            .unwrap();
+        garden.execute_command(run(2, 10)).unwrap();
+        assert!(garden.take_pending_events().is_empty());
+
+        garden.execute_command(Command::SetRainDelay(None)).unwrap();
+        assert_eq!(
+            garden.take_pending_events()[0].event_type,
+            EventType::ZoneStarted
+        );
+        assert_eq!(garden.running_zone(), Some(2));
+    }
+}
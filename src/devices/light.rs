@@ -4,37 +4,184 @@
 //! [`Device`] trait for smart lights in the PulseHome system.
 //!
 //! The `Light` device can be turned on or off and generates an [`Event`]
-//! whenever its state changes.
+//! whenever its state changes. Depending on its [`LightKind`], a light may also
+//! support brightness, white color temperature and RGB color. Changes to these
+//! can fade over a transition period, which progresses as the hub calls
+//! [`Device::tick`].
 
+use crate::error::PulseHomeError;
 use crate::models::{
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    color::Color,
+    command::Command,
     device::Device,
     event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
 };
+use std::time::Duration;
+
+/// Warmest color temperature (K) a tunable light accepts.
+pub const MIN_COLOR_TEMP: u16 = 2000;
+
+/// Coolest color temperature (K) a tunable light accepts.
+pub const MAX_COLOR_TEMP: u16 = 6500;
+
+/// The feature set of a light fixture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+    /// Plain on/off light.
+    OnOff,
+    /// On/off with brightness.
+    Dimmable,
+    /// Dimmable with adjustable white color temperature.
+    TunableWhite,
+    /// Dimmable with both color temperature and RGB color.
+    Color,
+}
+
+impl LightKind {
+    fn supports_brightness(&self) -> bool {
+        !matches!(self, LightKind::OnOff)
+    }
+
+    fn supports_color_temp(&self) -> bool {
+        matches!(self, LightKind::TunableWhite | LightKind::Color)
+    }
+
+    fn supports_color(&self) -> bool {
+        matches!(self, LightKind::Color)
+    }
+}
+
+/// Whether a color light is currently showing white or RGB output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorMode {
+    Temperature,
+    Rgb,
+}
+
+/// The adjustable output levels of a light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Levels {
+    brightness: u8,
+    color_temp: u16,
+    color: Color,
+}
+
+impl Levels {
+    fn lerp(self, to: Levels, t: f64) -> Levels {
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        Levels {
+            brightness: mix(self.brightness as f64, to.brightness as f64).round() as u8,
+            color_temp: mix(self.color_temp as f64, to.color_temp as f64).round() as u16,
+            color: self.color.lerp(to.color, t),
+        }
+    }
+}
+
+/// A fade in progress from one set of levels to another.
+#[derive(Debug)]
+struct Transition {
+    event_type: EventType,
+    from: Levels,
+    to: Levels,
+    duration: Duration,
+    elapsed: Duration,
+}
 
 /// Represents a smart light device.
 #[derive(Debug)]
 pub struct Light {
     name: String,
+    kind: LightKind,
     state: bool, // true = on, false = off
+    levels: Levels,
+    color_mode: ColorMode,
+    transition: Option<Transition>,
 }
 
 impl Light {
-    /// Creates a new `Light` with the given name, initially off.
+    /// Creates a new on/off `Light` with the given name, initially off.
     ///
     /// # Example
     /// ```
+    /// use pulsehome::models::device::Device;
     /// use pulsehome::devices::light::Light;
     ///
     /// let light = Light::new("Living Room Light");
     /// assert_eq!(light.get_name(), "Living Room Light");
-    /// assert_eq!(light.get_state(), "off");
+    /// assert_eq!(light.get_state().to_string(), "off");
     /// ```
     pub fn new(name: impl Into<String>) -> Self {
+        Self::with_kind(name, LightKind::OnOff)
+    }
+
+    /// Creates a new `Light` with the given feature set, initially off at full brightness.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::device::Device;
+    /// use pulsehome::devices::light::{Light, LightKind};
+    ///
+    /// let light = Light::with_kind("Desk Lamp", LightKind::Dimmable);
+    /// assert_eq!(light.get_state().to_string(), "off (brightness=100%)");
+    /// ```
+    pub fn with_kind(name: impl Into<String>, kind: LightKind) -> Self {
         Self {
             name: name.into(),
+            kind,
             state: false,
+            levels: Levels {
+                brightness: 100,
+                color_temp: 2700,
+                color: Color::new(255, 255, 255),
+            },
+            color_mode: ColorMode::Temperature,
+            transition: None,
         }
     }
+
+    /// Returns the light's feature set.
+    pub fn kind(&self) -> LightKind {
+        self.kind
+    }
+
+    /// Moves to `target` immediately, or starts a fade if a non-zero transition is given.
+    fn begin(&mut self, target: Levels, transition: Option<Duration>, event_type: EventType) {
+        self.state = true;
+        match transition.filter(|d| !d.is_zero()) {
+            Some(duration) => {
+                self.transition = Some(Transition {
+                    event_type,
+                    from: self.levels,
+                    to: target,
+                    duration,
+                    elapsed: Duration::ZERO,
+                })
+            }
+            None => {
+                self.transition = None;
+                self.levels = target;
+                self.settle();
+            }
+        }
+    }
+
+    /// Switches the light off once it has been dimmed all the way down.
+    fn settle(&mut self) {
+        if self.levels.brightness == 0 {
+            self.state = false;
+        }
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
 }
 
 impl Device for Light {
@@ -42,31 +189,154 @@ impl Device for Light {
         &self.name
     }
 
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
     fn get_type(&self) -> &str {
         "Light"
     }
 
-    fn execute_command(&mut self, command: EventType) -> Result<Event, Box<dyn std::error::Error>> {
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
         match command {
-            EventType::TurnOn => self.state = true,
-            EventType::TurnOff => self.state = false,
-            _ => return Err("Light only supports TurnOn or TurnOff commands".into()),
+            Command::TurnOn => {
+                self.transition = None;
+                self.state = true;
+                if self.levels.brightness == 0 {
+                    self.levels.brightness = 100;
+                }
+            }
+            Command::TurnOff => {
+                self.transition = None;
+                self.state = false;
+            }
+            Command::SetBrightness { level, transition } if self.kind.supports_brightness() => {
+                if level > 100 {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "brightness {}% is out of range (0% to 100%)",
+                        level
+                    )));
+                }
+                let target = Levels {
+                    brightness: level,
+                    ..self.levels
+                };
+                self.begin(target, transition, command.event_type());
+            }
+            Command::SetColorTemp { kelvin, transition } if self.kind.supports_color_temp() => {
+                if !(MIN_COLOR_TEMP..=MAX_COLOR_TEMP).contains(&kelvin) {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "color temperature {}K is out of range ({}K to {}K)",
+                        kelvin, MIN_COLOR_TEMP, MAX_COLOR_TEMP
+                    )));
+                }
+                self.color_mode = ColorMode::Temperature;
+                let target = Levels {
+                    color_temp: kelvin,
+                    ..self.levels
+                };
+                self.begin(target, transition, command.event_type());
+            }
+            Command::SetColor { color, transition } if self.kind.supports_color() => {
+                self.color_mode = ColorMode::Rgb;
+                let target = Levels {
+                    color,
+                    ..self.levels
+                };
+                self.begin(target, transition, command.event_type());
+            }
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
         }
 
-        Ok(Event::new(
-            self.name.clone(),
-            self.get_type().to_string(),
-            command,
-            Some(self.get_state()),
-        ))
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let transition =
+            |spec: CommandSpec| spec.optional_arg("over", "secs", ArgKind::Duration(Unit::Second));
+        let mut capabilities = Capabilities::new()
+            .command(CommandSpec::new("turn_on", "Turn the light on"))
+            .command(CommandSpec::new("turn_off", "Turn the light off"))
+            .attribute("power", AttributeKind::Bool);
+        if self.kind.supports_brightness() {
+            capabilities = capabilities
+                .command(transition(
+                    CommandSpec::new("dim", "Set the brightness")
+                        .arg("level", ArgKind::integer(0, 100, Unit::Percent)),
+                ))
+                .attribute("brightness", AttributeKind::Measure(Unit::Percent));
+        }
+        if self.kind.supports_color_temp() {
+            capabilities = capabilities
+                .command(transition(
+                    CommandSpec::new("color_temp", "Set the white color temperature").arg(
+                        "kelvin",
+                        ArgKind::integer(
+                            MIN_COLOR_TEMP as i64,
+                            MAX_COLOR_TEMP as i64,
+                            Unit::Kelvin,
+                        ),
+                    ),
+                ))
+                .attribute("color_temp", AttributeKind::Measure(Unit::Kelvin));
+        }
+        if self.kind.supports_color() {
+            capabilities = capabilities
+                .command(transition(
+                    CommandSpec::new("color", "Set the color").arg("color", ArgKind::Color),
+                ))
+                .attribute("color", AttributeKind::Text);
+        }
+        if self.kind.supports_brightness() {
+            capabilities =
+                capabilities.attribute("transition", AttributeKind::Measure(Unit::Second));
+        }
+        capabilities
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let mut state = DeviceState::new().with("power", AttributeValue::Bool(self.state));
+        if self.kind.supports_brightness() {
+            state.set(
+                "brightness",
+                AttributeValue::Measure(self.levels.brightness as f64, Unit::Percent),
+            );
+        }
+        match self.color_mode {
+            ColorMode::Temperature if self.kind.supports_color_temp() => state.set(
+                "color_temp",
+                AttributeValue::Measure(self.levels.color_temp as f64, Unit::Kelvin),
+            ),
+            ColorMode::Rgb => {
+                state.set("color", AttributeValue::Text(self.levels.color.to_string()))
+            }
+            _ => {}
+        }
+        if let Some(transition) = &self.transition {
+            let remaining = transition.duration - transition.elapsed;
+            state.set(
+                "transition",
+                AttributeValue::Measure(remaining.as_secs_f64(), Unit::Second),
+            );
+        }
+        state
     }
 
-    fn get_state(&self) -> String {
-        if self.state {
-            "on".to_string()
-        } else {
-            "off".to_string()
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let Some(transition) = self.transition.as_mut() else {
+            return Vec::new();
+        };
+
+        transition.elapsed = (transition.elapsed + elapsed).min(transition.duration);
+        let progress = transition.elapsed.as_secs_f64() / transition.duration.as_secs_f64();
+        self.levels = transition.from.lerp(transition.to, progress);
+
+        let event_type = transition.event_type.clone();
+        if transition.elapsed >= transition.duration {
+            self.transition = None;
+            self.settle();
         }
+        vec![self.event(event_type)]
     }
 }
 
@@ -74,39 +344,180 @@ impl Device for Light {
 mod tests {
     use super::*;
     use crate::models::event::EventType;
+    use crate::models::temperature::Temperature;
 
     #[test]
     fn light_initial_state_off() {
         let light = Light::new("Bedroom Light");
         assert_eq!(light.get_name(), "Bedroom Light");
         assert_eq!(light.get_type(), "Light");
-        assert_eq!(light.get_state(), "off");
+        assert_eq!(light.get_state().to_string(), "off");
     }
 
     #[test]
     fn light_turn_on() {
         let mut light = Light::new("Living Room Light");
-        let event = light.execute_command(EventType::TurnOn).unwrap();
-        assert_eq!(light.get_state(), "on");
+        let event = light.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(light.get_state().to_string(), "on");
         assert_eq!(event.device_name, "Living Room Light");
         assert_eq!(event.event_type, EventType::TurnOn);
-        assert_eq!(event.payload.unwrap(), "on");
+        assert_eq!(
+            event.payload.unwrap().get("power"),
+            Some(&AttributeValue::Bool(true))
+        );
     }
 
     #[test]
     fn light_turn_off() {
         let mut light = Light::new("Living Room Light");
-        light.execute_command(EventType::TurnOn).unwrap(); // turn on first
-        let event = light.execute_command(EventType::TurnOff).unwrap();
-        assert_eq!(light.get_state(), "off");
+        light.execute_command(Command::TurnOn).unwrap(); // turn on first
+        let event = light.execute_command(Command::TurnOff).unwrap();
+        assert_eq!(light.get_state().to_string(), "off");
         assert_eq!(event.event_type, EventType::TurnOff);
-        assert_eq!(event.payload.unwrap(), "off");
+        assert_eq!(event.payload.unwrap().to_string(), "off");
+    }
+
+    #[test]
+    fn light_set_brightness() {
+        let mut light = Light::with_kind("Desk Lamp", LightKind::Dimmable);
+        let event = light
+            .execute_command(Command::SetBrightness {
+                level: 40,
+                transition: None,
+            })
+            .unwrap();
+        assert_eq!(event.event_type, EventType::SetBrightness);
+        assert_eq!(light.get_state().to_string(), "on (brightness=40%)");
+
+        light
+            .execute_command(Command::SetBrightness {
+                level: 0,
+                transition: None,
+            })
+            .unwrap();
+        assert_eq!(light.get_state().to_string(), "off (brightness=0%)");
+
+        light.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(light.get_state().to_string(), "on (brightness=100%)");
+    }
+
+    #[test]
+    fn light_rejects_out_of_range_levels() {
+        let mut light = Light::with_kind("Strip", LightKind::Color);
+        let result = light.execute_command(Command::SetBrightness {
+            level: 101,
+            transition: None,
+        });
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+
+        let result = light.execute_command(Command::SetColorTemp {
+            kelvin: 9000,
+            transition: None,
+        });
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn light_color_modes() {
+        let mut light = Light::with_kind("Strip", LightKind::Color);
+        light
+            .execute_command(Command::SetColor {
+                color: Color::new(255, 136, 0),
+                transition: None,
+            })
+            .unwrap();
+        let state = light.get_state();
+        assert_eq!(
+            state.get("color"),
+            Some(&AttributeValue::Text("#ff8800".into()))
+        );
+        assert_eq!(state.get("color_temp"), None);
+
+        light
+            .execute_command(Command::SetColorTemp {
+                kelvin: 4000,
+                transition: None,
+            })
+            .unwrap();
+        assert_eq!(
+            light.get_state().to_string(),
+            "on (brightness=100%, color_temp=4000 K)"
+        );
+    }
+
+    #[test]
+    fn light_rejects_unsupported_features() {
+        let mut light = Light::with_kind("Hall", LightKind::TunableWhite);
+        let result = light.execute_command(Command::SetColor {
+            color: Color::new(0, 0, 255),
+            transition: None,
+        });
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+
+        let mut light = Light::new("Porch");
+        let result = light.execute_command(Command::SetBrightness {
+            level: 50,
+            transition: None,
+        });
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn light_capabilities_follow_kind() {
+        let plain = Light::new("Porch").capabilities();
+        assert!(plain.supports(&Command::TurnOn));
+        assert!(plain.find("dim").is_none());
+
+        let color = Light::with_kind("Strip", LightKind::Color).capabilities();
+        assert_eq!(
+            color.find("color_temp").unwrap().usage(),
+            "color_temp <kelvin:2000-6500K> [over <secs:s>]"
+        );
+        assert!(color.find("color").is_some());
+        assert!(color.readings.is_empty());
+    }
+
+    #[test]
+    fn light_fades_over_transition() {
+        let mut light = Light::with_kind("Bedroom", LightKind::Dimmable);
+        light.execute_command(Command::TurnOn).unwrap();
+        light
+            .execute_command(Command::SetBrightness {
+                level: 0,
+                transition: Some(Duration::from_secs(10)),
+            })
+            .unwrap();
+        assert_eq!(
+            light.get_state().get("brightness"),
+            Some(&AttributeValue::Measure(100.0, Unit::Percent))
+        );
+
+        let events = light.tick(Duration::from_secs(4));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::SetBrightness);
+        assert_eq!(
+            light.get_state().to_string(),
+            "on (brightness=60%, transition=6 s)"
+        );
+
+        light.tick(Duration::from_secs(10));
+        assert_eq!(light.get_state().to_string(), "off (brightness=0%)");
+        assert!(light.tick(Duration::from_secs(1)).is_empty());
     }
 
     #[test]
     fn light_invalid_command() {
         let mut light = Light::new("Test Light");
-        let result = light.execute_command(EventType::SetTemp);
-        assert!(result.is_err());
+        let result = light.execute_command(Command::SetTemp(Temperature::from_celsius(22.0)));
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
     }
 }
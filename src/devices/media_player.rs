@@ -0,0 +1,325 @@
+//! # Media Player Device
+//!
+//! This module defines the `MediaPlayer` struct, a concrete implementation of
+//! the [`Device`] trait for speakers, TVs and other media players.
+//!
+//! A player has transport controls (play, pause, stop, next, previous), a
+//! volume with mute, an input source and a queue of [`Track`]s. The track at
+//! the head of the queue is reported as the player's now-playing metadata.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::media_player::MediaPlayer;
+//! use pulsehome::models::argument::Track;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//!
+//! let mut speaker = MediaPlayer::new("Kitchen Speaker");
+//! speaker.execute_command(Command::QueueTrack(Track::new("So What").by("Miles Davis"))).unwrap();
+//! speaker.execute_command(Command::Play).unwrap();
+//! speaker.execute_command(Command::SetVolume(25)).unwrap();
+//!
+//! assert_eq!(
+//!     speaker.get_state().to_string(),
+//!     "playing (volume=25%, muted=off, source=default, track=So What – Miles Davis)"
+//! );
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    argument::Track,
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
+};
+use std::fmt;
+
+/// Volume a player starts at, in percent.
+pub const DEFAULT_VOLUME: u8 = 30;
+
+/// Source a player starts on.
+pub const DEFAULT_SOURCE: &str = "default";
+
+/// Whether a player is playing, paused or stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+impl fmt::Display for Playback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Playback::Stopped => "stopped",
+            Playback::Playing => "playing",
+            Playback::Paused => "paused",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Represents a media player or smart speaker.
+#[derive(Debug)]
+pub struct MediaPlayer {
+    name: String,
+    playback: Playback,
+    volume: u8,
+    muted: bool,
+    source: String,
+    queue: Vec<Track>,
+    current: usize, // index of the now-playing track in `queue`
+}
+
+impl MediaPlayer {
+    /// Creates a new stopped `MediaPlayer` with an empty queue.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::media_player::MediaPlayer;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let tv = MediaPlayer::new("Living Room TV");
+    /// assert_eq!(
+    ///     tv.get_state().to_string(),
+    ///     "stopped (volume=30%, muted=off, source=default)"
+    /// );
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            playback: Playback::Stopped,
+            volume: DEFAULT_VOLUME,
+            muted: false,
+            source: DEFAULT_SOURCE.to_string(),
+            queue: Vec::new(),
+            current: 0,
+        }
+    }
+
+    /// Returns whether the player is playing, paused or stopped.
+    pub fn playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Returns the volume in percent (kept while muted).
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    /// Returns the now-playing track, if anything is queued.
+    pub fn now_playing(&self) -> Option<&Track> {
+        self.queue.get(self.current)
+    }
+
+    /// Moves to the next (`step = 1`) or previous (`step = -1`) queued track.
+    fn skip(&mut self, command: &Command, step: isize) -> Result<(), PulseHomeError> {
+        let next = self
+            .current
+            .checked_add_signed(step)
+            .filter(|i| *i < self.queue.len())
+            .ok_or_else(|| PulseHomeError::InvalidTransition {
+                device: self.name.clone(),
+                state: match self.queue.len() {
+                    0 => "empty".to_string(),
+                    _ => format!("on track {} of {}", self.current + 1, self.queue.len()),
+                },
+                command: command.name().to_string(),
+            })?;
+        self.current = next;
+        Ok(())
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for MediaPlayer {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        "MediaPlayer"
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match &command {
+            Command::Play => self.playback = Playback::Playing,
+            Command::Pause if self.playback == Playback::Playing => {
+                self.playback = Playback::Paused
+            }
+            Command::Pause => {
+                return Err(PulseHomeError::InvalidTransition {
+                    device: self.name.clone(),
+                    state: self.playback.to_string(),
+                    command: command.name().to_string(),
+                });
+            }
+            Command::Stop => self.playback = Playback::Stopped,
+            Command::Next => self.skip(&command, 1)?,
+            Command::Previous => self.skip(&command, -1)?,
+            Command::SetVolume(volume) => {
+                if *volume > 100 {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "volume {}% is outside 0–100%",
+                        volume
+                    )));
+                }
+                self.volume = *volume;
+            }
+            Command::SetMute(muted) => self.muted = *muted,
+            Command::SetSource(source) => {
+                if source.trim().is_empty() {
+                    return Err(PulseHomeError::InvalidArgument(
+                        "source must not be empty".to_string(),
+                    ));
+                }
+                self.source = source.trim().to_string();
+            }
+            Command::QueueTrack(track) => self.queue.push(track.clone()),
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(CommandSpec::new("play", "Start or resume playback"))
+            .command(CommandSpec::new("pause", "Pause playback"))
+            .command(CommandSpec::new("stop", "Stop playback"))
+            .command(CommandSpec::new("next", "Skip to the next queued track"))
+            .command(CommandSpec::new(
+                "previous",
+                "Go back to the previous track",
+            ))
+            .command(
+                CommandSpec::new("volume", "Set the volume")
+                    .arg("volume", ArgKind::integer(0, 100, Unit::Percent)),
+            )
+            .command(CommandSpec::new("mute", "Mute, keeping the volume"))
+            .command(CommandSpec::new("unmute", "Unmute"))
+            .command(
+                CommandSpec::new("source", "Switch the input source").keyword_arg(
+                    "to",
+                    "source",
+                    ArgKind::Text,
+                ),
+            )
+            .command(
+                CommandSpec::new("queue", "Queue a track")
+                    .keyword_arg("track", "title", ArgKind::Text)
+                    .optional_arg("by", "artist", ArgKind::Text),
+            )
+            .attribute(
+                "playback",
+                AttributeKind::Enum(&["stopped", "playing", "paused"]),
+            )
+            .attribute("volume", AttributeKind::Measure(Unit::Percent))
+            .attribute("muted", AttributeKind::Bool)
+            .attribute("source", AttributeKind::Text)
+            .attribute("track", AttributeKind::Text)
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let mut state = DeviceState::new()
+            .with("playback", AttributeValue::Enum(self.playback.to_string()))
+            .with(
+                "volume",
+                AttributeValue::Measure(self.volume as f64, Unit::Percent),
+            )
+            .with("muted", AttributeValue::Bool(self.muted))
+            .with("source", AttributeValue::Text(self.source.clone()));
+        if let Some(track) = self.now_playing() {
+            state.set("track", AttributeValue::Text(track.to_string()));
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_controls() {
+        let mut player = MediaPlayer::new("Speaker");
+        assert_eq!(player.get_type(), "MediaPlayer");
+        assert!(matches!(
+            player.execute_command(Command::Pause),
+            Err(PulseHomeError::InvalidTransition { .. })
+        ));
+
+        let event = player.execute_command(Command::Play).unwrap();
+        assert_eq!(event.event_type, EventType::Play);
+        player.execute_command(Command::Pause).unwrap();
+        assert_eq!(player.playback(), Playback::Paused);
+        player.execute_command(Command::Stop).unwrap();
+        assert_eq!(player.playback(), Playback::Stopped);
+    }
+
+    #[test]
+    fn skipping_through_the_queue() {
+        let mut player = MediaPlayer::new("Speaker");
+        assert!(player.execute_command(Command::Next).is_err());
+
+        player
+            .execute_command(Command::QueueTrack(Track::new("One")))
+            .unwrap();
+        player
+            .execute_command(Command::QueueTrack(Track::new("Two").by("Band")))
+            .unwrap();
+        assert_eq!(player.now_playing(), Some(&Track::new("One")));
+
+        let event = player.execute_command(Command::Next).unwrap();
+        assert_eq!(event.event_type, EventType::TrackChanged);
+        assert_eq!(
+            player.get_state().get("track"),
+            Some(&AttributeValue::Text("Two – Band".into()))
+        );
+        assert!(matches!(
+            player.execute_command(Command::Next),
+            Err(PulseHomeError::InvalidTransition { ref state, .. }) if state == "on track 2 of 2"
+        ));
+        player.execute_command(Command::Previous).unwrap();
+        assert_eq!(player.now_playing(), Some(&Track::new("One")));
+    }
+
+    #[test]
+    fn volume_mute_and_source() {
+        let mut player = MediaPlayer::new("TV");
+        player.execute_command(Command::SetVolume(60)).unwrap();
+        assert!(player.execute_command(Command::SetVolume(101)).is_err());
+        player.execute_command(Command::SetMute(true)).unwrap();
+        assert_eq!(player.volume(), 60);
+        let event = player
+            .execute_command(Command::SetSource("HDMI 1".into()))
+            .unwrap();
+        assert_eq!(event.event_type, EventType::SetSource);
+        assert!(
+            player
+                .execute_command(Command::SetSource("  ".into()))
+                .is_err()
+        );
+        assert_eq!(
+            player.get_state().to_string(),
+            "stopped (volume=60%, muted=on, source=HDMI 1)"
+        );
+        assert!(matches!(
+            player.execute_command(Command::Lock),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+}
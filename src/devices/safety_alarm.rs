@@ -0,0 +1,345 @@
+//! # Safety Alarm Device
+//!
+//! This module defines the `SafetyAlarm` struct, a concrete implementation of
+//! the [`Device`] trait for smoke and carbon-monoxide detectors.
+//!
+//! A detector reports an [`AlarmLevel`] (`clear`, `warning` or `alarm`) as a
+//! [`SensorReading::Smoke`] or [`SensorReading::CarbonMonoxide`]. Rising to
+//! `warning` raises a high-priority [`EventType::AlarmWarning`]; rising to
+//! `alarm` raises a critical [`EventType::AlarmTriggered`], which the
+//! [`HomeHub`](crate::mediator::HomeHub) delivers ahead of every other event.
+//!
+//! A sounding detector can be silenced for [`SILENCE_DURATION`]. If the hazard
+//! escalates while silenced, or is still present when the silence runs out,
+//! the detector sounds again.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::safety_alarm::SafetyAlarm;
+//! use pulsehome::models::reading::{AlarmLevel, SensorReading};
+//! use pulsehome::models::device::Device;
+//! use pulsehome::models::event::{EventPriority, EventType};
+//!
+//! let mut detector = SafetyAlarm::smoke("Kitchen Smoke");
+//! let event = detector
+//!     .report_reading(SensorReading::Smoke(AlarmLevel::Alarm))
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! assert_eq!(event.event_type, EventType::AlarmTriggered);
+//! assert_eq!(event.priority(), EventPriority::Critical);
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    reading::{AlarmLevel, SensorReading},
+    state::{AttributeValue, DeviceState},
+};
+use std::time::Duration;
+
+/// How long `silence` hushes a sounding detector.
+pub const SILENCE_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// The hazard a detector senses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hazard {
+    Smoke,
+    CarbonMonoxide,
+}
+
+/// Represents a smoke or carbon-monoxide detector.
+#[derive(Debug)]
+pub struct SafetyAlarm {
+    name: String,
+    hazard: Hazard,
+    level: AlarmLevel,
+    silenced_for: Option<Duration>, // remaining silence while hushed
+}
+
+impl SafetyAlarm {
+    /// Creates a smoke detector with the given name, initially clear.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::safety_alarm::SafetyAlarm;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let detector = SafetyAlarm::smoke("Hall Smoke");
+    /// assert_eq!(detector.get_type(), "SmokeAlarm");
+    /// assert_eq!(detector.get_state().to_string(), "clear");
+    /// ```
+    pub fn smoke(name: impl Into<String>) -> Self {
+        Self::new(name, Hazard::Smoke)
+    }
+
+    /// Creates a carbon-monoxide detector with the given name, initially clear.
+    pub fn carbon_monoxide(name: impl Into<String>) -> Self {
+        Self::new(name, Hazard::CarbonMonoxide)
+    }
+
+    fn new(name: impl Into<String>, hazard: Hazard) -> Self {
+        Self {
+            name: name.into(),
+            hazard,
+            level: AlarmLevel::Clear,
+            silenced_for: None,
+        }
+    }
+
+    /// Returns the hazard this detector senses.
+    pub fn hazard(&self) -> Hazard {
+        self.hazard
+    }
+
+    /// Returns the current hazard level.
+    pub fn level(&self) -> AlarmLevel {
+        self.level
+    }
+
+    /// Returns `true` while the detector is hushed by `silence`.
+    pub fn is_silenced(&self) -> bool {
+        self.silenced_for.is_some()
+    }
+
+    fn level_event_type(&self) -> EventType {
+        match self.level {
+            AlarmLevel::Clear => EventType::AlarmCleared,
+            AlarmLevel::Warning => EventType::AlarmWarning,
+            AlarmLevel::Alarm => EventType::AlarmTriggered,
+        }
+    }
+
+    fn invalid_transition(&self, command: &Command) -> PulseHomeError {
+        PulseHomeError::InvalidTransition {
+            device: self.name.clone(),
+            state: self.level.to_string(),
+            command: command.name().to_string(),
+        }
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for SafetyAlarm {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        match self.hazard {
+            Hazard::Smoke => "SmokeAlarm",
+            Hazard::CarbonMonoxide => "CoAlarm",
+        }
+    }
+
+    /// Runs a self-test (only while clear) or silences a sounding detector.
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match command {
+            Command::Test if self.level == AlarmLevel::Clear => {}
+            Command::Silence if self.level != AlarmLevel::Clear && !self.is_silenced() => {
+                self.silenced_for = Some(SILENCE_DURATION);
+            }
+            Command::Test | Command::Silence => return Err(self.invalid_transition(&command)),
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let (reading, attribute) = match self.hazard {
+            Hazard::Smoke => ("smoke", "smoke"),
+            Hazard::CarbonMonoxide => ("co", "co"),
+        };
+        Capabilities::new()
+            .command(CommandSpec::new(
+                "test",
+                "Run a self-test (only while clear)",
+            ))
+            .command(CommandSpec::new(
+                "silence",
+                "Silence a sounding alarm for 10 minutes",
+            ))
+            .reading(reading)
+            .attribute(
+                attribute,
+                AttributeKind::Enum(&["clear", "warning", "alarm"]),
+            )
+            .attribute("silenced", AttributeKind::Bool)
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let attribute = match self.hazard {
+            Hazard::Smoke => "smoke",
+            Hazard::CarbonMonoxide => "co",
+        };
+        let mut state =
+            DeviceState::new().with(attribute, AttributeValue::Enum(self.level.to_string()));
+        if self.is_silenced() {
+            state.set("silenced", AttributeValue::Bool(true));
+        }
+        state
+    }
+
+    /// Accepts the hazard level for this detector's hazard.
+    ///
+    /// A rise in level cancels any silence so the detector sounds again.
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        let level = match (self.hazard, reading) {
+            (Hazard::Smoke, SensorReading::Smoke(level))
+            | (Hazard::CarbonMonoxide, SensorReading::CarbonMonoxide(level)) => level,
+            _ => {
+                return Err(PulseHomeError::unsupported_reading(
+                    self.get_type(),
+                    &reading,
+                ));
+            }
+        };
+        if level == self.level {
+            return Ok(None);
+        }
+        if level > self.level || level == AlarmLevel::Clear {
+            self.silenced_for = None;
+        }
+        self.level = level;
+        Ok(Some(self.event(self.level_event_type())))
+    }
+
+    /// Counts down a silence; if the hazard is still present, sounds again.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let Some(remaining) = self.silenced_for else {
+            return Vec::new();
+        };
+        if remaining > elapsed {
+            self.silenced_for = Some(remaining - elapsed);
+            return Vec::new();
+        }
+        self.silenced_for = None;
+        vec![self.event(self.level_event_type())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::EventPriority;
+
+    #[test]
+    fn alarm_escalates_and_clears() {
+        let mut detector = SafetyAlarm::carbon_monoxide("Garage CO");
+        assert_eq!(detector.get_type(), "CoAlarm");
+
+        let event = detector
+            .report_reading(SensorReading::CarbonMonoxide(AlarmLevel::Warning))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AlarmWarning);
+        assert_eq!(event.priority(), EventPriority::High);
+        assert_eq!(detector.get_state().to_string(), "warning");
+
+        let event = detector
+            .report_reading(SensorReading::CarbonMonoxide(AlarmLevel::Alarm))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AlarmTriggered);
+        assert!(
+            detector
+                .report_reading(SensorReading::CarbonMonoxide(AlarmLevel::Alarm))
+                .unwrap()
+                .is_none()
+        );
+
+        let event = detector
+            .report_reading(SensorReading::CarbonMonoxide(AlarmLevel::Clear))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AlarmCleared);
+        assert_eq!(event.priority(), EventPriority::Normal);
+    }
+
+    #[test]
+    fn alarm_rejects_other_hazards() {
+        let mut detector = SafetyAlarm::smoke("Hall Smoke");
+        assert!(matches!(
+            detector.report_reading(SensorReading::CarbonMonoxide(AlarmLevel::Alarm)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+        assert!(matches!(
+            detector.execute_command(Command::TurnOn),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn alarm_self_test_only_while_clear() {
+        let mut detector = SafetyAlarm::smoke("Hall Smoke");
+        let event = detector.execute_command(Command::Test).unwrap();
+        assert_eq!(event.event_type, EventType::AlarmTest);
+
+        detector
+            .report_reading(SensorReading::Smoke(AlarmLevel::Alarm))
+            .unwrap();
+        assert!(matches!(
+            detector.execute_command(Command::Test),
+            Err(PulseHomeError::InvalidTransition { .. })
+        ));
+    }
+
+    #[test]
+    fn silence_expires_while_hazard_persists() {
+        let mut detector = SafetyAlarm::smoke("Hall Smoke");
+        assert!(detector.execute_command(Command::Silence).is_err());
+
+        detector
+            .report_reading(SensorReading::Smoke(AlarmLevel::Alarm))
+            .unwrap();
+        let event = detector.execute_command(Command::Silence).unwrap();
+        assert_eq!(event.event_type, EventType::AlarmSilenced);
+        assert_eq!(detector.get_state().to_string(), "alarm (silenced=on)");
+        assert!(detector.execute_command(Command::Silence).is_err());
+
+        assert!(detector.tick(Duration::from_secs(60)).is_empty());
+        let events = detector.tick(SILENCE_DURATION);
+        assert_eq!(events[0].event_type, EventType::AlarmTriggered);
+        assert!(!detector.is_silenced());
+    }
+
+    #[test]
+    fn escalation_cancels_silence() {
+        let mut detector = SafetyAlarm::smoke("Hall Smoke");
+        detector
+            .report_reading(SensorReading::Smoke(AlarmLevel::Warning))
+            .unwrap();
+        detector.execute_command(Command::Silence).unwrap();
+
+        let event = detector
+            .report_reading(SensorReading::Smoke(AlarmLevel::Alarm))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AlarmTriggered);
+        assert!(!detector.is_silenced());
+    }
+
+    #[test]
+    fn alarm_level_parsing() {
+        assert_eq!(
+            "Warning".parse::<AlarmLevel>().unwrap(),
+            AlarmLevel::Warning
+        );
+        assert!("smoky".parse::<AlarmLevel>().is_err());
+    }
+}
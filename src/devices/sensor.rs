@@ -0,0 +1,413 @@
+//! # Sensor Devices
+//!
+//! This module defines the `Sensor` struct, a read-only [`Device`] that
+//! reports measurements instead of reacting to commands.
+//!
+//! Readings are pushed in through [`Device::report_reading`] (usually via
+//! [`HomeHub::report_reading`](crate::mediator::HomeHub::report_reading)). A
+//! sensor only raises an [`Event`] when a reading actually changes, so
+//! repeated identical reports stay quiet. Motion sensors also clear
+//! themselves on their own once no motion has been seen for their occupancy
+//! timeout.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::sensor::Sensor;
+//! use pulsehome::models::reading::SensorReading;
+//! use pulsehome::models::device::Device;
+//! use pulsehome::models::event::EventType;
+//!
+//! let mut sensor = Sensor::contact("Back Window");
+//! let event = sensor.report_reading(SensorReading::Contact { open: true }).unwrap();
+//! assert_eq!(event.unwrap().event_type, EventType::ContactOpened);
+//!
+//! // Same reading again: nothing changed, so no event.
+//! assert!(sensor.report_reading(SensorReading::Contact { open: true }).unwrap().is_none());
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{AttributeKind, Capabilities},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    reading::SensorReading,
+    state::{AttributeValue, DeviceState, Unit},
+    temperature::Temperature,
+};
+use std::time::Duration;
+
+/// How long a motion sensor stays occupied after the last detected motion.
+pub const DEFAULT_OCCUPANCY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The kind of measurement a sensor takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorKind {
+    /// Motion / occupancy sensor.
+    Motion,
+    /// Door or window contact sensor.
+    Contact,
+    /// Combined temperature and humidity sensor.
+    Climate,
+    /// Light level sensor.
+    Illuminance,
+}
+
+/// Represents a read-only sensor device.
+#[derive(Debug)]
+pub struct Sensor {
+    name: String,
+    kind: SensorKind,
+    motion: Option<bool>,
+    open: Option<bool>,
+    temperature: Option<Temperature>,
+    humidity: Option<f64>,
+    illuminance: Option<f64>,
+    occupancy_timeout: Duration,
+    clear_in: Option<Duration>, // time left before motion clears
+}
+
+impl Sensor {
+    /// Creates a sensor of the given kind with no readings yet.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::sensor::{Sensor, SensorKind};
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let sensor = Sensor::new("Hall Motion", SensorKind::Motion);
+    /// assert_eq!(sensor.get_type(), "MotionSensor");
+    /// assert_eq!(sensor.get_state().to_string(), "unknown");
+    /// ```
+    pub fn new(name: impl Into<String>, kind: SensorKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            motion: None,
+            open: None,
+            temperature: None,
+            humidity: None,
+            illuminance: None,
+            occupancy_timeout: DEFAULT_OCCUPANCY_TIMEOUT,
+            clear_in: None,
+        }
+    }
+
+    /// Creates a motion sensor.
+    pub fn motion(name: impl Into<String>) -> Self {
+        Self::new(name, SensorKind::Motion)
+    }
+
+    /// Creates a door/window contact sensor.
+    pub fn contact(name: impl Into<String>) -> Self {
+        Self::new(name, SensorKind::Contact)
+    }
+
+    /// Creates a temperature and humidity sensor.
+    pub fn climate(name: impl Into<String>) -> Self {
+        Self::new(name, SensorKind::Climate)
+    }
+
+    /// Creates a light level sensor.
+    pub fn illuminance(name: impl Into<String>) -> Self {
+        Self::new(name, SensorKind::Illuminance)
+    }
+
+    /// Returns the sensor with a different occupancy timeout (motion sensors only).
+    pub fn with_occupancy_timeout(mut self, timeout: Duration) -> Self {
+        self.occupancy_timeout = timeout;
+        self
+    }
+
+    /// Returns the kind of sensor.
+    pub fn kind(&self) -> SensorKind {
+        self.kind
+    }
+
+    /// Returns whether this sensor kind produces the given reading.
+    fn accepts(&self, reading: &SensorReading) -> bool {
+        matches!(
+            (self.kind, reading),
+            (SensorKind::Motion, SensorReading::Motion(_))
+                | (SensorKind::Contact, SensorReading::Contact { .. })
+                | (SensorKind::Climate, SensorReading::Temperature(_))
+                | (SensorKind::Climate, SensorReading::Humidity(_))
+                | (SensorKind::Illuminance, SensorReading::Illuminance(_))
+        )
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for Sensor {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        match self.kind {
+            SensorKind::Motion => "MotionSensor",
+            SensorKind::Contact => "ContactSensor",
+            SensorKind::Climate => "ClimateSensor",
+            SensorKind::Illuminance => "LightSensor",
+        }
+    }
+
+    /// Sensors are read-only and reject every command.
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        Err(PulseHomeError::unsupported(self.get_type(), &command))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        match self.kind {
+            SensorKind::Motion => Capabilities::new()
+                .reading("motion")
+                .attribute("motion", AttributeKind::Enum(&["detected", "clear"])),
+            SensorKind::Contact => Capabilities::new()
+                .reading("contact")
+                .attribute("contact", AttributeKind::Enum(&["open", "closed"])),
+            SensorKind::Climate => Capabilities::new()
+                .reading("temperature")
+                .reading("humidity")
+                .attribute("temperature", AttributeKind::Temperature)
+                .attribute("humidity", AttributeKind::Measure(Unit::Percent)),
+            SensorKind::Illuminance => Capabilities::new()
+                .reading("illuminance")
+                .attribute("illuminance", AttributeKind::Measure(Unit::Lux)),
+        }
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let mut state = DeviceState::new();
+        if let Some(motion) = self.motion {
+            let motion = if motion { "detected" } else { "clear" };
+            state.set("motion", AttributeValue::Enum(motion.to_string()));
+        }
+        if let Some(open) = self.open {
+            let contact = if open { "open" } else { "closed" };
+            state.set("contact", AttributeValue::Enum(contact.to_string()));
+        }
+        if let Some(temperature) = self.temperature {
+            state.set("temperature", temperature.to_attribute());
+        }
+        if let Some(humidity) = self.humidity {
+            state.set("humidity", AttributeValue::Measure(humidity, Unit::Percent));
+        }
+        if let Some(lux) = self.illuminance {
+            state.set("illuminance", AttributeValue::Measure(lux, Unit::Lux));
+        }
+        state
+    }
+
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        if !self.accepts(&reading) {
+            return Err(PulseHomeError::unsupported_reading(
+                self.get_type(),
+                &reading,
+            ));
+        }
+
+        let event_type = match reading {
+            SensorReading::Motion(detected) => {
+                // Fresh motion restarts the occupancy timer even if already occupied.
+                self.clear_in = detected.then_some(self.occupancy_timeout);
+                if self.motion.replace(detected) == Some(detected) {
+                    return Ok(None);
+                }
+                if detected {
+                    EventType::MotionDetected
+                } else {
+                    EventType::MotionCleared
+                }
+            }
+            SensorReading::Contact { open } => {
+                if self.open.replace(open) == Some(open) {
+                    return Ok(None);
+                }
+                if open {
+                    EventType::ContactOpened
+                } else {
+                    EventType::ContactClosed
+                }
+            }
+            SensorReading::Temperature(temperature) => {
+                if !temperature.celsius().is_finite() {
+                    return Err(PulseHomeError::InvalidArgument(
+                        "temperature must be a finite number".to_string(),
+                    ));
+                }
+                if self.temperature.replace(temperature) == Some(temperature) {
+                    return Ok(None);
+                }
+                EventType::TemperatureChanged
+            }
+            SensorReading::Humidity(humidity) => {
+                if !(0.0..=100.0).contains(&humidity) {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "humidity {}% is outside 0–100%",
+                        humidity
+                    )));
+                }
+                if self.humidity.replace(humidity) == Some(humidity) {
+                    return Ok(None);
+                }
+                EventType::HumidityChanged
+            }
+            SensorReading::Illuminance(lux) => {
+                if !lux.is_finite() || lux < 0.0 {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "illuminance {} lx must not be negative",
+                        lux
+                    )));
+                }
+                if self.illuminance.replace(lux) == Some(lux) {
+                    return Ok(None);
+                }
+                EventType::IlluminanceChanged
+            }
+            SensorReading::Power(_)
+            | SensorReading::Voltage(_)
+            | SensorReading::Energy(_)
+            | SensorReading::Obstruction(_)
+            | SensorReading::Smoke(_)
+            | SensorReading::CarbonMonoxide(_) => {
+                unreachable!("device readings are rejected by accepts()")
+            }
+        };
+        Ok(Some(self.event(event_type)))
+    }
+
+    /// Clears motion once the occupancy timeout passes without new motion.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let Some(remaining) = self.clear_in else {
+            return Vec::new();
+        };
+        let remaining = remaining.saturating_sub(elapsed);
+        if !remaining.is_zero() {
+            self.clear_in = Some(remaining);
+            return Vec::new();
+        }
+        self.clear_in = None;
+        self.motion = Some(false);
+        vec![self.event(EventType::MotionCleared)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensor_types_and_initial_state() {
+        assert_eq!(Sensor::motion("M").get_type(), "MotionSensor");
+        assert_eq!(Sensor::contact("C").get_type(), "ContactSensor");
+        assert_eq!(Sensor::climate("T").get_type(), "ClimateSensor");
+        assert_eq!(Sensor::illuminance("L").get_type(), "LightSensor");
+        assert!(Sensor::climate("T").get_state().is_empty());
+    }
+
+    #[test]
+    fn sensor_rejects_commands_and_foreign_readings() {
+        let mut sensor = Sensor::contact("Back Door");
+        assert!(matches!(
+            sensor.execute_command(Command::TurnOn),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+        assert!(matches!(
+            sensor.report_reading(SensorReading::Humidity(40.0)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn sensor_emits_only_on_change() {
+        let mut sensor = Sensor::climate("Bathroom");
+        let event = sensor
+            .report_reading(SensorReading::Temperature(Temperature::from_celsius(21.5)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::TemperatureChanged);
+        assert!(
+            sensor
+                .report_reading(SensorReading::Temperature(Temperature::from_celsius(21.5)))
+                .unwrap()
+                .is_none()
+        );
+
+        let event = sensor
+            .report_reading(SensorReading::Humidity(55.0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::HumidityChanged);
+        assert_eq!(sensor.get_state().to_string(), "21.5°C (humidity=55%)");
+        assert!(
+            sensor
+                .report_reading(SensorReading::Humidity(120.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sensor_contact_and_illuminance() {
+        let mut contact = Sensor::contact("Back Door");
+        let opened = contact
+            .report_reading(SensorReading::Contact { open: true })
+            .unwrap()
+            .unwrap();
+        assert_eq!(opened.event_type, EventType::ContactOpened);
+        let closed = contact
+            .report_reading(SensorReading::Contact { open: false })
+            .unwrap()
+            .unwrap();
+        assert_eq!(closed.event_type, EventType::ContactClosed);
+        assert_eq!(contact.get_state().to_string(), "closed");
+
+        let mut light = Sensor::illuminance("Porch Lux");
+        light
+            .report_reading(SensorReading::Illuminance(320.0))
+            .unwrap();
+        assert_eq!(light.get_state().to_string(), "320 lx");
+        assert!(
+            light
+                .report_reading(SensorReading::Illuminance(-1.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn sensor_motion_clears_after_timeout() {
+        let mut sensor = Sensor::motion("Hall").with_occupancy_timeout(Duration::from_secs(30));
+        let event = sensor
+            .report_reading(SensorReading::Motion(true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::MotionDetected);
+
+        assert!(sensor.tick(Duration::from_secs(20)).is_empty());
+        // Fresh motion restarts the timer without a new event.
+        assert!(
+            sensor
+                .report_reading(SensorReading::Motion(true))
+                .unwrap()
+                .is_none()
+        );
+        assert!(sensor.tick(Duration::from_secs(20)).is_empty());
+
+        let events = sensor.tick(Duration::from_secs(10));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::MotionCleared);
+        assert_eq!(sensor.get_state().to_string(), "clear");
+        assert!(sensor.tick(Duration::from_secs(60)).is_empty());
+    }
+}
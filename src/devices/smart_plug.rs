@@ -0,0 +1,263 @@
+//! # Smart Plug Device
+//!
+//! This module defines the `SmartPlug` struct, a concrete implementation of the
+//! [`Device`] trait for metering smart plugs and in-wall switches.
+//!
+//! A `SmartPlug` switches its outlet on or off like a [`Light`](crate::devices::light::Light)
+//! and meters the appliance plugged into it: instantaneous power (W), supply
+//! voltage (V) and a cumulative energy counter (kWh). The appliance's draw and
+//! the supply voltage are reported as [`SensorReading`]s; while the outlet is on,
+//! the energy counter advances as the hub calls [`Device::tick`].
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::reading::SensorReading;
+//! use pulsehome::devices::smart_plug::SmartPlug;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//! use std::time::Duration;
+//!
+//! let mut kettle = SmartPlug::new("Kettle");
+//! kettle.report_reading(SensorReading::Power(2000.0)).unwrap();
+//! kettle.execute_command(Command::TurnOn).unwrap();
+//! kettle.tick(Duration::from_secs(90));
+//!
+//! assert_eq!(kettle.meter().unwrap().energy_kwh, 0.05);
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    energy::MeterReading,
+    event::{Event, EventType},
+    reading::SensorReading,
+    state::{AttributeValue, DeviceState, Unit},
+};
+use std::time::Duration;
+
+/// Supply voltage assumed until the plug reports one.
+pub const DEFAULT_VOLTAGE: f64 = 230.0;
+
+/// Represents a metering smart plug or switch.
+#[derive(Debug)]
+pub struct SmartPlug {
+    name: String,
+    on: bool,
+    load_w: f64, // draw of the attached appliance while the outlet is on
+    voltage: f64,
+    energy_kwh: f64,
+}
+
+impl SmartPlug {
+    /// Creates a new `SmartPlug` with the given name, initially off with no load.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::smart_plug::SmartPlug;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let plug = SmartPlug::new("Desk Lamp");
+    /// assert_eq!(
+    ///     plug.get_state().to_string(),
+    ///     "off (power_usage=0 W, energy=0 kWh, voltage=230 V)"
+    /// );
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            on: false,
+            load_w: 0.0,
+            voltage: DEFAULT_VOLTAGE,
+            energy_kwh: 0.0,
+        }
+    }
+
+    /// Returns the instantaneous power drawn through the outlet in watts.
+    pub fn power_w(&self) -> f64 {
+        if self.on { self.load_w } else { 0.0 }
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+impl Device for SmartPlug {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        "SmartPlug"
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match command {
+            Command::TurnOn => self.on = true,
+            Command::TurnOff => self.on = false,
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(CommandSpec::new("turn_on", "Switch the outlet on"))
+            .command(CommandSpec::new("turn_off", "Switch the outlet off"))
+            .reading("power")
+            .reading("voltage")
+            .reading("energy")
+            .attribute("power", AttributeKind::Bool)
+            .attribute("power_usage", AttributeKind::Measure(Unit::Watt))
+            .attribute("energy", AttributeKind::Measure(Unit::KilowattHour))
+            .attribute("voltage", AttributeKind::Measure(Unit::Volt))
+    }
+
+    fn get_state(&self) -> DeviceState {
+        DeviceState::new()
+            .with("power", AttributeValue::Bool(self.on))
+            .with(
+                "power_usage",
+                AttributeValue::Measure(self.power_w(), Unit::Watt),
+            )
+            .with(
+                "energy",
+                AttributeValue::Measure(self.energy_kwh, Unit::KilowattHour),
+            )
+            .with("voltage", AttributeValue::Measure(self.voltage, Unit::Volt))
+    }
+
+    /// Accepts the appliance's power draw, the supply voltage and the
+    /// plug's own energy counter.
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        let (event_type, changed) = match reading {
+            SensorReading::Power(watts) => {
+                if !watts.is_finite() || watts < 0.0 {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "power {} W must not be negative",
+                        watts
+                    )));
+                }
+                let changed = self.load_w != watts;
+                self.load_w = watts;
+                (EventType::PowerChanged, changed)
+            }
+            SensorReading::Voltage(volts) => {
+                if !volts.is_finite() || volts <= 0.0 {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "voltage {} V must be positive",
+                        volts
+                    )));
+                }
+                let changed = self.voltage != volts;
+                self.voltage = volts;
+                (EventType::VoltageChanged, changed)
+            }
+            SensorReading::Energy(kwh) => {
+                if !kwh.is_finite() || kwh < self.energy_kwh {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "energy counter {} kWh is below the current {} kWh",
+                        kwh, self.energy_kwh
+                    )));
+                }
+                let changed = self.energy_kwh != kwh;
+                self.energy_kwh = kwh;
+                (EventType::EnergyChanged, changed)
+            }
+            _ => {
+                return Err(PulseHomeError::unsupported_reading(
+                    self.get_type(),
+                    &reading,
+                ));
+            }
+        };
+        Ok(changed.then(|| self.event(event_type)))
+    }
+
+    /// Advances the energy counter by the power drawn over `elapsed`.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        self.energy_kwh += self.power_w() * elapsed.as_secs_f64() / 3_600_000.0;
+        Vec::new()
+    }
+
+    fn meter(&self) -> Option<MeterReading> {
+        Some(MeterReading {
+            power_w: self.power_w(),
+            energy_kwh: self.energy_kwh,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plug_turn_on_off() {
+        let mut plug = SmartPlug::new("Heater");
+        assert_eq!(plug.get_type(), "SmartPlug");
+        let event = plug.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(event.event_type, EventType::TurnOn);
+        assert_eq!(
+            plug.get_state().get("power"),
+            Some(&AttributeValue::Bool(true))
+        );
+        assert!(matches!(
+            plug.execute_command(Command::Lock),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn plug_meters_only_while_on() {
+        let mut plug = SmartPlug::new("Heater");
+        plug.report_reading(SensorReading::Power(1000.0)).unwrap();
+        assert_eq!(plug.power_w(), 0.0);
+        plug.tick(Duration::from_secs(3600));
+        assert_eq!(plug.meter().unwrap().energy_kwh, 0.0);
+
+        plug.execute_command(Command::TurnOn).unwrap();
+        plug.tick(Duration::from_secs(1800));
+        let meter = plug.meter().unwrap();
+        assert_eq!(meter.power_w, 1000.0);
+        assert_eq!(meter.energy_kwh, 0.5);
+        assert_eq!(
+            plug.get_state().to_string(),
+            "on (power_usage=1000 W, energy=0.5 kWh, voltage=230 V)"
+        );
+    }
+
+    #[test]
+    fn plug_readings() {
+        let mut plug = SmartPlug::new("Fridge");
+        let event = plug
+            .report_reading(SensorReading::Voltage(120.0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::VoltageChanged);
+        assert!(
+            plug.report_reading(SensorReading::Voltage(120.0))
+                .unwrap()
+                .is_none()
+        );
+
+        plug.report_reading(SensorReading::Energy(12.5)).unwrap();
+        assert!(plug.report_reading(SensorReading::Energy(3.0)).is_err());
+        assert!(plug.report_reading(SensorReading::Power(-5.0)).is_err());
+        assert!(matches!(
+            plug.report_reading(SensorReading::Motion(true)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+}
@@ -3,37 +3,168 @@
 //! This module defines the `Thermostat` struct, a concrete implementation of the
 //! [`Device`] trait for smart thermostats in the PulseHome system.
 //!
-//! The `Thermostat` can set a temperature and generate an [`Event`] for each change.
+//! A `Thermostat` runs in an [`HvacMode`] with separate heating and cooling
+//! setpoints. Given the measured ambient temperature, it decides its current
+//! [`HvacAction`] (heating, cooling, idle...) and raises an extra
+//! [`EventType::HvacActionChanged`] event whenever that action changes.
 
+use crate::error::PulseHomeError;
 use crate::models::{
+    argument::HvacMode,
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
     device::Device,
     event::{Event, EventType},
+    reading::SensorReading,
+    state::{AttributeValue, DeviceState},
+    temperature::Temperature,
 };
+use std::fmt;
+
+/// Lowest target temperature a thermostat accepts (5°C).
+pub const MIN_TEMP: Temperature = Temperature::from_celsius(5.0);
+
+/// Highest target temperature a thermostat accepts (35°C).
+pub const MAX_TEMP: Temperature = Temperature::from_celsius(35.0);
+
+/// What a thermostat is currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HvacAction {
+    Off,
+    Idle,
+    Heating,
+    Cooling,
+    Fan,
+}
+
+impl fmt::Display for HvacAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HvacAction::Off => "off",
+            HvacAction::Idle => "idle",
+            HvacAction::Heating => "heating",
+            HvacAction::Cooling => "cooling",
+            HvacAction::Fan => "fan",
+        };
+        write!(f, "{}", name)
+    }
+}
 
 /// Represents a smart thermostat device.
 #[derive(Debug)]
 pub struct Thermostat {
     name: String,
-    temperature: i32, // current temperature in Celsius
+    mode: HvacMode,
+    heat_setpoint: Temperature,
+    cool_setpoint: Temperature,
+    ambient: Option<Temperature>, // last measured temperature
+    action: HvacAction,
+    pending: Vec<Event>,
 }
 
 impl Thermostat {
-    /// Creates a new `Thermostat` with the given name and initial temperature.
+    /// Creates a new `Thermostat` in heat mode with both setpoints at the initial temperature.
     ///
     /// # Example
     /// ```
+    /// use pulsehome::models::device::Device;
+    /// use pulsehome::models::temperature::Temperature;
     /// use pulsehome::devices::thermostat::Thermostat;
     ///
-    /// let thermo = Thermostat::new("Bedroom Thermostat", 22);
+    /// let thermo = Thermostat::new("Bedroom Thermostat", Temperature::from_celsius(22.0));
     /// assert_eq!(thermo.get_name(), "Bedroom Thermostat");
-    /// assert_eq!(thermo.get_state(), "22°C");
+    /// assert_eq!(
+    ///     thermo.get_state().to_string(),
+    ///     "heat (heat_setpoint=22°C, cool_setpoint=22°C, action=idle)"
+    /// );
     /// ```
-    pub fn new(name: impl Into<String>, initial_temp: i32) -> Self {
+    pub fn new(name: impl Into<String>, initial_temp: Temperature) -> Self {
         Self {
             name: name.into(),
-            temperature: initial_temp,
+            mode: HvacMode::Heat,
+            heat_setpoint: initial_temp,
+            cool_setpoint: initial_temp,
+            ambient: None,
+            action: HvacAction::Idle,
+            pending: Vec::new(),
         }
     }
+
+    /// Creates a new `Thermostat` like [`Thermostat::new`], checking that the
+    /// initial temperature is within [`MIN_TEMP`] and [`MAX_TEMP`].
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if the temperature is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::temperature::Temperature;
+    /// use pulsehome::devices::thermostat::Thermostat;
+    ///
+    /// assert!(Thermostat::try_new("Hall", Temperature::from_celsius(21.0)).is_ok());
+    /// assert!(Thermostat::try_new("Hall", Temperature::from_celsius(99.0)).is_err());
+    /// ```
+    pub fn try_new(
+        name: impl Into<String>,
+        initial_temp: Temperature,
+    ) -> Result<Self, PulseHomeError> {
+        Ok(Self::new(name, Self::validate(initial_temp)?))
+    }
+
+    /// Returns the current operating mode.
+    pub fn mode(&self) -> HvacMode {
+        self.mode
+    }
+
+    /// Returns what the thermostat is currently doing.
+    pub fn action(&self) -> HvacAction {
+        self.action
+    }
+
+    fn validate(temp: Temperature) -> Result<Temperature, PulseHomeError> {
+        if (MIN_TEMP..=MAX_TEMP).contains(&temp) {
+            Ok(temp)
+        } else {
+            Err(PulseHomeError::InvalidArgument(format!(
+                "temperature {} is out of range ({} to {})",
+                temp, MIN_TEMP, MAX_TEMP
+            )))
+        }
+    }
+
+    /// Determines the running action from the mode, setpoints and ambient temperature.
+    fn compute_action(&self) -> HvacAction {
+        match (self.mode, self.ambient) {
+            (HvacMode::Off, _) => HvacAction::Off,
+            (HvacMode::FanOnly, _) => HvacAction::Fan,
+            (_, None) => HvacAction::Idle,
+            (HvacMode::Heat | HvacMode::Auto, Some(t)) if t < self.heat_setpoint => {
+                HvacAction::Heating
+            }
+            (HvacMode::Cool | HvacMode::Auto, Some(t)) if t > self.cool_setpoint => {
+                HvacAction::Cooling
+            }
+            _ => HvacAction::Idle,
+        }
+    }
+
+    /// Recomputes the running action and queues an event if it changed.
+    fn update_action(&mut self) {
+        let action = self.compute_action();
+        if action != self.action {
+            self.action = action;
+            self.pending.push(self.event(EventType::HvacActionChanged));
+        }
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
 }
 
 impl Device for Thermostat {
@@ -41,58 +172,309 @@ impl Device for Thermostat {
         &self.name
     }
 
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
     fn get_type(&self) -> &str {
         "Thermostat"
     }
 
-    fn execute_command(&mut self, command: EventType) -> Result<Event, Box<dyn std::error::Error>> {
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
         match command {
-            EventType::SetTemp => {
-                self.temperature += 1;
+            Command::SetTemp(target) => {
+                let target = Self::validate(target)?;
+                // The other setpoint moves along if needed to keep heat <= cool
+                match self.mode {
+                    HvacMode::Heat => {
+                        self.heat_setpoint = target;
+                        if self.cool_setpoint < target {
+                            self.cool_setpoint = target;
+                        }
+                    }
+                    HvacMode::Cool => {
+                        self.cool_setpoint = target;
+                        if self.heat_setpoint > target {
+                            self.heat_setpoint = target;
+                        }
+                    }
+                    _ => {
+                        self.heat_setpoint = target;
+                        self.cool_setpoint = target;
+                    }
+                }
+            }
+            Command::SetHvacMode(mode) => self.mode = mode,
+            Command::SetSetpoints { heat, cool } => {
+                let (heat, cool) = (Self::validate(heat)?, Self::validate(cool)?);
+                if heat > cool {
+                    return Err(PulseHomeError::InvalidArgument(format!(
+                        "heating setpoint {} is above cooling setpoint {}",
+                        heat, cool
+                    )));
+                }
+                self.heat_setpoint = heat;
+                self.cool_setpoint = cool;
             }
-            _ => return Err("Thermostat only supports SetTemp commands".into()),
+            Command::ReportAmbient(temp) => self.ambient = Some(temp),
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
         }
 
-        Ok(Event::new(
-            self.name.clone(),
-            self.get_type().to_string(),
-            command,
-            Some(self.get_state()),
-        ))
+        let event = self.event(command.event_type());
+        self.update_action();
+        Ok(event)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(
+                CommandSpec::new("set_temp", "Set the target temperature")
+                    .arg("target", ArgKind::Temperature),
+            )
+            .command(CommandSpec::new("mode", "Set the HVAC mode").arg(
+                "mode",
+                ArgKind::Choice(&["off", "heat", "cool", "auto", "fan_only"]),
+            ))
+            .command(
+                CommandSpec::new("setpoints", "Set the heating and cooling setpoints")
+                    .arg("heat", ArgKind::Temperature)
+                    .arg("cool", ArgKind::Temperature),
+            )
+            .command(
+                CommandSpec::new("ambient", "Report the measured room temperature")
+                    .arg("temperature", ArgKind::Temperature),
+            )
+            .reading("temperature")
+            .attribute(
+                "mode",
+                AttributeKind::Enum(&["off", "heat", "cool", "auto", "fan_only"]),
+            )
+            .attribute("heat_setpoint", AttributeKind::Temperature)
+            .attribute("cool_setpoint", AttributeKind::Temperature)
+            .attribute("ambient_temperature", AttributeKind::Temperature)
+            .attribute(
+                "action",
+                AttributeKind::Enum(&["off", "idle", "heating", "cooling", "fan"]),
+            )
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let mut state = DeviceState::new()
+            .with("mode", AttributeValue::Enum(self.mode.to_string()))
+            .with("heat_setpoint", self.heat_setpoint.to_attribute())
+            .with("cool_setpoint", self.cool_setpoint.to_attribute());
+        if let Some(ambient) = self.ambient {
+            state.set("ambient_temperature", ambient.to_attribute());
+        }
+        state.with("action", AttributeValue::Enum(self.action.to_string()))
+    }
+
+    /// Accepts temperature readings from the built-in sensor as ambient updates.
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        match reading {
+            SensorReading::Temperature(temp) if self.ambient == Some(temp) => Ok(None),
+            SensorReading::Temperature(temp) => {
+                self.execute_command(Command::ReportAmbient(temp)).map(Some)
+            }
+            _ => Err(PulseHomeError::unsupported_reading(
+                self.get_type(),
+                &reading,
+            )),
+        }
     }
 
-    fn get_state(&self) -> String {
-        format!("{}°C", self.temperature)
+    fn take_pending_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.pending)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::event::EventType;
+    use crate::models::state::Unit;
+
+    fn celsius(value: f64) -> Temperature {
+        Temperature::from_celsius(value)
+    }
 
     #[test]
     fn thermostat_initial_temperature() {
-        let thermo = Thermostat::new("Bedroom Thermostat", 22);
+        let thermo = Thermostat::new("Bedroom Thermostat", celsius(22.0));
         assert_eq!(thermo.get_name(), "Bedroom Thermostat");
         assert_eq!(thermo.get_type(), "Thermostat");
-        assert_eq!(thermo.get_state(), "22°C");
+        assert_eq!(thermo.mode(), HvacMode::Heat);
+        assert_eq!(
+            thermo.get_state().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(22.0, Unit::Celsius))
+        );
     }
 
     #[test]
     fn thermostat_set_temperature() {
-        let mut thermo = Thermostat::new("Living Room Thermostat", 20);
-        let event = thermo.execute_command(EventType::SetTemp).unwrap();
-        assert_eq!(thermo.get_state(), "21°C");
+        let mut thermo = Thermostat::new("Living Room Thermostat", celsius(20.0));
+        let event = thermo
+            .execute_command(Command::SetTemp(celsius(24.0)))
+            .unwrap();
         assert_eq!(event.device_name, "Living Room Thermostat");
         assert_eq!(event.event_type, EventType::SetTemp);
-        assert_eq!(event.payload.unwrap(), "21°C");
+        assert_eq!(
+            event.payload.unwrap().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(24.0, Unit::Celsius))
+        );
+    }
+
+    #[test]
+    fn thermostat_set_temperature_follows_mode() {
+        let mut thermo = Thermostat::new("Hall", celsius(20.0));
+        thermo
+            .execute_command(Command::SetHvacMode(HvacMode::Cool))
+            .unwrap();
+        thermo
+            .execute_command(Command::SetTemp(celsius(25.0)))
+            .unwrap();
+        assert_eq!(
+            thermo.get_state().to_string(),
+            "cool (heat_setpoint=20°C, cool_setpoint=25°C, action=idle)"
+        );
+    }
+
+    #[test]
+    fn thermostat_set_temperature_keeps_setpoints_ordered() {
+        let mut thermo = Thermostat::new("Hall", celsius(22.0));
+        thermo
+            .execute_command(Command::SetTemp(celsius(30.0)))
+            .unwrap();
+        thermo
+            .execute_command(Command::SetHvacMode(HvacMode::Auto))
+            .unwrap();
+        assert_eq!(
+            thermo.get_state().to_string(),
+            "auto (heat_setpoint=30°C, cool_setpoint=30°C, action=idle)"
+        );
+
+        thermo
+            .execute_command(Command::SetHvacMode(HvacMode::Cool))
+            .unwrap();
+        thermo
+            .execute_command(Command::SetTemp(celsius(18.0)))
+            .unwrap();
+        assert_eq!(
+            thermo.get_state().to_string(),
+            "cool (heat_setpoint=18°C, cool_setpoint=18°C, action=idle)"
+        );
+    }
+
+    #[test]
+    fn thermostat_rejects_out_of_range_temperature() {
+        let mut thermo = Thermostat::new("Hall Thermostat", celsius(20.0));
+        let result = thermo.execute_command(Command::SetTemp(celsius(35.5)));
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+        assert!(
+            thermo
+                .execute_command(Command::SetTemp(celsius(4.0)))
+                .is_err()
+        );
+        assert_eq!(
+            thermo.get_state().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(20.0, Unit::Celsius))
+        );
+    }
+
+    #[test]
+    fn thermostat_accepts_fractional_and_fahrenheit_targets() {
+        let mut thermo = Thermostat::new("Hall", celsius(20.0));
+        thermo
+            .execute_command(Command::SetTemp(Temperature::from_fahrenheit(72.5)))
+            .unwrap();
+        assert_eq!(
+            thermo.get_state().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(22.5, Unit::Celsius))
+        );
+
+        let result = thermo.execute_command(Command::SetTemp(Temperature::from_fahrenheit(100.0)));
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn thermostat_dual_setpoints() {
+        let mut thermo = Thermostat::new("Hall", celsius(20.0));
+        thermo
+            .execute_command(Command::SetSetpoints {
+                heat: celsius(19.0),
+                cool: celsius(24.0),
+            })
+            .unwrap();
+        let state = thermo.get_state();
+        assert_eq!(
+            state.get("cool_setpoint"),
+            Some(&AttributeValue::Measure(24.0, Unit::Celsius))
+        );
+
+        let result = thermo.execute_command(Command::SetSetpoints {
+            heat: celsius(25.0),
+            cool: celsius(21.0),
+        });
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn thermostat_action_follows_ambient_temperature() {
+        let mut thermo = Thermostat::new("Hall", celsius(20.0));
+        thermo
+            .execute_command(Command::SetHvacMode(HvacMode::Auto))
+            .unwrap();
+        thermo
+            .execute_command(Command::SetSetpoints {
+                heat: celsius(19.0),
+                cool: celsius(24.0),
+            })
+            .unwrap();
+        assert!(thermo.take_pending_events().is_empty());
+
+        let event = thermo
+            .execute_command(Command::ReportAmbient(celsius(17.0)))
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AmbientTemperatureChanged);
+        assert_eq!(thermo.action(), HvacAction::Heating);
+        let pending = thermo.take_pending_events();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event_type, EventType::HvacActionChanged);
+        assert_eq!(
+            pending[0].payload.as_ref().unwrap().get("action"),
+            Some(&AttributeValue::Enum("heating".into()))
+        );
+
+        thermo
+            .execute_command(Command::ReportAmbient(celsius(18.0)))
+            .unwrap();
+        assert!(thermo.take_pending_events().is_empty());
+
+        thermo
+            .execute_command(Command::ReportAmbient(celsius(26.0)))
+            .unwrap();
+        assert_eq!(thermo.action(), HvacAction::Cooling);
+
+        thermo
+            .execute_command(Command::SetHvacMode(HvacMode::Off))
+            .unwrap();
+        assert_eq!(thermo.action(), HvacAction::Off);
+        assert_eq!(thermo.take_pending_events().len(), 2);
+    }
+
+    #[test]
+    fn parse_hvac_mode() {
+        assert_eq!("Heat".parse::<HvacMode>().unwrap(), HvacMode::Heat);
+        assert_eq!("fan_only".parse::<HvacMode>().unwrap(), HvacMode::FanOnly);
+        assert!("dry".parse::<HvacMode>().is_err());
     }
 
     #[test]
     fn thermostat_invalid_command() {
-        let mut thermo = Thermostat::new("Test Thermo", 20);
-        let result = thermo.execute_command(EventType::TurnOn);
-        assert!(result.is_err());
+        let mut thermo = Thermostat::new("Test Thermo", celsius(20.0));
+        let result = thermo.execute_command(Command::TurnOn);
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
     }
 }
@@ -0,0 +1,314 @@
+//! # Window Covering Device
+//!
+//! This module defines the `WindowCovering` struct, a concrete implementation
+//! of the [`Device`] trait for motorized blinds, shades and curtains.
+//!
+//! A covering has a position (0% = closed, 100% = fully open) and a slat tilt
+//! angle (−90° to 90°, 0° = horizontal). Commands set a target; the motor then
+//! travels toward it as the hub calls [`Device::tick`], raising an
+//! [`EventType::PositionChanged`] or [`EventType::TiltChanged`] event on every
+//! step instead of jumping straight to the target.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::devices::window_covering::WindowCovering;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::device::Device;
+//! use std::time::Duration;
+//!
+//! let mut blinds = WindowCovering::new("Office Blinds");
+//! blinds.execute_command(Command::Open).unwrap();
+//! assert_eq!(blinds.get_state().to_string(), "opening (position=0%, tilt=0°)");
+//!
+//! blinds.tick(Duration::from_secs(10));
+//! assert_eq!(blinds.get_state().to_string(), "opening (position=50%, tilt=0°)");
+//!
+//! blinds.tick(Duration::from_secs(10));
+//! assert_eq!(blinds.get_state().to_string(), "open (position=100%, tilt=0°)");
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::{ArgKind, AttributeKind, Capabilities, CommandSpec},
+    command::Command,
+    device::Device,
+    event::{Event, EventType},
+    state::{AttributeValue, DeviceState, Unit},
+};
+use std::time::Duration;
+
+/// Time a covering takes to travel from fully closed to fully open by default.
+pub const DEFAULT_TRAVEL_TIME: Duration = Duration::from_secs(20);
+
+/// Time the slats take to tilt across their full range (−90° to 90°).
+pub const TILT_TRAVEL_TIME: Duration = Duration::from_secs(3);
+
+/// Steepest slat angle accepted in either direction, in degrees.
+pub const MAX_TILT: i16 = 90;
+
+/// Represents a motorized window covering (blinds, shades, curtains).
+#[derive(Debug)]
+pub struct WindowCovering {
+    name: String,
+    position: f64, // 0.0 = closed, 100.0 = open
+    tilt: f64,     // degrees, −90.0..=90.0
+    target_position: Option<f64>,
+    target_tilt: Option<f64>,
+    travel_time: Duration,
+}
+
+impl WindowCovering {
+    /// Creates a new `WindowCovering` with the given name, closed with level slats.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::window_covering::WindowCovering;
+    /// use pulsehome::models::device::Device;
+    ///
+    /// let blinds = WindowCovering::new("Kitchen Blinds");
+    /// assert_eq!(blinds.get_state().to_string(), "closed (position=0%, tilt=0°)");
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            position: 0.0,
+            tilt: 0.0,
+            target_position: None,
+            target_tilt: None,
+            travel_time: DEFAULT_TRAVEL_TIME,
+        }
+    }
+
+    /// Returns the covering with a different full open/close travel time.
+    pub fn with_travel_time(mut self, travel_time: Duration) -> Self {
+        self.travel_time = travel_time;
+        self
+    }
+
+    /// Returns the current position in percent (0 = closed, 100 = open).
+    pub fn position(&self) -> u8 {
+        self.position.round() as u8
+    }
+
+    /// Returns the current slat tilt in degrees.
+    pub fn tilt(&self) -> i16 {
+        self.tilt.round() as i16
+    }
+
+    /// Returns `true` while the covering or its slats are travelling.
+    pub fn is_moving(&self) -> bool {
+        self.target_position.is_some() || self.target_tilt.is_some()
+    }
+
+    fn move_to(&mut self, position: u8) -> Result<(), PulseHomeError> {
+        if position > 100 {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "position {}% is outside 0–100%",
+                position
+            )));
+        }
+        let target = position as f64;
+        self.target_position = (target != self.position).then_some(target);
+        Ok(())
+    }
+
+    fn tilt_to(&mut self, degrees: i16) -> Result<(), PulseHomeError> {
+        if !(-MAX_TILT..=MAX_TILT).contains(&degrees) {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "tilt {}° is outside −{}° to {}°",
+                degrees, MAX_TILT, MAX_TILT
+            )));
+        }
+        let target = degrees as f64;
+        self.target_tilt = (target != self.tilt).then_some(target);
+        Ok(())
+    }
+
+    fn event(&self, event_type: EventType) -> Event {
+        Event::new(
+            self.name.clone(),
+            self.get_type().to_string(),
+            event_type,
+            Some(self.get_state()),
+        )
+    }
+}
+
+/// Moves `current` toward `target` by at most `step`.
+fn approach(current: f64, target: f64, step: f64) -> f64 {
+    if (target - current).abs() <= step {
+        target
+    } else {
+        current + step.copysign(target - current)
+    }
+}
+
+impl Device for WindowCovering {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn get_type(&self) -> &str {
+        "WindowCovering"
+    }
+
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
+        match command {
+            Command::Open => self.move_to(100)?,
+            Command::Close => self.move_to(0)?,
+            Command::SetPosition(position) => self.move_to(position)?,
+            Command::SetTilt(degrees) => self.tilt_to(degrees)?,
+            Command::Stop => {
+                self.target_position = None;
+                self.target_tilt = None;
+            }
+            _ => return Err(PulseHomeError::unsupported(self.get_type(), &command)),
+        }
+        Ok(self.event(command.event_type()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new()
+            .command(CommandSpec::new("open", "Open fully"))
+            .command(CommandSpec::new("close", "Close fully"))
+            .command(CommandSpec::new("stop", "Halt wherever it is"))
+            .command(
+                CommandSpec::new("position", "Move to a position (0 = closed)")
+                    .arg("position", ArgKind::integer(0, 100, Unit::Percent)),
+            )
+            .command(CommandSpec::new("tilt", "Tilt the slats").arg(
+                "angle",
+                ArgKind::integer(-MAX_TILT as i64, MAX_TILT as i64, Unit::Degree),
+            ))
+            .attribute(
+                "covering",
+                AttributeKind::Enum(&["open", "closed", "opening", "closing"]),
+            )
+            .attribute("position", AttributeKind::Measure(Unit::Percent))
+            .attribute("tilt", AttributeKind::Measure(Unit::Degree))
+    }
+
+    fn get_state(&self) -> DeviceState {
+        let status = match self.target_position {
+            Some(target) if target > self.position => "opening",
+            Some(_) => "closing",
+            None if self.position() == 0 => "closed",
+            None => "open",
+        };
+        DeviceState::new()
+            .with("covering", AttributeValue::Enum(status.to_string()))
+            .with(
+                "position",
+                AttributeValue::Measure(self.position.round(), Unit::Percent),
+            )
+            .with(
+                "tilt",
+                AttributeValue::Measure(self.tilt.round(), Unit::Degree),
+            )
+    }
+
+    /// Moves the covering and its slats toward their targets.
+    fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        let mut events = Vec::new();
+        if let Some(target) = self.target_position {
+            let step = 100.0 * elapsed.as_secs_f64() / self.travel_time.as_secs_f64();
+            self.position = approach(self.position, target, step);
+            if self.position == target {
+                self.target_position = None;
+            }
+            events.push(self.event(EventType::PositionChanged));
+        }
+        if let Some(target) = self.target_tilt {
+            let range = 2.0 * MAX_TILT as f64;
+            let step = range * elapsed.as_secs_f64() / TILT_TRAVEL_TIME.as_secs_f64();
+            self.tilt = approach(self.tilt, target, step);
+            if self.tilt == target {
+                self.target_tilt = None;
+            }
+            events.push(self.event(EventType::TiltChanged));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covering_initial_state() {
+        let blinds = WindowCovering::new("Blinds");
+        assert_eq!(blinds.get_type(), "WindowCovering");
+        assert_eq!(blinds.position(), 0);
+        assert!(!blinds.is_moving());
+    }
+
+    #[test]
+    fn covering_travels_gradually() {
+        let mut blinds = WindowCovering::new("Blinds").with_travel_time(Duration::from_secs(10));
+        let event = blinds.execute_command(Command::SetPosition(60)).unwrap();
+        assert_eq!(event.event_type, EventType::SetPosition);
+        assert_eq!(blinds.position(), 0);
+
+        let events = blinds.tick(Duration::from_secs(3));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::PositionChanged);
+        assert_eq!(
+            events[0].payload.as_ref().unwrap().to_string(),
+            "opening (position=30%, tilt=0°)"
+        );
+
+        blinds.tick(Duration::from_secs(5));
+        assert_eq!(blinds.position(), 60);
+        assert!(!blinds.is_moving());
+        assert!(blinds.tick(Duration::from_secs(1)).is_empty());
+
+        blinds.execute_command(Command::Close).unwrap();
+        blinds.tick(Duration::from_secs(1));
+        assert_eq!(
+            blinds.get_state().get("covering"),
+            Some(&AttributeValue::Enum("closing".into()))
+        );
+    }
+
+    #[test]
+    fn covering_stop_holds_position() {
+        let mut blinds = WindowCovering::new("Blinds");
+        blinds.execute_command(Command::Open).unwrap();
+        blinds.tick(Duration::from_secs(5));
+        let event = blinds.execute_command(Command::Stop).unwrap();
+        assert_eq!(event.event_type, EventType::Stop);
+        assert_eq!(
+            blinds.get_state().to_string(),
+            "open (position=25%, tilt=0°)"
+        );
+        assert!(blinds.tick(Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn covering_tilts_slats() {
+        let mut blinds = WindowCovering::new("Blinds");
+        blinds.execute_command(Command::SetTilt(-45)).unwrap();
+        let events = blinds.tick(Duration::from_millis(500));
+        assert_eq!(events[0].event_type, EventType::TiltChanged);
+        assert_eq!(blinds.tilt(), -30);
+        blinds.tick(Duration::from_secs(1));
+        assert_eq!(blinds.tilt(), -45);
+        assert!(!blinds.is_moving());
+    }
+
+    #[test]
+    fn covering_rejects_out_of_range_targets() {
+        let mut blinds = WindowCovering::new("Blinds");
+        assert!(blinds.execute_command(Command::SetPosition(101)).is_err());
+        assert!(blinds.execute_command(Command::SetTilt(91)).is_err());
+        assert!(matches!(
+            blinds.execute_command(Command::TurnOn),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+//! # Error Module
+//!
+//! Defines [`PulseHomeError`], the error type returned by every fallible
+//! operation on devices and the [`HomeHub`](crate::mediator::HomeHub).
+//!
+//! Callers can match on the variants instead of inspecting error strings.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::error::PulseHomeError;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::HomeHub;
+//!
+//! let mut hub = HomeHub::new();
+//! let result = hub.execute_device_command("Garage Light", Command::TurnOn);
+//! assert!(matches!(result, Err(PulseHomeError::DeviceNotFound(_))));
+//! ```
+
+use thiserror::Error;
+
+/// Errors raised by devices and the hub.
+#[derive(Debug, Error)]
+pub enum PulseHomeError {
+    /// No device with the given name is registered.
+    #[error("Device '{0}' not found")]
+    DeviceNotFound(String),
+
+    /// The device does not understand the requested command.
+    #[error("{device_type} does not support the '{command}' command")]
+    UnsupportedCommand {
+        device_type: String,
+        command: String,
+    },
+
+    /// A command argument is malformed or out of range.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// A device with the same name is already registered.
+    #[error("Device '{0}' is already registered")]
+    DuplicateDevice(String),
+
+    /// The device exists but cannot currently accept commands.
+    #[error("Device '{0}' is offline")]
+    DeviceOffline(String),
+
+    /// The credential supplied to a lock is missing, wrong or expired.
+    #[error("Access to '{device}' denied: {reason}")]
+    AccessDenied { device: String, reason: String },
+
+    /// The command is not valid in the device's current state (e.g. closing a closed door).
+    #[error("Cannot {command} '{device}' while it is {state}")]
+    InvalidTransition {
+        device: String,
+        state: String,
+        command: String,
+    },
+
+    /// The device reported a hardware fault (e.g. a jammed bolt) and needs attention.
+    #[error("Device '{device}' has a fault: {fault}")]
+    DeviceFault { device: String, fault: String },
+
+    /// No device type with the given name is registered with the factory.
+    #[error("Unknown device type '{0}'")]
+    UnknownDeviceType(String),
+
+    /// No room with the given name exists.
+    #[error("Room '{0}' not found")]
+    RoomNotFound(String),
+
+    /// No room is on the given floor.
+    #[error("Floor '{0}' not found")]
+    FloorNotFound(String),
+
+    /// A room with the same name already exists.
+    #[error("Room '{0}' already exists")]
+    DuplicateRoom(String),
+
+    /// No group with the given name exists.
+    #[error("Group '{0}' not found")]
+    GroupNotFound(String),
+
+    /// A group with the same name already exists.
+    #[error("Group '{0}' already exists")]
+    DuplicateGroup(String),
+
+    /// No scene with the given name exists.
+    #[error("Scene '{0}' not found")]
+    SceneNotFound(String),
+
+    /// No rule with the given name exists.
+    #[error("Rule '{0}' not found")]
+    RuleNotFound(String),
+
+    /// A rule with the same name already exists.
+    #[error("Rule '{0}' already exists")]
+    DuplicateRule(String),
+
+    /// No scheduled job with the given name exists.
+    #[error("Schedule '{0}' not found")]
+    ScheduleNotFound(String),
+
+    /// A scheduled job with the same name already exists.
+    #[error("Schedule '{0}' already exists")]
+    DuplicateSchedule(String),
+
+    /// A line of a device or scene file could not be read.
+    #[error("Config line {line}: {reason}")]
+    Config { line: usize, reason: String },
+}
+
+impl PulseHomeError {
+    /// Builds an [`PulseHomeError::UnsupportedCommand`] for the given device type and command.
+    pub fn unsupported(device_type: &str, command: &crate::models::command::Command) -> Self {
+        PulseHomeError::UnsupportedCommand {
+            device_type: device_type.to_string(),
+            command: command.name().to_string(),
+        }
+    }
+
+    /// Builds an [`PulseHomeError::UnsupportedCommand`] for a sensor reading the device cannot ingest.
+    pub fn unsupported_reading(
+        device_type: &str,
+        reading: &crate::models::reading::SensorReading,
+    ) -> Self {
+        PulseHomeError::UnsupportedCommand {
+            device_type: device_type.to_string(),
+            command: format!("report {}", reading.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::command::Command;
+
+    #[test]
+    fn unsupported_command_message() {
+        let err = PulseHomeError::unsupported("Light", &Command::Lock);
+        assert_eq!(err.to_string(), "Light does not support the 'lock' command");
+    }
+
+    #[test]
+    fn unsupported_reading_message() {
+        let err = PulseHomeError::unsupported_reading(
+            "Light",
+            &crate::models::reading::SensorReading::Humidity(40.0),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Light does not support the 'report humidity' command"
+        );
+    }
+
+    #[test]
+    fn invalid_transition_message() {
+        let err = PulseHomeError::InvalidTransition {
+            device: "Garage".into(),
+            state: "closed".into(),
+            command: "close".into(),
+        };
+        assert_eq!(err.to_string(), "Cannot close 'Garage' while it is closed");
+    }
+
+    #[test]
+    fn device_not_found_message() {
+        let err = PulseHomeError::DeviceNotFound("Porch".into());
+        assert_eq!(err.to_string(), "Device 'Porch' not found");
+    }
+}
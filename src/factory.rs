@@ -0,0 +1,544 @@
+//! # Device Factory
+//!
+//! The [`DeviceFactory`] is a registry of device types. Each type registers a
+//! constructor that builds a device from a name and a [`DeviceConfig`] of
+//! `key=value` options, so the CLI's `add` command, config files and any other
+//! client construct devices through the same path.
+//!
+//! [`DeviceFactory::with_builtins`] knows every device type in this crate;
+//! downstream crates can [`register`](DeviceFactory::register) their own.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::factory::DeviceFactory;
+//! use pulsehome::models::temperature::TemperatureUnit;
+//!
+//! let factory = DeviceFactory::with_builtins();
+//! let garden = factory
+//!     .parse_line("irrigation Front Lawn zones=4", TemperatureUnit::Celsius)
+//!     .unwrap();
+//!
+//! assert_eq!(garden.get_name(), "Front Lawn");
+//! assert_eq!(garden.get_state().to_string(), "idle (zones=4, queued=0)");
+//! ```
+
+use crate::devices::{
+    door_lock::DoorLock,
+    fan::{Fan, FanKind},
+    garage_door::GarageDoor,
+    irrigation::{DEFAULT_ZONES, IrrigationController},
+    light::{Light, LightKind},
+    media_player::MediaPlayer,
+    safety_alarm::SafetyAlarm,
+    sensor::Sensor,
+    smart_plug::SmartPlug,
+    thermostat::Thermostat,
+    window_covering::WindowCovering,
+};
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::ArgKind,
+    device::Device,
+    temperature::{Temperature, TemperatureUnit},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Builds a device from its name and configuration.
+pub type Constructor = Box<dyn Fn(&str, &DeviceConfig) -> Result<Box<dyn Device>, PulseHomeError>>;
+
+/// The `key=value` options a device is created with.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceConfig {
+    options: BTreeMap<String, String>,
+    unit: TemperatureUnit, // unit for temperatures given without a suffix
+}
+
+impl DeviceConfig {
+    /// Creates an empty configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the configuration with an option set.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key.into().to_lowercase(), value.into());
+        self
+    }
+
+    /// Returns the configuration with the unit used for unsuffixed temperatures.
+    pub fn with_unit(mut self, unit: TemperatureUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Parses `key=value` words into a configuration.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if a word is not a `key=value` pair.
+    pub fn parse(words: &[&str]) -> Result<Self, PulseHomeError> {
+        words
+            .iter()
+            .try_fold(Self::new(), |config, word| match word.split_once('=') {
+                Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+                    Ok(config.with(key, value))
+                }
+                _ => Err(PulseHomeError::InvalidArgument(format!(
+                    "expected key=value, got '{}'",
+                    word
+                ))),
+            })
+    }
+
+    /// Returns the raw value of an option.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    /// Returns the option parsed as `T`, or `default` if it is not set.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if the value does not parse.
+    pub fn get_or<T>(&self, key: &str, default: T) -> Result<T, PulseHomeError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.get(key) {
+            Some(value) => value.parse().map_err(|e| {
+                PulseHomeError::InvalidArgument(format!("invalid {} '{}': {}", key, value, e))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the option as a temperature, or `default` if it is not set.
+    ///
+    /// Values without a suffix are read in the configuration's unit.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if the value is not a temperature.
+    pub fn temperature_or(
+        &self,
+        key: &str,
+        default: Temperature,
+    ) -> Result<Temperature, PulseHomeError> {
+        match self.get(key) {
+            Some(value) => Temperature::parse(value, self.unit),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the names of the options that are set, in alphabetical order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.options.keys().map(String::as_str)
+    }
+}
+
+/// A device type known to a [`DeviceFactory`].
+pub struct DeviceType {
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// Options the type accepts, with the kind of value each takes.
+    pub options: Vec<(&'static str, ArgKind)>,
+    constructor: Constructor,
+}
+
+impl DeviceType {
+    /// Creates a device type without aliases or options.
+    pub fn new(
+        name: impl Into<String>,
+        constructor: impl Fn(&str, &DeviceConfig) -> Result<Box<dyn Device>, PulseHomeError> + 'static,
+    ) -> Self {
+        Self {
+            name: name.into().to_lowercase(),
+            aliases: Vec::new(),
+            options: Vec::new(),
+            constructor: Box::new(constructor),
+        }
+    }
+
+    /// Returns the type with another name it can be created by.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into().to_lowercase());
+        self
+    }
+
+    /// Returns the type with an accepted option.
+    pub fn option(mut self, key: &'static str, kind: ArgKind) -> Self {
+        self.options.push((key, kind));
+        self
+    }
+
+    /// Returns the type's usage line, e.g. `irrigation <name> [zones=<1-255>]`.
+    pub fn usage(&self) -> String {
+        std::iter::once(format!("{} <name>", self.name))
+            .chain(
+                self.options
+                    .iter()
+                    .map(|(key, kind)| format!("[{}=<{}>]", key, kind)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A registry of device types, keyed by name and alias.
+#[derive(Default)]
+pub struct DeviceFactory {
+    types: BTreeMap<String, DeviceType>,
+    aliases: HashMap<String, String>, // alias -> type name
+}
+
+impl DeviceFactory {
+    /// Creates a factory with no device types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a factory that knows every device type in this crate.
+    pub fn with_builtins() -> Self {
+        let mut factory = Self::new();
+        let light = |kind: LightKind| {
+            move |name: &str, _: &DeviceConfig| {
+                Ok(Box::new(Light::with_kind(name, kind)) as Box<dyn Device>)
+            }
+        };
+        factory.register(DeviceType::new("light", light(LightKind::OnOff)));
+        factory.register(DeviceType::new("dimmer", light(LightKind::Dimmable)));
+        factory.register(DeviceType::new(
+            "tunable_light",
+            light(LightKind::TunableWhite),
+        ));
+        factory.register(DeviceType::new("color_light", light(LightKind::Color)));
+        factory.register(
+            DeviceType::new("thermostat", |name, config| {
+                let target = config.temperature_or("target", Temperature::from_celsius(22.0))?;
+                Ok(Box::new(Thermostat::try_new(name, target)?))
+            })
+            .option("target", ArgKind::Temperature),
+        );
+        factory.register(DeviceType::new("doorlock", |name, _| {
+            Ok(Box::new(DoorLock::new(name)))
+        }));
+        factory.register(
+            DeviceType::new("plug", |name, _| Ok(Box::new(SmartPlug::new(name)))).alias("switch"),
+        );
+        factory.register(
+            DeviceType::new("blinds", |name, _| Ok(Box::new(WindowCovering::new(name))))
+                .alias("covering"),
+        );
+        factory.register(
+            DeviceType::new("garage_door", |name, _| Ok(Box::new(GarageDoor::new(name))))
+                .alias("garage"),
+        );
+        factory.register(
+            DeviceType::new("smoke_alarm", |name, _| {
+                Ok(Box::new(SafetyAlarm::smoke(name)))
+            })
+            .alias("smoke_detector"),
+        );
+        factory.register(
+            DeviceType::new("co_alarm", |name, _| {
+                Ok(Box::new(SafetyAlarm::carbon_monoxide(name)))
+            })
+            .alias("co_detector"),
+        );
+        factory.register(DeviceType::new("fan", |name, _| {
+            Ok(Box::new(Fan::new(name)))
+        }));
+        factory.register(DeviceType::new("ceiling_fan", |name, _| {
+            Ok(Box::new(Fan::with_kind(name, FanKind::Ceiling)))
+        }));
+        factory.register(
+            DeviceType::new("irrigation", |name, config| {
+                let zones: u8 = config.get_or("zones", DEFAULT_ZONES)?;
+                if zones == 0 {
+                    return Err(PulseHomeError::InvalidArgument(
+                        "an irrigation controller needs at least one zone".to_string(),
+                    ));
+                }
+                Ok(Box::new(IrrigationController::new(name, zones)))
+            })
+            .alias("sprinklers")
+            .option("zones", ArgKind::count(1, u8::MAX as i64)),
+        );
+        factory.register(
+            DeviceType::new("speaker", |name, _| Ok(Box::new(MediaPlayer::new(name))))
+                .alias("tv")
+                .alias("media_player"),
+        );
+        factory.register(DeviceType::new("motion_sensor", |name, _| {
+            Ok(Box::new(Sensor::motion(name)))
+        }));
+        factory.register(DeviceType::new("contact_sensor", |name, _| {
+            Ok(Box::new(Sensor::contact(name)))
+        }));
+        factory.register(DeviceType::new("climate_sensor", |name, _| {
+            Ok(Box::new(Sensor::climate(name)))
+        }));
+        factory.register(DeviceType::new("light_sensor", |name, _| {
+            Ok(Box::new(Sensor::illuminance(name)))
+        }));
+        factory
+    }
+
+    /// Registers a device type, replacing any type or alias of the same name.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::light::Light;
+    /// use pulsehome::factory::{DeviceConfig, DeviceFactory, DeviceType};
+    ///
+    /// let mut factory = DeviceFactory::new();
+    /// factory.register(
+    ///     DeviceType::new("lamp", |name, _| Ok(Box::new(Light::new(name)))).alias("bulb"),
+    /// );
+    ///
+    /// let bulb = factory.create("bulb", "Desk", &DeviceConfig::new()).unwrap();
+    /// assert_eq!(bulb.get_type(), "Light");
+    /// ```
+    pub fn register(&mut self, device_type: DeviceType) {
+        self.aliases.remove(&device_type.name);
+        for alias in &device_type.aliases {
+            self.aliases.insert(alias.clone(), device_type.name.clone());
+        }
+        self.types.insert(device_type.name.clone(), device_type);
+    }
+
+    /// Returns the registered type with the given name or alias.
+    pub fn get(&self, type_name: &str) -> Option<&DeviceType> {
+        let type_name = type_name.to_lowercase();
+        let type_name = self.aliases.get(&type_name).unwrap_or(&type_name);
+        self.types.get(type_name)
+    }
+
+    /// Returns every registered type, in alphabetical order.
+    pub fn types(&self) -> impl Iterator<Item = &DeviceType> {
+        self.types.values()
+    }
+
+    /// Creates a device of the given type.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::UnknownDeviceType`] if no type has that name or alias.
+    /// - [`PulseHomeError::InvalidArgument`] if the name is empty, an option is
+    ///   not accepted by the type, or its value is invalid.
+    pub fn create(
+        &self,
+        type_name: &str,
+        name: &str,
+        config: &DeviceConfig,
+    ) -> Result<Box<dyn Device>, PulseHomeError> {
+        let device_type = self
+            .get(type_name)
+            .ok_or_else(|| PulseHomeError::UnknownDeviceType(type_name.to_string()))?;
+        if name.trim().is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "device name must not be empty".to_string(),
+            ));
+        }
+        if let Some(key) = config
+            .keys()
+            .find(|key| !device_type.options.iter().any(|(option, _)| option == key))
+        {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "{} does not take option '{}'",
+                device_type.name, key
+            )));
+        }
+        (device_type.constructor)(name.trim(), config)
+    }
+
+    /// Creates a device from a line such as `thermostat Bedroom target=21`.
+    ///
+    /// The first word is the type, words containing `=` are options and the
+    /// remaining words form the name.
+    ///
+    /// # Errors
+    /// Returns the errors of [`DeviceFactory::create`].
+    pub fn parse_line(
+        &self,
+        line: &str,
+        unit: TemperatureUnit,
+    ) -> Result<Box<dyn Device>, PulseHomeError> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((type_name, rest)) = words.split_first() else {
+            return Err(PulseHomeError::InvalidArgument(
+                "expected <device_type> <device_name> [key=value ...]".to_string(),
+            ));
+        };
+        let (options, name): (Vec<&str>, Vec<&str>) = rest.iter().partition(|w| w.contains('='));
+        let config = DeviceConfig::parse(&options)?.with_unit(unit);
+        self.create(type_name, &name.join(" "), &config)
+    }
+
+    /// Creates the devices listed in a config file, one per line.
+    ///
+    /// Each line reads like the CLI's `add` command without the `add`.
+    /// Blank lines and lines starting with `#` are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::factory::DeviceFactory;
+    /// use pulsehome::models::temperature::TemperatureUnit;
+    ///
+    /// let config = "\
+    /// ## Ground floor
+    /// dimmer Living Room Lamp
+    /// thermostat Hallway target=70F
+    /// ";
+    /// let devices = DeviceFactory::with_builtins()
+    ///     .load(config, TemperatureUnit::Celsius)
+    ///     .unwrap();
+    /// assert_eq!(devices.len(), 2);
+    /// assert_eq!(devices[1].get_name(), "Hallway");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::Config`] with the line number of the first
+    /// line that cannot be turned into a device.
+    pub fn load(
+        &self,
+        source: &str,
+        unit: TemperatureUnit,
+    ) -> Result<Vec<Box<dyn Device>>, PulseHomeError> {
+        source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(index, line)| {
+                self.parse_line(line, unit)
+                    .map_err(|e| PulseHomeError::Config {
+                        line: index + 1,
+                        reason: e.to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Reads a config file from disk and creates the devices it lists.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::Config`] if the file cannot be read (reported as line 0).
+    /// - Any error returned by [`DeviceFactory::load`].
+    pub fn load_file(
+        &self,
+        path: impl AsRef<Path>,
+        unit: TemperatureUnit,
+    ) -> Result<Vec<Box<dyn Device>>, PulseHomeError> {
+        let source =
+            std::fs::read_to_string(path.as_ref()).map_err(|e| PulseHomeError::Config {
+                line: 0,
+                reason: format!("cannot read {}: {}", path.as_ref().display(), e),
+            })?;
+        self.load(&source, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_builtin_types_by_name_and_alias() {
+        let factory = DeviceFactory::with_builtins();
+        let config = DeviceConfig::new();
+        assert_eq!(
+            factory
+                .create("Color_Light", "Strip", &config)
+                .unwrap()
+                .get_type(),
+            "Light"
+        );
+        assert_eq!(
+            factory.create("tv", "Den TV", &config).unwrap().get_type(),
+            "MediaPlayer"
+        );
+        assert!(matches!(
+            factory.create("toaster", "Kitchen", &config),
+            Err(PulseHomeError::UnknownDeviceType(ref t)) if t == "toaster"
+        ));
+        assert!(factory.create("light", "  ", &config).is_err());
+    }
+
+    #[test]
+    fn parses_names_and_typed_options() {
+        let factory = DeviceFactory::with_builtins();
+        let thermostat = factory
+            .parse_line(
+                "thermostat Bedroom Thermostat target=72",
+                TemperatureUnit::Fahrenheit,
+            )
+            .unwrap();
+        assert_eq!(thermostat.get_name(), "Bedroom Thermostat");
+        assert_eq!(
+            Temperature::from_attribute(thermostat.get_state().get("heat_setpoint").unwrap())
+                .unwrap()
+                .in_unit(TemperatureUnit::Fahrenheit)
+                .round(),
+            72.0
+        );
+
+        assert!(matches!(
+            factory.parse_line("irrigation Lawn zones=lots", TemperatureUnit::Celsius),
+            Err(PulseHomeError::InvalidArgument(ref m)) if m.contains("zones")
+        ));
+        assert!(
+            factory
+                .parse_line("irrigation Lawn zones=0", TemperatureUnit::Celsius)
+                .is_err()
+        );
+        assert!(matches!(
+            factory.parse_line("light Porch zones=2", TemperatureUnit::Celsius),
+            Err(PulseHomeError::InvalidArgument(ref m)) if m == "light does not take option 'zones'"
+        ));
+        assert!(matches!(
+            factory.parse_line("thermostat Hall target=99", TemperatureUnit::Celsius),
+            Err(PulseHomeError::InvalidArgument(ref m)) if m.contains("out of range")
+        ));
+        assert!(DeviceConfig::parse(&["zones="]).is_err());
+    }
+
+    #[test]
+    fn registers_downstream_types() {
+        let mut factory = DeviceFactory::with_builtins();
+        factory.register(
+            DeviceType::new("porch_light", |name, _| {
+                Ok(Box::new(Light::with_kind(name, LightKind::Dimmable)))
+            })
+            .alias("light"),
+        );
+        let light = factory
+            .create("light", "Porch", &DeviceConfig::new())
+            .unwrap();
+        assert_eq!(light.get_state().to_string(), "off (brightness=100%)");
+        assert_eq!(
+            factory.get("irrigation").unwrap().usage(),
+            "irrigation <name> [zones=<1-255>]"
+        );
+    }
+
+    #[test]
+    fn load_reports_the_failing_line() {
+        let factory = DeviceFactory::with_builtins();
+        let devices = factory
+            .load(
+                "# lights\nlight Porch\n\nsprinklers Lawn zones=4\n",
+                TemperatureUnit::Celsius,
+            )
+            .unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[1].get_type(), "IrrigationController");
+
+        assert!(matches!(
+            factory.load("light Porch\ntoaster Kitchen\n", TemperatureUnit::Celsius),
+            Err(PulseHomeError::Config { line: 2, .. })
+        ));
+    }
+}
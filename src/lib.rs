@@ -1,9 +1,15 @@
 pub mod cli;
+pub mod clock;
 pub mod devices;
+pub mod error;
+pub mod factory;
 pub mod mediator;
 pub mod models;
 pub mod observer;
+pub mod scheduler;
 extern crate chrono;
 
 pub use cli::CLI;
+pub use error::PulseHomeError;
+pub use factory::DeviceFactory;
 pub use mediator::HomeHub;
@@ -11,6 +11,6 @@ fn main() {
     hub.register_observer(Box::new(LoggerObserver::new("home_log.txt")));
 
     // Start CLI
-    let mut cli = CLI::new(hub);
+    let mut cli = CLI::new(hub).with_scene_file("scenes.txt");
     cli.start();
 }
@@ -2,29 +2,296 @@
 //!
 //! The `HomeHub` mediates between devices and observers in the PulseHome system.
 //! It allows the CLI or other clients to execute commands on devices, and automatically
-//! notifies observers about device events.
+//! notifies observers about device events. Sensor readings enter through the same
+//! hub via [`HomeHub::report_reading`] and reach observers like any other event.
+//!
+//! Whenever several events are raised together, the hub delivers them in
+//! [`EventPriority`] order, so an alarm reaches observers before the routine
+//! events around it. Urgent events skip observer filters and reach observers
+//! that persist events (see [`Observer::persists`]) before any other, so an
+//! alarm is on record even if a later observer panics. Release builds abort on
+//! panic, so a panicking observer still stops delivery to the observers after
+//! it; only builds that unwind report the failure and carry on.
+//!
+//! Once observers have seen every event raised together, the hub runs the
+//! automation [`Rule`]s they trigger. A rule is never re-entered while its own
+//! actions are running and chains of rules are capped at [`MAX_RULE_DEPTH`],
+//! so rules that trigger each other cannot loop forever; skipped rules and
+//! failing actions reach observers as notifications. Time-of-day triggers and
+//! conditions follow the hub's [`Clock`].
 
-use crate::models::{device::Device, event::Event};
+use crate::clock::{Clock, SystemClock};
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::Capabilities,
+    command::Command,
+    device::{Device, DeviceId, DeviceSnapshot},
+    energy::{DeviceEnergy, EnergyLedger, EnergyReport},
+    event::{Event, EventPriority, EventType},
+    group::{Group, GroupResult, aggregate_state},
+    reading::SensorReading,
+    rule::{Action, Rule, RuleCheck, Trigger},
+    scene::{Scene, SceneActivation, restore_commands},
+    state::{AttributeValue, DeviceState},
+    temperature::TemperatureUnit,
+    topology::Topology,
+};
 use crate::observer::Observer;
+use chrono::{NaiveDateTime, NaiveTime};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+/// How many rules may trigger one another in a single chain before the hub
+/// stops running them.
+pub const MAX_RULE_DEPTH: usize = 8;
 
 /// The HomeHub struct acts as a Mediator for devices and observers.
+///
+/// Devices are stored by their [`DeviceId`] (in registration order) with a
+/// secondary index from name to ID, so lookups by either key are cheap and
+/// device names are guaranteed to be unique.
 pub struct HomeHub {
-    devices: Vec<Box<dyn Device>>,
+    devices: BTreeMap<DeviceId, Box<dyn Device>>,
+    names: HashMap<String, DeviceId>,
+    next_id: u32,
+    offline: HashSet<DeviceId>, // devices marked unreachable
     observers: Vec<Box<dyn Observer>>,
+    temperature_unit: TemperatureUnit,
+    uptime: Duration, // total time advanced through `tick`
+    energy: EnergyLedger,
+    topology: Topology,
+    groups: Vec<Group>, // in creation order
+    scenes: Vec<Scene>, // in creation order
+    rules: Vec<Rule>,   // in creation order
+    home_mode: String,
+    rule_states: HashMap<DeviceId, DeviceState>, // last state seen by the rules
+    rule_chain: Vec<String>,                     // rules whose actions are running
+    last_rule_check: Option<NaiveDateTime>,      // when time triggers were last checked
+    clock: Box<dyn Clock>,
+}
+
+impl Default for HomeHub {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HomeHub {
     /// Creates a new empty HomeHub.
     pub fn new() -> Self {
         Self {
-            devices: Vec::new(),
+            devices: BTreeMap::new(),
+            names: HashMap::new(),
+            next_id: 1,
+            offline: HashSet::new(),
             observers: Vec::new(),
+            temperature_unit: TemperatureUnit::default(),
+            uptime: Duration::ZERO,
+            energy: EnergyLedger::new(),
+            topology: Topology::new(),
+            groups: Vec::new(),
+            scenes: Vec::new(),
+            rules: Vec::new(),
+            home_mode: "home".to_string(),
+            rule_states: HashMap::new(),
+            rule_chain: Vec::new(),
+            last_rule_check: None,
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Creates an empty HomeHub that runs time-of-day rules and conditions by
+    /// the given clock instead of the system clock.
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        Self {
+            clock: Box::new(clock),
+            ..Self::new()
+        }
+    }
+
+    /// Registers a new device with the hub and returns its assigned ID.
+    ///
+    /// Notifies all observers with a [`EventType::DeviceAdded`] event.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DuplicateDevice`] if a device with the same
+    /// name is already registered.
+    pub fn register_device(&mut self, device: Box<dyn Device>) -> Result<DeviceId, PulseHomeError> {
+        let name = device.get_name().to_string();
+        if self.names.contains_key(&name) {
+            return Err(PulseHomeError::DuplicateDevice(name));
+        }
+
+        let id = DeviceId(self.next_id);
+        self.next_id += 1;
+        let event = Self::lifecycle_event(device.as_ref(), EventType::DeviceAdded, None);
+        self.names.insert(name, id);
+        self.devices.insert(id, device);
+        self.record_energy(id);
+        self.dispatch(std::iter::once(&event));
+        Ok(id)
+    }
+
+    /// Removes a device from the hub and returns it.
+    ///
+    /// Notifies all observers with a [`EventType::DeviceRemoved`] event.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn remove_device(&mut self, device_name: &str) -> Result<Box<dyn Device>, PulseHomeError> {
+        let id = self
+            .names
+            .remove(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        let device = self
+            .devices
+            .remove(&id)
+            .expect("device index out of sync with device map");
+        self.energy.forget(id);
+        self.offline.remove(&id);
+
+        let mut event = Self::lifecycle_event(device.as_ref(), EventType::DeviceRemoved, None);
+        event.room = self.topology.unassign(id);
+        for group in &mut self.groups {
+            group.remove(id);
+        }
+        self.dispatch(std::iter::once(&event));
+        Ok(device)
+    }
+
+    /// Renames a device, keeping its ID.
+    ///
+    /// Notifies all observers with a [`EventType::DeviceRenamed`] event whose
+    /// payload is the previous name.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the old name.
+    /// - [`PulseHomeError::DuplicateDevice`] if the new name is already taken.
+    pub fn rename_device(&mut self, old_name: &str, new_name: &str) -> Result<(), PulseHomeError> {
+        let id = self
+            .device_id(old_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(old_name.to_string()))?;
+        if self.names.contains_key(new_name) {
+            return Err(PulseHomeError::DuplicateDevice(new_name.to_string()));
+        }
+
+        self.names.remove(old_name);
+        self.names.insert(new_name.to_string(), id);
+        let device = self
+            .devices
+            .get_mut(&id)
+            .expect("device index out of sync with device map");
+        device.set_name(new_name);
+        for scene in &mut self.scenes {
+            scene.rename_device(old_name, new_name);
+        }
+
+        let event = Self::lifecycle_event(
+            device.as_ref(),
+            EventType::DeviceRenamed,
+            Some(
+                DeviceState::new()
+                    .with("previous_name", AttributeValue::Text(old_name.to_string())),
+            ),
+        );
+        self.dispatch(std::iter::once(&event));
+        Ok(())
+    }
+
+    /// Replaces a registered device with a new one, keeping its name and ID.
+    ///
+    /// The new device is renamed to match the device it replaces and starts
+    /// out reachable. Observers
+    /// receive a [`EventType::DeviceRemoved`] event for the old device followed
+    /// by a [`EventType::DeviceAdded`] event for the new one. The old device is
+    /// returned to the caller.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn replace_device(
+        &mut self,
+        device_name: &str,
+        mut device: Box<dyn Device>,
+    ) -> Result<Box<dyn Device>, PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+
+        device.set_name(device_name);
+        let added = Self::lifecycle_event(device.as_ref(), EventType::DeviceAdded, None);
+        let old = self
+            .devices
+            .insert(id, device)
+            .expect("device index out of sync with device map");
+        let removed = Self::lifecycle_event(old.as_ref(), EventType::DeviceRemoved, None);
+        self.energy.forget(id);
+        self.offline.remove(&id);
+        self.record_energy(id);
+
+        self.dispatch([&removed, &added]);
+        Ok(old)
+    }
+
+    /// Marks a device as reachable or unreachable, for example when its
+    /// network link drops or comes back.
+    ///
+    /// While a device is offline, commands and readings addressed to it are
+    /// rejected with [`PulseHomeError::DeviceOffline`] without reaching it.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::light::Light;
+    /// use pulsehome::error::PulseHomeError;
+    /// use pulsehome::models::command::Command;
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(Light::new("Porch Light"))).unwrap();
+    ///
+    /// hub.set_online("Porch Light", false).unwrap();
+    /// assert!(matches!(
+    ///     hub.execute_device_command("Porch Light", Command::TurnOn),
+    ///     Err(PulseHomeError::DeviceOffline(_))
+    /// ));
+    /// ```
+    pub fn set_online(&mut self, device_name: &str, online: bool) -> Result<(), PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        if online {
+            self.offline.remove(&id);
+        } else {
+            self.offline.insert(id);
         }
+        Ok(())
+    }
+
+    /// Returns the ID of the device with the given name, if registered.
+    pub fn device_id(&self, device_name: &str) -> Option<DeviceId> {
+        self.names.get(device_name).copied()
+    }
+
+    /// Returns the name of the device with the given ID, if registered.
+    pub fn device_name(&self, id: DeviceId) -> Option<&str> {
+        self.devices.get(&id).map(|d| d.get_name())
+    }
+
+    /// Returns the unit in which temperatures are shown to observers.
+    pub fn temperature_unit(&self) -> TemperatureUnit {
+        self.temperature_unit
     }
 
-    /// Registers a new device with the hub.
-    pub fn register_device(&mut self, device: Box<dyn Device>) {
-        self.devices.push(device);
+    /// Sets the unit in which temperatures are shown to observers.
+    ///
+    /// Devices keep working in their own units; only the copies of event
+    /// payloads delivered to observers are converted.
+    pub fn set_temperature_unit(&mut self, unit: TemperatureUnit) {
+        self.temperature_unit = unit;
     }
 
     /// Registers a new observer with the hub.
@@ -34,84 +301,2119 @@ impl HomeHub {
 
     /// Executes a command on a device by name.
     ///
-    /// Notifies all observers of the resulting event.
+    /// Commands the device does not advertise in its
+    /// [`Capabilities`] are rejected before they reach it; otherwise
+    /// the command's arguments are passed through to the device unchanged.
+    /// Notifies all observers of the resulting event, followed by any side
+    /// effects the device raised. Side effects are delivered even when the
+    /// command fails, so rejected attempts (such as a wrong PIN) are audited.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    /// - [`PulseHomeError::UnsupportedCommand`] if the device does not support the command.
+    /// - [`PulseHomeError::DeviceOffline`] if the device is unreachable.
+    /// - Any error returned by the device itself.
     pub fn execute_device_command(
         &mut self,
         device_name: &str,
-        command: crate::models::event::EventType,
-    ) -> Result<Event, Box<dyn std::error::Error>> {
+        command: Command,
+    ) -> Result<Event, PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        self.execute_command_by_id(id, command)
+    }
+
+    /// Executes a command on a device by ID.
+    ///
+    /// Behaves like [`HomeHub::execute_device_command`], but addresses the
+    /// device by its stable ID rather than its name.
+    pub fn execute_command_by_id(
+        &mut self,
+        id: DeviceId,
+        command: Command,
+    ) -> Result<Event, PulseHomeError> {
         let device = self
             .devices
-            .iter_mut()
-            .find(|d| d.get_name() == device_name)
-            .ok_or_else(|| format!("Device '{}' not found", device_name))?;
+            .get_mut(&id)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(id.to_string()))?;
 
-        let event = device.execute_command(command)?;
+        if !device.capabilities().supports(&command) {
+            return Err(PulseHomeError::unsupported(device.get_type(), &command));
+        }
+        if self.offline.contains(&id) {
+            return Err(PulseHomeError::DeviceOffline(device.get_name().to_string()));
+        }
+
+        let result = device.execute_command(command);
+        let side_effects = device.take_pending_events();
+        let result = result.map(|event| self.locate(event));
+        let side_effects: Vec<Event> = side_effects.into_iter().map(|e| self.locate(e)).collect();
+        self.record_energy(id);
+        self.dispatch(result.iter().chain(&side_effects));
+        result
+    }
+
+    /// Ingests a reading reported by a device's sensor, addressed by name.
+    ///
+    /// If the reading changed the device's state, the resulting event is sent
+    /// to the observers and returned; unchanged readings return `None`.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    /// - [`PulseHomeError::DeviceOffline`] if the device is unreachable.
+    /// - [`PulseHomeError::UnsupportedCommand`] if the device does not take this kind of reading.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::sensor::Sensor;
+    /// use pulsehome::models::reading::SensorReading;
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(Sensor::motion("Hall Motion"))).unwrap();
+    ///
+    /// let event = hub.report_reading("Hall Motion", SensorReading::Motion(true)).unwrap();
+    /// assert!(event.is_some());
+    /// ```
+    pub fn report_reading(
+        &mut self,
+        device_name: &str,
+        reading: SensorReading,
+    ) -> Result<Option<Event>, PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        self.report_reading_by_id(id, reading)
+    }
+
+    /// Ingests a sensor reading for a device addressed by ID.
+    ///
+    /// Behaves like [`HomeHub::report_reading`].
+    pub fn report_reading_by_id(
+        &mut self,
+        id: DeviceId,
+        reading: SensorReading,
+    ) -> Result<Option<Event>, PulseHomeError> {
+        let device = self
+            .devices
+            .get_mut(&id)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(id.to_string()))?;
 
-        // Notify observers
-        for obs in &mut self.observers {
-            obs.on_event(&event);
+        if !device.capabilities().accepts(&reading) {
+            return Err(PulseHomeError::unsupported_reading(
+                device.get_type(),
+                &reading,
+            ));
+        }
+        if self.offline.contains(&id) {
+            return Err(PulseHomeError::DeviceOffline(device.get_name().to_string()));
         }
 
+        let event = device.report_reading(reading)?;
+        let side_effects = device.take_pending_events();
+        let event = event.map(|event| self.locate(event));
+        let side_effects: Vec<Event> = side_effects.into_iter().map(|e| self.locate(e)).collect();
+        self.record_energy(id);
+        self.dispatch(event.iter().chain(&side_effects));
         Ok(event)
     }
 
-    /// Returns a list of registered device names.
-    pub fn list_devices(&self) -> Vec<String> {
-        self.devices
-            .iter()
-            .map(|d| d.get_name().to_string())
+    /// Advances time for every device by `elapsed`.
+    ///
+    /// Devices use this to progress time-based behavior such as light fades.
+    /// Every event produced is sent to the observers (most urgent first) and
+    /// returned. Afterwards the energy counters of metered devices are sampled
+    /// for [`HomeHub::energy_report`], and rules whose time of day has come
+    /// are run.
+    pub fn tick(&mut self, elapsed: Duration) -> Vec<Event> {
+        self.uptime += elapsed;
+        let events: Vec<Event> = self
+            .devices
+            .values_mut()
+            .flat_map(|d| d.tick(elapsed))
+            .collect();
+        let events: Vec<Event> = events.into_iter().map(|e| self.locate(e)).collect();
+        let ids: Vec<DeviceId> = self.devices.keys().copied().collect();
+        for id in ids {
+            self.record_energy(id);
+        }
+        self.dispatch(&events);
+        self.run_time_rules(self.clock.now().naive_local());
+        events
+    }
+
+    /// Returns the total time the hub has been advanced through [`HomeHub::tick`].
+    pub fn uptime(&self) -> Duration {
+        self.uptime
+    }
+
+    /// Reports the consumption of every metered device over the last `window`
+    /// of uptime (or since the hub started, for `None`), heaviest consumer first.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::reading::SensorReading;
+    /// use pulsehome::devices::smart_plug::SmartPlug;
+    /// use pulsehome::models::command::Command;
+    /// use pulsehome::HomeHub;
+    /// use std::time::Duration;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(SmartPlug::new("Heater"))).unwrap();
+    /// hub.report_reading("Heater", SensorReading::Power(2000.0)).unwrap();
+    /// hub.execute_device_command("Heater", Command::TurnOn).unwrap();
+    /// hub.tick(Duration::from_secs(3600));
+    ///
+    /// let report = hub.energy_report(Some(Duration::from_secs(1800)));
+    /// assert_eq!(report.devices[0].name, "Heater");
+    /// assert_eq!(report.total_kwh(), 1.0);
+    /// ```
+    pub fn energy_report(&self, window: Option<Duration>) -> EnergyReport {
+        let mut devices: Vec<DeviceEnergy> = self
+            .devices
+            .keys()
+            .filter_map(|id| self.device_energy_by_id(*id, window))
+            .collect();
+        devices.sort_by(|a, b| b.energy_kwh.total_cmp(&a.energy_kwh));
+        EnergyReport { window, devices }
+    }
+
+    /// Reports a single device's consumption over the last `window` of uptime.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    /// - [`PulseHomeError::InvalidArgument`] if the device has no energy meter.
+    pub fn device_energy(
+        &self,
+        device_name: &str,
+        window: Option<Duration>,
+    ) -> Result<DeviceEnergy, PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        self.device_energy_by_id(id, window).ok_or_else(|| {
+            PulseHomeError::InvalidArgument(format!("'{}' has no energy meter", device_name))
+        })
+    }
+
+    fn device_energy_by_id(&self, id: DeviceId, window: Option<Duration>) -> Option<DeviceEnergy> {
+        let device = self.devices.get(&id)?;
+        let meter = device.meter()?;
+        let since = window.map_or(Duration::ZERO, |w| self.uptime.saturating_sub(w));
+        Some(DeviceEnergy {
+            id,
+            name: device.get_name().to_string(),
+            power_w: meter.power_w,
+            energy_kwh: self.energy.usage_since(id, since, meter.energy_kwh),
+        })
+    }
+
+    /// Samples a device's energy counter into the ledger, if it is metered.
+    fn record_energy(&mut self, id: DeviceId) {
+        if let Some(meter) = self.devices.get(&id).and_then(|d| d.meter()) {
+            self.energy.record(self.uptime, id, meter.energy_kwh);
+        }
+    }
+
+    /// Adds a room to the home, optionally on a floor.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DuplicateRoom`] if a room with that name exists.
+    /// - [`PulseHomeError::InvalidArgument`] if the room name is empty.
+    pub fn add_room(&mut self, room: &str, floor: Option<&str>) -> Result<(), PulseHomeError> {
+        self.topology.add_room(room, floor)
+    }
+
+    /// Removes a room. Its devices stay registered but no longer have a room.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn remove_room(&mut self, room: &str) -> Result<(), PulseHomeError> {
+        self.topology.remove_room(room).map(|_| ())
+    }
+
+    /// Places a device in a room, moving it out of any previous room.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    /// - [`PulseHomeError::RoomNotFound`] if there is no such room.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::light::Light;
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(Light::new("Pendant"))).unwrap();
+    /// hub.add_room("Kitchen", Some("Ground Floor")).unwrap();
+    /// hub.assign_device("Pendant", "Kitchen").unwrap();
+    ///
+    /// assert_eq!(hub.room_of("Pendant"), Some("Kitchen"));
+    /// assert_eq!(hub.devices_in_room("Kitchen").unwrap(), vec!["Pendant".to_string()]);
+    /// ```
+    pub fn assign_device(&mut self, device_name: &str, room: &str) -> Result<(), PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        self.topology.assign(id, room)
+    }
+
+    /// Takes a device out of its room, returning the room it was in.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn unassign_device(&mut self, device_name: &str) -> Result<Option<String>, PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        Ok(self.topology.unassign(id))
+    }
+
+    /// Returns the room a device is in, if it is registered and assigned one.
+    pub fn room_of(&self, device_name: &str) -> Option<&str> {
+        self.device_id(device_name)
+            .and_then(|id| self.topology.room_of(id))
+    }
+
+    /// Returns the floors and rooms of the home.
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    /// Returns the names of the devices in a room, in registration order.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn devices_in_room(&self, room: &str) -> Result<Vec<String>, PulseHomeError> {
+        Ok(self.names_of(&self.topology.devices_in(room)?))
+    }
+
+    /// Returns the names of the devices in every room on a floor, in registration order.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::FloorNotFound`] if no room is on that floor.
+    pub fn devices_on_floor(&self, floor: &str) -> Result<Vec<String>, PulseHomeError> {
+        Ok(self.names_of(&self.topology.devices_on(floor)?))
+    }
+
+    fn names_of(&self, ids: &[DeviceId]) -> Vec<String> {
+        ids.iter()
+            .filter_map(|id| self.device_name(*id))
+            .map(String::from)
             .collect()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::devices::light::Light;
-    use crate::models::event::EventType;
-    use crate::observer::Observer;
+    /// Executes a command on every device in a room that supports it.
+    ///
+    /// Devices whose [`Capabilities`] do not include the command are skipped,
+    /// so `turn_off` reaches the lights and plugs but not the thermostat. A
+    /// failing device does not stop the others (see [`GroupResult`]).
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn execute_room_command(
+        &mut self,
+        room: &str,
+        command: Command,
+    ) -> Result<GroupResult, PulseHomeError> {
+        let ids: Vec<DeviceId> = self
+            .topology
+            .devices_in(room)?
+            .into_iter()
+            .filter(|id| {
+                self.devices
+                    .get(id)
+                    .is_some_and(|d| d.capabilities().supports(&command))
+            })
+            .collect();
+        Ok(self.fan_out(room, &ids, command))
+    }
 
-    /// Dummy observer for testing
-    struct DummyObserver {
-        pub events: Vec<String>,
+    /// Creates a group of devices that can be commanded as one.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::InvalidArgument`] if the group name is empty.
+    /// - [`PulseHomeError::DuplicateGroup`] if a group with that name exists.
+    /// - [`PulseHomeError::DeviceNotFound`] if a member is not registered.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::door_lock::DoorLock;
+    /// use pulsehome::models::command::Command;
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(DoorLock::new("Front Door"))).unwrap();
+    /// hub.register_device(Box::new(DoorLock::new("Back Door"))).unwrap();
+    /// hub.create_group("All Locks", &["Front Door", "Back Door"]).unwrap();
+    ///
+    /// let result = hub.execute_group_command("All Locks", Command::Lock).unwrap();
+    /// assert!(result.is_complete());
+    /// assert_eq!(hub.group_state("All Locks").unwrap().to_string(), "all locked");
+    /// ```
+    pub fn create_group(&mut self, name: &str, members: &[&str]) -> Result<(), PulseHomeError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "group name must not be empty".to_string(),
+            ));
+        }
+        if self.group(name).is_ok() {
+            return Err(PulseHomeError::DuplicateGroup(name.to_string()));
+        }
+        let ids = members
+            .iter()
+            .map(|m| {
+                self.device_id(m)
+                    .ok_or_else(|| PulseHomeError::DeviceNotFound(m.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.groups.push(Group::new(name, ids));
+        Ok(())
     }
 
-    impl DummyObserver {
-        fn new() -> Self {
-            Self { events: Vec::new() }
+    /// Deletes a group. Its members are not affected.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::GroupNotFound`] if there is no such group.
+    pub fn delete_group(&mut self, name: &str) -> Result<(), PulseHomeError> {
+        self.group(name)?;
+        self.groups.retain(|g| g.name != name);
+        Ok(())
+    }
+
+    /// Adds a device to a group. Adding a member twice has no effect.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::GroupNotFound`] if there is no such group.
+    /// - [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn add_to_group(&mut self, group: &str, device_name: &str) -> Result<(), PulseHomeError> {
+        let id = self
+            .device_id(device_name)
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))?;
+        self.group_mut(group)?.add(id);
+        Ok(())
+    }
+
+    /// Removes a device from a group.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::GroupNotFound`] if there is no such group.
+    /// - [`PulseHomeError::DeviceNotFound`] if the device is not a member.
+    pub fn remove_from_group(
+        &mut self,
+        group: &str,
+        device_name: &str,
+    ) -> Result<(), PulseHomeError> {
+        let id = self.device_id(device_name);
+        let group = self.group_mut(group)?;
+        match id {
+            Some(id) if group.remove(id) => Ok(()),
+            _ => Err(PulseHomeError::DeviceNotFound(device_name.to_string())),
         }
     }
 
-    impl Observer for DummyObserver {
-        fn on_event(&mut self, event: &Event) {
-            self.events.push(format!(
-                "{}:{}",
-                event.device_name,
-                event.payload.clone().unwrap()
+    /// Returns the names of all groups, in creation order.
+    pub fn groups(&self) -> Vec<String> {
+        self.groups.iter().map(|g| g.name.clone()).collect()
+    }
+
+    /// Returns the names of a group's members, in the order they were added.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::GroupNotFound`] if there is no such group.
+    pub fn group_members(&self, name: &str) -> Result<Vec<String>, PulseHomeError> {
+        Ok(self.names_of(&self.group(name)?.members))
+    }
+
+    /// Returns the aggregated state of a group's members (see [`aggregate_state`]).
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::GroupNotFound`] if there is no such group.
+    pub fn group_state(&self, name: &str) -> Result<DeviceState, PulseHomeError> {
+        let states: Vec<DeviceState> = self
+            .group(name)?
+            .members
+            .iter()
+            .filter_map(|id| self.devices.get(id))
+            .map(|d| d.get_state())
+            .collect();
+        Ok(aggregate_state(&states))
+    }
+
+    /// Executes a command on every member of a group.
+    ///
+    /// Each member goes through [`HomeHub::execute_command_by_id`], so its
+    /// events reach the observers as usual. Members that reject the command
+    /// (including ones that do not support it) are recorded as failures in
+    /// the [`GroupResult`] without stopping the rest.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::GroupNotFound`] if there is no such group.
+    pub fn execute_group_command(
+        &mut self,
+        name: &str,
+        command: Command,
+    ) -> Result<GroupResult, PulseHomeError> {
+        let group = self.group(name)?;
+        let (target, ids) = (group.name.clone(), group.members.clone());
+        Ok(self.fan_out(&target, &ids, command))
+    }
+
+    /// Adds an automation rule.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::InvalidArgument`] if the rule name is empty.
+    /// - [`PulseHomeError::DuplicateRule`] if a rule with that name exists.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::light::Light;
+    /// use pulsehome::devices::sensor::Sensor;
+    /// use pulsehome::models::reading::SensorReading;
+    /// use pulsehome::models::command::Command;
+    /// use pulsehome::models::rule::{Action, Rule};
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(Sensor::motion("Hall Motion"))).unwrap();
+    /// hub.register_device(Box::new(Light::new("Hall Light"))).unwrap();
+    /// let trigger = "Hall Motion motion becomes detected".parse().unwrap();
+    /// hub.add_rule(Rule::new("Hall Lights", trigger).action(Action::Command {
+    ///     device: "Hall Light".to_string(),
+    ///     command: Command::TurnOn,
+    /// }))
+    /// .unwrap();
+    ///
+    /// hub.report_reading("Hall Motion", SensorReading::Motion(true)).unwrap();
+    /// assert_eq!(hub.device_state("Hall Light").unwrap().state.to_string(), "on");
+    /// ```
+    pub fn add_rule(&mut self, rule: Rule) -> Result<(), PulseHomeError> {
+        if rule.name.trim().is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "rule name must not be empty".to_string(),
             ));
         }
+        if self.rule(&rule.name).is_ok() {
+            return Err(PulseHomeError::DuplicateRule(rule.name));
+        }
+        self.rules.push(rule);
+        Ok(())
     }
 
-    #[test]
-    fn homehub_execute_device_command() {
-        let mut hub = HomeHub::new();
-        let mut light = Light::new("Living Room Light");
-        hub.register_device(Box::new(light));
-        let mut observer = DummyObserver::new();
-        hub.register_observer(Box::new(observer));
+    /// Removes a rule and returns it.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RuleNotFound`] if there is no such rule.
+    pub fn remove_rule(&mut self, name: &str) -> Result<Rule, PulseHomeError> {
+        let index = self
+            .rules
+            .iter()
+            .position(|r| r.name == name)
+            .ok_or_else(|| PulseHomeError::RuleNotFound(name.to_string()))?;
+        Ok(self.rules.remove(index))
+    }
 
-        let event = hub
-            .execute_device_command("Living Room Light", EventType::TurnOn)
-            .unwrap();
-        assert_eq!(event.device_name, "Living Room Light");
-        assert_eq!(event.payload.unwrap(), "on");
+    /// Enables or disables a rule. Disabled rules are kept but never run.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RuleNotFound`] if there is no such rule.
+    pub fn set_rule_enabled(&mut self, name: &str, enabled: bool) -> Result<(), PulseHomeError> {
+        self.rules
+            .iter_mut()
+            .find(|r| r.name == name)
+            .ok_or_else(|| PulseHomeError::RuleNotFound(name.to_string()))?
+            .enabled = enabled;
+        Ok(())
     }
 
-    #[test]
-    fn homehub_device_not_found() {
-        let mut hub = HomeHub::new();
-        let result = hub.execute_device_command("NonExistent", EventType::TurnOn);
-        assert!(result.is_err());
+    /// Returns the rule with the given name.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RuleNotFound`] if there is no such rule.
+    pub fn rule(&self, name: &str) -> Result<&Rule, PulseHomeError> {
+        self.rules
+            .iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| PulseHomeError::RuleNotFound(name.to_string()))
+    }
+
+    /// Returns every rule, in creation order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Checks a rule's conditions against the hub clock's time, the mode and
+    /// device states without running its actions.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RuleNotFound`] if there is no such rule.
+    pub fn test_rule(&self, name: &str) -> Result<RuleCheck, PulseHomeError> {
+        Ok(self.check_rule(self.rule(name)?, self.clock.now().time()))
+    }
+
+    /// Returns the home mode that rule conditions check (`home` to begin with).
+    pub fn home_mode(&self) -> &str {
+        &self.home_mode
+    }
+
+    /// Sets the home mode (e.g. `away`, `night`).
+    pub fn set_home_mode(&mut self, mode: &str) {
+        self.home_mode = mode.trim().to_lowercase();
+    }
+
+    /// Runs the enabled rules triggered by an event that observers have seen.
+    ///
+    /// Adding, removing or renaming a device is not a change of its state, so
+    /// those events only fire rules waiting for the event itself.
+    fn run_rules(&mut self, event: &Event) {
+        let lifecycle = matches!(
+            event.event_type,
+            EventType::DeviceAdded | EventType::DeviceRemoved | EventType::DeviceRenamed
+        );
+        let id = self
+            .device_id(&event.device_name)
+            .filter(|id| self.devices[id].get_type() == event.device_type);
+        let previous = id.and_then(|id| self.rule_states.get(&id)).cloned();
+        if let (Some(id), Some(payload)) = (id, &event.payload)
+            && event.event_type != EventType::DeviceRenamed
+        {
+            self.rule_states.insert(id, payload.clone());
+        }
+        let triggered: Vec<Rule> = self
+            .rules
+            .iter()
+            .filter(|r| r.enabled && r.trigger.matches(event, previous.as_ref()))
+            .filter(|r| !lifecycle || matches!(r.trigger, Trigger::Event { .. }))
+            .cloned()
+            .collect();
+        let now = self.clock.now().time();
+        for rule in triggered {
+            self.fire_rule(&rule, now);
+        }
+    }
+
+    /// Runs the enabled rules whose time of day passed since the last check.
+    fn run_time_rules(&mut self, now: NaiveDateTime) {
+        let Some(last) = self.last_rule_check.replace(now) else {
+            return;
+        };
+        let due: Vec<Rule> = self
+            .rules
+            .iter()
+            .filter(|r| r.enabled && r.trigger.is_due(last, now))
+            .cloned()
+            .collect();
+        for rule in due {
+            self.fire_rule(&rule, now.time());
+        }
+    }
+
+    /// Runs a triggered rule's actions if its conditions hold, unless doing
+    /// so would loop. Skipped rules and failing actions are reported to the
+    /// observers as notifications from the rule; a failing action does not
+    /// stop the others.
+    fn fire_rule(&mut self, rule: &Rule, now: NaiveTime) {
+        if self.rule_chain.contains(&rule.name) {
+            self.notify(&rule.name, "skipped: it would trigger itself".to_string());
+            return;
+        }
+        if self.rule_chain.len() >= MAX_RULE_DEPTH {
+            let message = format!(
+                "skipped: more than {} rules triggered in a row",
+                MAX_RULE_DEPTH
+            );
+            self.notify(&rule.name, message);
+            return;
+        }
+        if !self.check_rule(rule, now).passes() {
+            return;
+        }
+        self.rule_chain.push(rule.name.clone());
+        for action in &rule.actions {
+            if let Err(e) = self.run_action(&rule.name, action) {
+                self.notify(&rule.name, format!("failed to {}: {}", action, e));
+            }
+        }
+        self.rule_chain.pop();
+    }
+
+    fn check_rule(&self, rule: &Rule, now: NaiveTime) -> RuleCheck {
+        let state_of = |name: &str| {
+            self.device_id(name)
+                .and_then(|id| self.devices.get(&id))
+                .map(|d| d.get_state())
+        };
+        RuleCheck {
+            conditions: rule
+                .conditions
+                .iter()
+                .map(|c| (c.clone(), c.holds(state_of, now, &self.home_mode)))
+                .collect(),
+        }
+    }
+
+    fn run_action(&mut self, rule_name: &str, action: &Action) -> Result<(), PulseHomeError> {
+        match action {
+            Action::Command { device, command } => self
+                .execute_device_command(device, command.clone())
+                .map(|_| ()),
+            Action::Scene(scene) => self.activate_scene(scene).map(|_| ()),
+            Action::Notify(message) => {
+                self.notify(rule_name, message.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends the observers a notification from a rule.
+    fn notify(&mut self, rule_name: &str, message: String) {
+        let event = Event::new(
+            rule_name,
+            "Rule",
+            EventType::Notification,
+            Some(DeviceState::new().with("message", AttributeValue::Text(message))),
+        );
+        self.dispatch(std::iter::once(&event));
+    }
+
+    fn group(&self, name: &str) -> Result<&Group, PulseHomeError> {
+        self.groups
+            .iter()
+            .find(|g| g.name == name)
+            .ok_or_else(|| PulseHomeError::GroupNotFound(name.to_string()))
+    }
+
+    fn group_mut(&mut self, name: &str) -> Result<&mut Group, PulseHomeError> {
+        self.groups
+            .iter_mut()
+            .find(|g| g.name == name)
+            .ok_or_else(|| PulseHomeError::GroupNotFound(name.to_string()))
+    }
+
+    /// Sends a command to each of the given devices, collecting every outcome.
+    fn fan_out(&mut self, target: &str, ids: &[DeviceId], command: Command) -> GroupResult {
+        let mut result = GroupResult::new(target);
+        for id in ids {
+            let name = self.device_name(*id).unwrap_or_default().to_string();
+            let outcome = self.execute_command_by_id(*id, command.clone());
+            result.outcomes.push((name, outcome));
+        }
+        result
+    }
+
+    /// Captures the current state of devices as a scene, replacing any scene
+    /// with the same name. Without device names, every device is captured.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::InvalidArgument`] if the scene name is empty.
+    /// - [`PulseHomeError::DeviceNotFound`] if a device is not registered.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::devices::light::{Light, LightKind};
+    /// use pulsehome::models::command::Command;
+    /// use pulsehome::HomeHub;
+    ///
+    /// let mut hub = HomeHub::new();
+    /// hub.register_device(Box::new(Light::with_kind("Sofa Lamp", LightKind::Dimmable))).unwrap();
+    /// hub.execute_device_command("Sofa Lamp", Command::SetBrightness { level: 20, transition: None })
+    ///     .unwrap();
+    /// hub.save_scene("Movie Night", &["Sofa Lamp"]).unwrap();
+    ///
+    /// hub.execute_device_command("Sofa Lamp", Command::TurnOff).unwrap();
+    /// let activation = hub.activate_scene("Movie Night").unwrap();
+    /// assert!(activation.failures.is_empty());
+    /// assert_eq!(hub.device_state("Sofa Lamp").unwrap().state.to_string(), "on (brightness=20%)");
+    /// ```
+    pub fn save_scene(&mut self, name: &str, devices: &[&str]) -> Result<&Scene, PulseHomeError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "scene name must not be empty".to_string(),
+            ));
+        }
+        let ids = match devices {
+            [] => self.devices.keys().copied().collect(),
+            _ => devices
+                .iter()
+                .map(|d| {
+                    self.device_id(d)
+                        .ok_or_else(|| PulseHomeError::DeviceNotFound(d.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let mut scene = Scene::new(name);
+        for id in ids {
+            let device = &self.devices[&id];
+            scene.set_target(device.get_name(), device.get_state());
+        }
+        Ok(self.add_scene(scene))
+    }
+
+    /// Adds a scene, such as one read back from a file, replacing any scene
+    /// with the same name.
+    pub fn add_scene(&mut self, scene: Scene) -> &Scene {
+        let index = match self.scenes.iter().position(|s| s.name == scene.name) {
+            Some(index) => {
+                self.scenes[index] = scene;
+                index
+            }
+            None => {
+                self.scenes.push(scene);
+                self.scenes.len() - 1
+            }
+        };
+        &self.scenes[index]
+    }
+
+    /// Deletes a scene and returns it. The devices are not affected.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::SceneNotFound`] if there is no such scene.
+    pub fn delete_scene(&mut self, name: &str) -> Result<Scene, PulseHomeError> {
+        let index = self
+            .scenes
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| PulseHomeError::SceneNotFound(name.to_string()))?;
+        Ok(self.scenes.remove(index))
+    }
+
+    /// Returns the scene with the given name.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::SceneNotFound`] if there is no such scene.
+    pub fn scene(&self, name: &str) -> Result<&Scene, PulseHomeError> {
+        self.scenes
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| PulseHomeError::SceneNotFound(name.to_string()))
+    }
+
+    /// Returns every scene, in creation order.
+    pub fn scenes(&self) -> &[Scene] {
+        &self.scenes
+    }
+
+    /// Brings every device in a scene to its target state.
+    ///
+    /// Each device is sent only the commands that close the gap between its
+    /// current state and its target (see [`restore_commands`]), skipping any
+    /// it does not support. Instead of an event per command, observers receive
+    /// a single [`EventType::SceneActivated`] event; urgent side effects such
+    /// as alarms are still delivered. Rule triggers still compare each device's
+    /// next event against the state the scene left it in. A device that is
+    /// missing, offline or rejects a command is recorded as a failure without
+    /// stopping the rest.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::SceneNotFound`] if there is no such scene.
+    pub fn activate_scene(&mut self, name: &str) -> Result<SceneActivation, PulseHomeError> {
+        let scene = self.scene(name)?.clone();
+        let mut failures = Vec::new();
+        let mut urgent = Vec::new();
+        for (device_name, target) in &scene.targets {
+            let Some(id) = self.device_id(device_name) else {
+                failures.push((
+                    device_name.clone(),
+                    PulseHomeError::DeviceNotFound(device_name.clone()),
+                ));
+                continue;
+            };
+            if self.offline.contains(&id) {
+                failures.push((
+                    device_name.clone(),
+                    PulseHomeError::DeviceOffline(device_name.clone()),
+                ));
+                continue;
+            }
+            let device = self
+                .devices
+                .get_mut(&id)
+                .expect("device index out of sync with device map");
+            let capabilities = device.capabilities();
+            let commands = restore_commands(&device.get_state(), target)
+                .into_iter()
+                .filter(|c| capabilities.supports(c));
+            for command in commands {
+                if let Err(e) = device.execute_command(command) {
+                    failures.push((device_name.clone(), e));
+                    break;
+                }
+            }
+            urgent.extend(
+                device
+                    .take_pending_events()
+                    .into_iter()
+                    .filter(|e| e.priority() > EventPriority::Normal),
+            );
+            // Rules compare the next event against the state the scene left
+            let state = device.get_state();
+            self.rule_states.insert(id, state);
+            self.record_energy(id);
+        }
+
+        let mut counts = DeviceState::new().with(
+            "devices",
+            AttributeValue::Int((scene.targets.len() - failures.len()) as i64),
+        );
+        if !failures.is_empty() {
+            counts.set("failed", AttributeValue::Int(failures.len() as i64));
+        }
+        let event = Event::new(
+            &scene.name,
+            "Scene",
+            EventType::SceneActivated,
+            Some(counts),
+        );
+        let urgent: Vec<Event> = urgent.into_iter().map(|e| self.locate(e)).collect();
+        self.dispatch(std::iter::once(&event).chain(&urgent));
+        Ok(SceneActivation { event, failures })
+    }
+
+    /// Returns a list of registered device names, in registration order.
+    pub fn list_devices(&self) -> Vec<String> {
+        self.devices
+            .values()
+            .map(|d| d.get_name().to_string())
+            .collect()
+    }
+
+    /// Returns the commands, readings and attributes a device supports.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn capabilities(&self, device_name: &str) -> Result<Capabilities, PulseHomeError> {
+        self.device_id(device_name)
+            .and_then(|id| self.devices.get(&id))
+            .map(|d| d.capabilities())
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))
+    }
+
+    /// Returns the current state of a single device.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::DeviceNotFound`] if no device has the given name.
+    pub fn device_state(&self, device_name: &str) -> Result<DeviceSnapshot, PulseHomeError> {
+        self.device_id(device_name)
+            .and_then(|id| {
+                self.devices
+                    .get(&id)
+                    .map(|d| DeviceSnapshot::of(id, d.as_ref()))
+            })
+            .ok_or_else(|| PulseHomeError::DeviceNotFound(device_name.to_string()))
+    }
+
+    /// Returns the current state of every registered device, in registration order.
+    pub fn snapshot(&self) -> Vec<DeviceSnapshot> {
+        self.devices
+            .iter()
+            .map(|(id, d)| DeviceSnapshot::of(*id, d.as_ref()))
+            .collect()
+    }
+
+    /// Fills in the room of the event's device, if it has one.
+    fn locate(&self, mut event: Event) -> Event {
+        if event.room.is_none() {
+            event.room = self
+                .device_id(&event.device_name)
+                .and_then(|id| self.topology.room_of(id))
+                .map(String::from);
+        }
+        event
+    }
+
+    /// Notifies observers of a batch of events raised together, most urgent
+    /// first. Events of equal priority keep the order they were raised in.
+    ///
+    /// Rules triggered by the batch only run once observers have seen all of
+    /// it, so events raised by their actions never overtake an alarm.
+    fn dispatch<'a>(&mut self, events: impl IntoIterator<Item = &'a Event>) {
+        let mut events: Vec<Event> = events.into_iter().map(|e| self.locate(e.clone())).collect();
+        events.sort_by_key(|e| Reverse(e.priority()));
+        for event in &events {
+            self.notify_observers(event);
+        }
+        for event in &events {
+            self.run_rules(event);
+        }
+    }
+
+    /// Sends an event to every registered observer that accepts it, with
+    /// temperatures expressed in the hub's display unit.
+    ///
+    /// Urgent events bypass [`Observer::accepts`] and go to persisting
+    /// observers first. Where panics unwind, an observer that panics is
+    /// reported and skipped; the remaining observers are still notified.
+    fn notify_observers(&mut self, event: &Event) {
+        let mut shown = event.clone();
+        shown.payload = shown
+            .payload
+            .map(|p| p.in_temperature_unit(self.temperature_unit));
+        let urgent = shown.priority() > EventPriority::Normal;
+        let mut order: Vec<usize> = (0..self.observers.len()).collect();
+        if urgent {
+            order.sort_by_key(|&i| !self.observers[i].persists());
+        }
+        for i in order {
+            let obs = &mut self.observers[i];
+            if !urgent && !obs.accepts(&shown) {
+                continue;
+            }
+            if panic::catch_unwind(AssertUnwindSafe(|| obs.on_event(&shown))).is_err() {
+                eprintln!("[HomeHub] An observer failed to handle: {}", shown);
+            }
+        }
+    }
+
+    /// Builds a lifecycle event describing the given device.
+    ///
+    /// Lifecycle events carry the device's current state as payload unless an
+    /// explicit payload (such as the previous name) is given.
+    fn lifecycle_event(
+        device: &dyn Device,
+        event_type: EventType,
+        payload: Option<DeviceState>,
+    ) -> Event {
+        Event::new(
+            device.get_name(),
+            device.get_type(),
+            event_type,
+            payload.or_else(|| Some(device.get_state())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::devices::door_lock::DoorLock;
+    use crate::devices::light::{Light, LightKind};
+    use crate::devices::safety_alarm::{SILENCE_DURATION, SafetyAlarm};
+    use crate::devices::sensor::Sensor;
+    use crate::devices::smart_plug::SmartPlug;
+    use crate::devices::thermostat::Thermostat;
+    use crate::devices::window_covering::WindowCovering;
+    use crate::models::argument::Credential;
+    use crate::models::reading::AlarmLevel;
+    use crate::models::state::Unit;
+    use crate::models::temperature::Temperature;
+    use crate::observer::Observer;
+
+    use chrono::{DateTime, TimeDelta};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Dummy observer for testing
+    struct DummyObserver {
+        pub events: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl DummyObserver {
+        fn new() -> Self {
+            Self {
+                events: Rc::new(RefCell::new(Vec::new())),
+            }
+        }
+    }
+
+    impl Observer for DummyObserver {
+        fn on_event(&mut self, event: &Event) {
+            self.events.borrow_mut().push(format!(
+                "{:?}:{}:{}",
+                event.event_type,
+                event.device_name,
+                event.payload.clone().unwrap()
+            ));
+        }
+    }
+
+    /// Observer that only wants events from one device.
+    struct FilteredObserver {
+        device: &'static str,
+        inner: DummyObserver,
+    }
+
+    impl Observer for FilteredObserver {
+        fn on_event(&mut self, event: &Event) {
+            self.inner.on_event(event);
+        }
+
+        fn accepts(&self, event: &Event) -> bool {
+            event.device_name == self.device
+        }
+    }
+
+    /// Observer that records events durably, like the logger.
+    struct PersistingObserver {
+        inner: DummyObserver,
+    }
+
+    impl Observer for PersistingObserver {
+        fn on_event(&mut self, event: &Event) {
+            self.inner.on_event(event);
+        }
+
+        fn persists(&self) -> bool {
+            true
+        }
+    }
+
+    /// Observer that fails on every event.
+    struct FailingObserver;
+
+    impl Observer for FailingObserver {
+        fn on_event(&mut self, _event: &Event) {
+            panic!("display unavailable");
+        }
+    }
+
+    #[test]
+    fn homehub_execute_device_command() {
+        let mut hub = HomeHub::new();
+        let light = Light::new("Living Room Light");
+        hub.register_device(Box::new(light)).unwrap();
+        let observer = DummyObserver::new();
+        hub.register_observer(Box::new(observer));
+
+        let event = hub
+            .execute_device_command("Living Room Light", Command::TurnOn)
+            .unwrap();
+        assert_eq!(event.device_name, "Living Room Light");
+        assert_eq!(event.payload.unwrap().to_string(), "on");
+    }
+
+    #[test]
+    fn homehub_device_not_found() {
+        let mut hub = HomeHub::new();
+        let result = hub.execute_device_command("NonExistent", Command::TurnOn);
+        assert!(
+            matches!(result, Err(PulseHomeError::DeviceNotFound(name)) if name == "NonExistent")
+        );
+    }
+
+    #[test]
+    fn homehub_forwards_command_arguments() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Thermostat::new(
+            "Bedroom Thermostat",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+
+        let event = hub
+            .execute_device_command(
+                "Bedroom Thermostat",
+                Command::SetTemp(Temperature::from_celsius(24.0)),
+            )
+            .unwrap();
+        assert_eq!(
+            event.payload.unwrap().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(24.0, Unit::Celsius))
+        );
+
+        let result = hub.execute_device_command(
+            "Bedroom Thermostat",
+            Command::SetTemp(Temperature::from_celsius(90.0)),
+        );
+        assert!(matches!(result, Err(PulseHomeError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn homehub_rejects_unsupported_command() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+
+        match hub.execute_device_command("Porch Light", Command::Lock) {
+            Err(PulseHomeError::UnsupportedCommand {
+                device_type,
+                command,
+            }) => {
+                assert_eq!(device_type, "Light");
+                assert_eq!(command, "lock");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn homehub_rejects_offline_devices() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(Sensor::motion("Hall Motion")))
+            .unwrap();
+        hub.save_scene("Evening", &["Porch Light"]).unwrap();
+        hub.set_online("Porch Light", false).unwrap();
+        hub.set_online("Hall Motion", false).unwrap();
+        events.borrow_mut().clear();
+
+        assert!(matches!(
+            hub.execute_device_command("Porch Light", Command::TurnOn),
+            Err(PulseHomeError::DeviceOffline(name)) if name == "Porch Light"
+        ));
+        assert!(matches!(
+            hub.report_reading("Hall Motion", SensorReading::Motion(true)),
+            Err(PulseHomeError::DeviceOffline(_))
+        ));
+        assert!(events.borrow().is_empty());
+        let activation = hub.activate_scene("Evening").unwrap();
+        assert!(matches!(
+            activation.failures[..],
+            [(_, PulseHomeError::DeviceOffline(_))]
+        ));
+
+        // Back online, the device accepts commands again
+        hub.set_online("Porch Light", true).unwrap();
+        hub.execute_device_command("Porch Light", Command::TurnOn)
+            .unwrap();
+        assert!(matches!(
+            hub.set_online("Garage Light", false),
+            Err(PulseHomeError::DeviceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn homehub_validates_against_capabilities_before_dispatch() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::with_kind(
+            "Porch Light",
+            LightKind::Dimmable,
+        )))
+        .unwrap();
+        hub.register_device(Box::new(Sensor::motion("Hall Motion")))
+            .unwrap();
+
+        // A dimmable light advertises no color temperature, so the command is
+        // rejected by the hub without reaching the device.
+        let capabilities = hub.capabilities("Porch Light").unwrap();
+        assert!(capabilities.find("dim").is_some());
+        assert!(capabilities.find("color_temp").is_none());
+        assert!(matches!(
+            hub.execute_device_command(
+                "Porch Light",
+                Command::SetColorTemp {
+                    kelvin: 3000,
+                    transition: None,
+                },
+            ),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+
+        assert!(matches!(
+            hub.report_reading("Hall Motion", SensorReading::Contact { open: true }),
+            Err(PulseHomeError::UnsupportedCommand { ref command, .. }) if command == "report contact"
+        ));
+        assert!(matches!(
+            hub.capabilities("Garage"),
+            Err(PulseHomeError::DeviceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn homehub_assigns_unique_ids() {
+        let mut hub = HomeHub::new();
+        let first = hub
+            .register_device(Box::new(Light::new("Hall Light")))
+            .unwrap();
+        let second = hub
+            .register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(hub.device_id("Porch Light"), Some(second));
+        assert_eq!(hub.device_name(first), Some("Hall Light"));
+
+        let event = hub.execute_command_by_id(second, Command::TurnOn).unwrap();
+        assert_eq!(event.device_name, "Porch Light");
+    }
+
+    #[test]
+    fn homehub_rejects_duplicate_names() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(DoorLock::new("Front Door")))
+            .unwrap();
+        let result = hub.register_device(Box::new(DoorLock::new("Front Door")));
+
+        assert!(
+            matches!(result, Err(PulseHomeError::DuplicateDevice(name)) if name == "Front Door")
+        );
+        assert_eq!(hub.list_devices(), vec!["Front Door".to_string()]);
+    }
+
+    #[test]
+    fn homehub_remove_device_notifies_observers() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(DoorLock::new("Back Door")))
+            .unwrap();
+
+        let removed = hub.remove_device("Back Door").unwrap();
+        assert_eq!(removed.get_name(), "Back Door");
+        assert!(hub.list_devices().is_empty());
+        assert!(hub.device_id("Back Door").is_none());
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                "DeviceAdded:Back Door:unlocked".to_string(),
+                "DeviceRemoved:Back Door:unlocked".to_string(),
+            ]
+        );
+
+        let result = hub.remove_device("Back Door");
+        assert!(matches!(result, Err(PulseHomeError::DeviceNotFound(_))));
+    }
+
+    #[test]
+    fn homehub_rooms_scope_queries_commands_and_events() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::new("Bedside Lamp")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Bedroom Thermostat",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        hub.register_device(Box::new(SmartPlug::new("Kettle")))
+            .unwrap();
+        hub.add_room("Bedroom", Some("Upstairs")).unwrap();
+        hub.add_room("Kitchen", Some("Ground Floor")).unwrap();
+        hub.assign_device("Bedside Lamp", "Bedroom").unwrap();
+        hub.assign_device("Bedroom Thermostat", "Bedroom").unwrap();
+        hub.assign_device("Kettle", "Kitchen").unwrap();
+        assert!(matches!(
+            hub.assign_device("Kettle", "Attic"),
+            Err(PulseHomeError::RoomNotFound(_))
+        ));
+
+        assert_eq!(
+            hub.devices_in_room("Bedroom").unwrap(),
+            vec!["Bedside Lamp".to_string(), "Bedroom Thermostat".to_string()]
+        );
+        assert_eq!(
+            hub.devices_on_floor("Ground Floor").unwrap(),
+            vec!["Kettle".to_string()]
+        );
+
+        // Only the lamp supports turn_on; the thermostat is skipped
+        let results = hub
+            .execute_room_command("Bedroom", Command::TurnOn)
+            .unwrap();
+        assert_eq!(results.outcomes.len(), 1);
+        let (name, result) = &results.outcomes[0];
+        assert_eq!(name, "Bedside Lamp");
+        let event = result.as_ref().unwrap();
+        assert_eq!(event.room.as_deref(), Some("Bedroom"));
+        assert_eq!(events.borrow().len(), 4);
+
+        // The room follows the device's ID through a rename, and is reported on removal
+        hub.rename_device("Bedside Lamp", "Reading Lamp").unwrap();
+        assert_eq!(hub.room_of("Reading Lamp"), Some("Bedroom"));
+        hub.remove_room("Kitchen").unwrap();
+        assert_eq!(hub.room_of("Kettle"), None);
+        assert!(
+            hub.execute_room_command("Kitchen", Command::TurnOn)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn homehub_groups_fan_out_and_aggregate() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::new("Hall Light")))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall Thermostat",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        hub.create_group(
+            "Downstairs",
+            &["Hall Light", "Porch Light", "Hall Thermostat"],
+        )
+        .unwrap();
+        assert!(matches!(
+            hub.create_group("Downstairs", &[]),
+            Err(PulseHomeError::DuplicateGroup(_))
+        ));
+        assert!(matches!(
+            hub.create_group("Attic", &["Attic Fan"]),
+            Err(PulseHomeError::DeviceNotFound(_))
+        ));
+
+        // The thermostat rejects turn_on; the lights still run and it is reported
+        let result = hub
+            .execute_group_command("Downstairs", Command::TurnOn)
+            .unwrap();
+        assert!(!result.is_complete());
+        assert_eq!(result.succeeded().count(), 2);
+        let failed: Vec<_> = result.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "Hall Thermostat");
+        assert!(matches!(
+            failed[0].1,
+            PulseHomeError::UnsupportedCommand { .. }
+        ));
+        assert_eq!(events.borrow().len(), 5);
+
+        hub.remove_from_group("Downstairs", "Hall Thermostat")
+            .unwrap();
+        hub.execute_device_command("Porch Light", Command::TurnOff)
+            .unwrap();
+        assert_eq!(
+            hub.group_state("Downstairs").unwrap().to_string(),
+            "some on"
+        );
+        hub.execute_group_command("Downstairs", Command::TurnOff)
+            .unwrap();
+        assert_eq!(
+            hub.group_state("Downstairs").unwrap().to_string(),
+            "all off"
+        );
+
+        // Members follow renames and leave the group when removed
+        hub.rename_device("Hall Light", "Entry Light").unwrap();
+        hub.remove_device("Porch Light").unwrap();
+        assert_eq!(
+            hub.group_members("Downstairs").unwrap(),
+            vec!["Entry Light".to_string()]
+        );
+        hub.delete_group("Downstairs").unwrap();
+        assert!(matches!(
+            hub.group_state("Downstairs"),
+            Err(PulseHomeError::GroupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn homehub_scenes_restore_states_with_one_event() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::with_kind("Sofa Lamp", LightKind::Dimmable)))
+            .unwrap();
+        hub.register_device(Box::new(WindowCovering::new("Blinds")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Thermostat",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+        hub.execute_device_command(
+            "Sofa Lamp",
+            Command::SetBrightness {
+                level: 20,
+                transition: None,
+            },
+        )
+        .unwrap();
+        hub.execute_device_command("Sofa Lamp", Command::TurnOn)
+            .unwrap();
+        let scene = hub.save_scene("Movie Night", &[]).unwrap();
+        assert_eq!(scene.targets.len(), 3);
+        assert!(hub.save_scene("Movie Night", &["Porch"]).is_err());
+
+        hub.execute_device_command("Sofa Lamp", Command::TurnOff)
+            .unwrap();
+        hub.execute_device_command("Blinds", Command::Open).unwrap();
+        hub.execute_device_command(
+            "Thermostat",
+            Command::SetTemp(Temperature::from_celsius(25.0)),
+        )
+        .unwrap();
+        hub.tick(Duration::from_secs(60));
+        let before = events.borrow().len();
+
+        let activation = hub.activate_scene("Movie Night").unwrap();
+        assert!(activation.failures.is_empty());
+        assert_eq!(events.borrow().len(), before + 1);
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "SceneActivated:Movie Night:3"
+        );
+        assert_eq!(
+            hub.device_state("Sofa Lamp").unwrap().state.to_string(),
+            "on (brightness=20%)"
+        );
+        let thermostat = hub.device_state("Thermostat").unwrap().state;
+        assert_eq!(
+            thermostat.get("heat_setpoint").map(ToString::to_string),
+            Some("21°C".to_string())
+        );
+        hub.tick(Duration::from_secs(60));
+        assert_eq!(
+            hub.device_state("Blinds").unwrap().state.get("position"),
+            Some(&AttributeValue::Measure(0.0, Unit::Percent))
+        );
+
+        // Scenes follow renames; missing devices are reported, not fatal
+        hub.rename_device("Sofa Lamp", "Reading Lamp").unwrap();
+        assert!(
+            hub.scene("Movie Night")
+                .unwrap()
+                .target("Reading Lamp")
+                .is_some()
+        );
+        hub.remove_device("Blinds").unwrap();
+        let activation = hub.activate_scene("Movie Night").unwrap();
+        assert_eq!(activation.failures.len(), 1);
+        assert_eq!(
+            activation.event.to_string(),
+            "Scene 'Movie Night' activated on 2 devices (1 failed)"
+        );
+
+        hub.delete_scene("Movie Night").unwrap();
+        assert!(matches!(
+            hub.activate_scene("Movie Night"),
+            Err(PulseHomeError::SceneNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn homehub_rename_device_keeps_id() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        let id = hub
+            .register_device(Box::new(Light::new("Light 1")))
+            .unwrap();
+
+        hub.rename_device("Light 1", "Kitchen Pendant").unwrap();
+        assert_eq!(hub.device_id("Kitchen Pendant"), Some(id));
+        assert!(hub.device_id("Light 1").is_none());
+        assert_eq!(hub.device_name(id), Some("Kitchen Pendant"));
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "DeviceRenamed:Kitchen Pendant:Light 1"
+        );
+
+        let event = hub
+            .execute_device_command("Kitchen Pendant", Command::TurnOn)
+            .unwrap();
+        assert_eq!(event.device_name, "Kitchen Pendant");
+    }
+
+    #[test]
+    fn homehub_rename_rejects_taken_name() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Light 1")))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Light 2")))
+            .unwrap();
+
+        let result = hub.rename_device("Light 1", "Light 2");
+        assert!(matches!(result, Err(PulseHomeError::DuplicateDevice(_))));
+        assert_eq!(hub.list_devices(), vec!["Light 1", "Light 2"]);
+    }
+
+    #[test]
+    fn homehub_replace_device_keeps_name_and_id() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        let id = hub
+            .register_device(Box::new(DoorLock::new("Front Door")))
+            .unwrap();
+        hub.execute_device_command("Front Door", Command::Lock)
+            .unwrap();
+
+        let old = hub
+            .replace_device("Front Door", Box::new(DoorLock::new("New Lock")))
+            .unwrap();
+        assert_eq!(old.get_state().to_string(), "locked");
+        assert_eq!(hub.device_id("Front Door"), Some(id));
+        assert_eq!(
+            events.borrow()[events.borrow().len() - 2..],
+            [
+                "DeviceRemoved:Front Door:locked".to_string(),
+                "DeviceAdded:Front Door:unlocked".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn homehub_device_state_and_snapshot() {
+        let mut hub = HomeHub::new();
+        let light = hub
+            .register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall Thermostat",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+        hub.execute_device_command("Porch Light", Command::TurnOn)
+            .unwrap();
+
+        let state = hub.device_state("Porch Light").unwrap();
+        assert_eq!(state.id, light);
+        assert_eq!(state.device_type, "Light");
+        assert_eq!(state.state.get("power"), Some(&AttributeValue::Bool(true)));
+
+        let snapshot = hub.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[1].name, "Hall Thermostat");
+        assert_eq!(
+            snapshot[1].state.to_string(),
+            "heat (heat_setpoint=21°C, cool_setpoint=21°C, action=idle)"
+        );
+
+        assert!(matches!(
+            hub.device_state("Garage"),
+            Err(PulseHomeError::DeviceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn homehub_tick_drives_transitions() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::with_kind("Lamp", LightKind::Dimmable)))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Porch"))).unwrap();
+
+        hub.execute_device_command(
+            "Lamp",
+            Command::SetBrightness {
+                level: 40,
+                transition: Some(Duration::from_secs(2)),
+            },
+        )
+        .unwrap();
+
+        let emitted = hub.tick(Duration::from_secs(1));
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "SetBrightness:Lamp:on (brightness=70%, transition=1 s)"
+        );
+
+        hub.tick(Duration::from_secs(1));
+        assert_eq!(
+            hub.device_state("Lamp").unwrap().state.to_string(),
+            "on (brightness=40%)"
+        );
+        assert!(hub.tick(Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn homehub_forwards_side_effect_events() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+        hub.register_observer(Box::new(observer));
+
+        let event = hub
+            .execute_device_command(
+                "Hall",
+                Command::ReportAmbient(Temperature::from_celsius(18.0)),
+            )
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AmbientTemperatureChanged);
+
+        let events = events.borrow();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].starts_with("AmbientTemperatureChanged:Hall:"));
+        assert!(events[1].starts_with("HvacActionChanged:Hall:"));
+        assert!(events[1].ends_with("action=heating)"));
+    }
+
+    #[test]
+    fn homehub_converts_temperatures_for_observers() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(20.0),
+        )))
+        .unwrap();
+        hub.register_observer(Box::new(observer));
+        hub.set_temperature_unit(TemperatureUnit::Fahrenheit);
+
+        let event = hub
+            .execute_device_command("Hall", Command::SetTemp(Temperature::from_fahrenheit(72.5)))
+            .unwrap();
+        assert_eq!(
+            event.payload.unwrap().get("heat_setpoint"),
+            Some(&AttributeValue::Measure(22.5, Unit::Celsius))
+        );
+        assert_eq!(
+            events.borrow()[0],
+            "SetTemp:Hall:heat (heat_setpoint=72.5°F, cool_setpoint=72.5°F, action=idle)"
+        );
+    }
+
+    #[test]
+    fn homehub_reports_rejected_unlock_attempts() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_device(Box::new(DoorLock::new("Front Door")))
+            .unwrap();
+        hub.register_observer(Box::new(observer));
+        hub.execute_device_command(
+            "Front Door",
+            Command::AddPin {
+                label: "guest".into(),
+                pin: "1234".into(),
+                expires: None,
+            },
+        )
+        .unwrap();
+        hub.execute_device_command("Front Door", Command::Lock)
+            .unwrap();
+
+        let result = hub.execute_device_command(
+            "Front Door",
+            Command::Unlock(Some(Credential::Pin("0000".into()))),
+        );
+        assert!(matches!(result, Err(PulseHomeError::AccessDenied { .. })));
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "AccessDenied:Front Door:locked (codes=1)"
+        );
+    }
+
+    #[test]
+    fn homehub_ingests_sensor_readings() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_device(Box::new(Sensor::contact("Back Door")))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Porch"))).unwrap();
+        hub.register_observer(Box::new(observer));
+
+        let event = hub
+            .report_reading("Back Door", SensorReading::Contact { open: true })
+            .unwrap();
+        assert_eq!(event.unwrap().event_type, EventType::ContactOpened);
+        assert!(
+            hub.report_reading("Back Door", SensorReading::Contact { open: true })
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            hub.report_reading("Porch", SensorReading::Motion(true)),
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
+        assert!(matches!(
+            hub.report_reading("Garage", SensorReading::Motion(true)),
+            Err(PulseHomeError::DeviceNotFound(_))
+        ));
+        assert_eq!(*events.borrow(), vec!["ContactOpened:Back Door:open"]);
+    }
+
+    #[test]
+    fn homehub_routes_temperature_readings_to_thermostats() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Thermostat::new(
+            "Hall",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+
+        let event = hub
+            .report_reading(
+                "Hall",
+                SensorReading::Temperature(Temperature::from_celsius(18.0)),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type, EventType::AmbientTemperatureChanged);
+        assert_eq!(
+            hub.device_state("Hall").unwrap().state.get("action"),
+            Some(&AttributeValue::Enum("heating".into()))
+        );
+    }
+
+    #[test]
+    fn homehub_reports_energy_over_windows() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(SmartPlug::new("Heater")))
+            .unwrap();
+        hub.register_device(Box::new(SmartPlug::new("Lamp")))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Porch"))).unwrap();
+        hub.report_reading("Heater", SensorReading::Power(1000.0))
+            .unwrap();
+        hub.report_reading("Lamp", SensorReading::Power(100.0))
+            .unwrap();
+        hub.execute_device_command("Heater", Command::TurnOn)
+            .unwrap();
+        hub.execute_device_command("Lamp", Command::TurnOn).unwrap();
+
+        let hour = Duration::from_secs(3600);
+        hub.tick(hour);
+        hub.execute_device_command("Heater", Command::TurnOff)
+            .unwrap();
+        hub.tick(hour);
+
+        let report = hub.energy_report(None);
+        let names: Vec<&str> = report.devices.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Heater", "Lamp"]);
+        assert_eq!(report.total_kwh(), 1.2);
+        assert_eq!(report.total_power_w(), 100.0);
+
+        let last_hour = hub.energy_report(Some(hour));
+        assert_eq!(last_hour.devices[0].name, "Lamp");
+        assert_eq!(last_hour.total_kwh(), 0.1);
+
+        assert_eq!(
+            hub.device_energy("Heater", Some(hour)).unwrap().energy_kwh,
+            0.0
+        );
+        assert!(matches!(
+            hub.device_energy("Porch", None),
+            Err(PulseHomeError::InvalidArgument(_))
+        ));
+
+        hub.remove_device("Lamp").unwrap();
+        assert_eq!(hub.energy_report(None).total_kwh(), 1.0);
+    }
+
+    #[test]
+    fn homehub_delivers_alarms_ahead_of_normal_events() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_device(Box::new(WindowCovering::new("Hall Blinds")))
+            .unwrap();
+        hub.register_device(Box::new(SafetyAlarm::smoke("Hall Smoke")))
+            .unwrap();
+        hub.report_reading("Hall Smoke", SensorReading::Smoke(AlarmLevel::Alarm))
+            .unwrap();
+        hub.execute_device_command("Hall Smoke", Command::Silence)
+            .unwrap();
+        hub.execute_device_command("Hall Blinds", Command::Open)
+            .unwrap();
+        hub.register_observer(Box::new(observer));
+
+        // The blinds' step is raised first, but the re-sounding alarm is delivered first.
+        let ticked = hub.tick(SILENCE_DURATION);
+        assert_eq!(ticked[0].event_type, EventType::PositionChanged);
+        assert_eq!(ticked[1].event_type, EventType::AlarmTriggered);
+        let events = events.borrow();
+        assert!(events[0].starts_with("AlarmTriggered:Hall Smoke:"));
+        assert!(events[1].starts_with("PositionChanged:Hall Blinds:"));
+    }
+
+    #[test]
+    fn homehub_runs_rules_after_the_whole_batch_is_delivered() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        for name in ["Hall Smoke", "Kitchen Smoke"] {
+            hub.register_device(Box::new(SafetyAlarm::smoke(name)))
+                .unwrap();
+            hub.report_reading(name, SensorReading::Smoke(AlarmLevel::Alarm))
+                .unwrap();
+            hub.execute_device_command(name, Command::Silence).unwrap();
+        }
+        hub.add_rule(
+            Rule::new(
+                "Evacuate",
+                "Hall Smoke raises alarm_triggered".parse().unwrap(),
+            )
+            .action(Action::Notify("leave the house".to_string())),
+        )
+        .unwrap();
+        hub.register_observer(Box::new(observer));
+
+        // Both alarms sound again in one tick; the rule's notification follows them
+        hub.tick(SILENCE_DURATION);
+        let events = events.borrow();
+        assert_eq!(events.len(), 3);
+        assert!(events[0].starts_with("AlarmTriggered:Hall Smoke:"));
+        assert!(events[1].starts_with("AlarmTriggered:Kitchen Smoke:"));
+        assert!(events[2].starts_with("Notification:Evacuate:"));
+    }
+
+    #[test]
+    fn homehub_alarms_bypass_filters_and_failing_observers() {
+        let mut hub = HomeHub::new();
+        let filtered = FilteredObserver {
+            device: "Porch Light",
+            inner: DummyObserver::new(),
+        };
+        let events = Rc::clone(&filtered.inner.events);
+        hub.register_observer(Box::new(FailingObserver));
+        hub.register_observer(Box::new(filtered));
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(SafetyAlarm::carbon_monoxide("Garage CO")))
+            .unwrap();
+
+        hub.execute_device_command("Porch Light", Command::TurnOn)
+            .unwrap();
+        hub.report_reading(
+            "Garage CO",
+            SensorReading::CarbonMonoxide(AlarmLevel::Warning),
+        )
+        .unwrap();
+        hub.report_reading(
+            "Garage CO",
+            SensorReading::CarbonMonoxide(AlarmLevel::Clear),
+        )
+        .unwrap();
+
+        let events = events.borrow();
+        assert_eq!(events.len(), 3);
+        assert!(events[0].starts_with("DeviceAdded:Porch Light:"));
+        assert!(events[1].starts_with("TurnOn:Porch Light:"));
+        assert!(events[2].starts_with("AlarmWarning:Garage CO:"));
+    }
+
+    #[test]
+    fn homehub_persists_urgent_events_before_other_observers() {
+        let mut hub = HomeHub::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+        hub.register_observer(Box::new(DummyObserver {
+            events: Rc::clone(&log),
+        }));
+        hub.register_observer(Box::new(PersistingObserver {
+            inner: DummyObserver {
+                events: Rc::clone(&log),
+            },
+        }));
+        hub.register_device(Box::new(SafetyAlarm::smoke("Kitchen Smoke")))
+            .unwrap();
+        log.borrow_mut().clear();
+
+        hub.report_reading("Kitchen Smoke", SensorReading::Smoke(AlarmLevel::Alarm))
+            .unwrap();
+        hub.execute_device_command("Kitchen Smoke", Command::Silence)
+            .unwrap();
+
+        // The alarm reaches the persisting observer first; routine events
+        // keep registration order.
+        let log = log.borrow();
+        assert_eq!(log.len(), 4);
+        assert!(log[0].starts_with("AlarmTriggered:"));
+        assert_eq!(log[0], log[1]);
+        assert!(!log[2].starts_with("AlarmTriggered:"));
+        assert_eq!(log[2], log[3]);
+    }
+
+    #[test]
+    fn homehub_runs_rules_triggered_by_state_changes() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Sensor::motion("Hall Motion")))
+            .unwrap();
+        hub.register_device(Box::new(Light::new("Hall Light")))
+            .unwrap();
+        let rule = Rule::new(
+            "Hall Lights",
+            "Hall Motion motion becomes detected".parse().unwrap(),
+        )
+        .condition("mode is home".parse().unwrap())
+        .action(Action::Command {
+            device: "Hall Light".to_string(),
+            command: Command::TurnOn,
+        })
+        .action(Action::Notify("someone is in the hall".to_string()));
+        hub.add_rule(rule.clone()).unwrap();
+        assert!(matches!(
+            hub.add_rule(rule),
+            Err(PulseHomeError::DuplicateRule(_))
+        ));
+
+        // Conditions that do not hold keep the rule from running
+        hub.set_home_mode("Away");
+        assert!(!hub.test_rule("Hall Lights").unwrap().passes());
+        hub.report_reading("Hall Motion", SensorReading::Motion(true))
+            .unwrap();
+        assert_eq!(
+            hub.device_state("Hall Light").unwrap().state.to_string(),
+            "off"
+        );
+
+        // The motion is already detected, so only a fresh detection fires the rule
+        hub.set_home_mode("home");
+        hub.report_reading("Hall Motion", SensorReading::Motion(true))
+            .unwrap();
+        assert_eq!(
+            hub.device_state("Hall Light").unwrap().state.to_string(),
+            "off"
+        );
+        hub.report_reading("Hall Motion", SensorReading::Motion(false))
+            .unwrap();
+        hub.report_reading("Hall Motion", SensorReading::Motion(true))
+            .unwrap();
+        assert_eq!(
+            hub.device_state("Hall Light").unwrap().state.to_string(),
+            "on"
+        );
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "Notification:Hall Lights:someone is in the hall"
+        );
+
+        // Disabled rules are kept but do not run
+        hub.execute_device_command("Hall Light", Command::TurnOff)
+            .unwrap();
+        hub.set_rule_enabled("Hall Lights", false).unwrap();
+        hub.report_reading("Hall Motion", SensorReading::Motion(false))
+            .unwrap();
+        hub.report_reading("Hall Motion", SensorReading::Motion(true))
+            .unwrap();
+        assert_eq!(
+            hub.device_state("Hall Light").unwrap().state.to_string(),
+            "off"
+        );
+        assert_eq!(hub.remove_rule("Hall Lights").unwrap().name, "Hall Lights");
+        assert!(hub.rules().is_empty());
+    }
+
+    #[test]
+    fn homehub_rules_see_the_states_scenes_set() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::new("Lamp"))).unwrap();
+        hub.execute_device_command("Lamp", Command::TurnOn).unwrap();
+        hub.save_scene("Reading", &["Lamp"]).unwrap();
+        hub.execute_device_command("Lamp", Command::TurnOff)
+            .unwrap();
+        hub.add_rule(
+            Rule::new("Lamp Off", "Lamp power becomes off".parse().unwrap())
+                .action(Action::Notify("lamp switched off".to_string())),
+        )
+        .unwrap();
+
+        // The scene turns the lamp on without a per-device event, so turning
+        // it off again is still a change the rule sees
+        hub.activate_scene("Reading").unwrap();
+        hub.execute_device_command("Lamp", Command::TurnOff)
+            .unwrap();
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "Notification:Lamp Off:lamp switched off"
+        );
+    }
+
+    #[test]
+    fn homehub_stops_rules_that_trigger_each_other() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Lamp A"))).unwrap();
+        hub.register_device(Box::new(Light::new("Lamp B"))).unwrap();
+        let toggle = |name: &str, watched: &str, other: &str| {
+            Rule::new(name, format!("{} power changes", watched).parse().unwrap())
+                .action(Action::Command {
+                    device: other.to_string(),
+                    command: Command::TurnOn,
+                })
+                .action(Action::Command {
+                    device: other.to_string(),
+                    command: Command::TurnOff,
+                })
+        };
+        hub.add_rule(toggle("A to B", "Lamp A", "Lamp B")).unwrap();
+        hub.add_rule(toggle("B to A", "Lamp B", "Lamp A")).unwrap();
+
+        // Each rule runs, but neither is re-entered while its own actions run
+        hub.execute_device_command("Lamp A", Command::TurnOn)
+            .unwrap();
+        assert_eq!(hub.device_state("Lamp A").unwrap().state.to_string(), "off");
+        assert_eq!(hub.device_state("Lamp B").unwrap().state.to_string(), "off");
+    }
+
+    #[test]
+    fn homehub_runs_time_rules_once_their_time_passes() {
+        let clock =
+            ManualClock::new(DateTime::parse_from_rfc3339("2024-06-01T18:00:00+01:00").unwrap());
+        let mut hub = HomeHub::with_clock(clock.clone());
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.add_rule(
+            Rule::new("Porch On", "at 18:30".parse().unwrap())
+                .condition("between 18:00 and 23:00".parse().unwrap())
+                .action(Action::Command {
+                    device: "Porch Light".to_string(),
+                    command: Command::TurnOn,
+                }),
+        )
+        .unwrap();
+        assert!(hub.test_rule("Porch On").unwrap().passes());
+
+        // The first check only records the time
+        hub.tick(Duration::ZERO);
+        clock.advance(TimeDelta::minutes(29));
+        hub.tick(Duration::ZERO);
+        assert_eq!(
+            hub.device_state("Porch Light").unwrap().state.to_string(),
+            "off"
+        );
+        clock.advance(TimeDelta::minutes(2));
+        hub.tick(Duration::ZERO);
+        assert_eq!(
+            hub.device_state("Porch Light").unwrap().state.to_string(),
+            "on"
+        );
+
+        hub.execute_device_command("Porch Light", Command::TurnOff)
+            .unwrap();
+        clock.advance(TimeDelta::minutes(29));
+        hub.tick(Duration::ZERO);
+        assert_eq!(
+            hub.device_state("Porch Light").unwrap().state.to_string(),
+            "off"
+        );
+        clock.advance(TimeDelta::hours(5));
+        assert!(!hub.test_rule("Porch On").unwrap().passes());
+    }
+
+    #[test]
+    fn homehub_notifies_observers_of_failing_rule_actions() {
+        let mut hub = HomeHub::new();
+        let observer = DummyObserver::new();
+        let events = Rc::clone(&observer.events);
+        hub.register_observer(Box::new(observer));
+        hub.register_device(Box::new(Light::new("Lamp"))).unwrap();
+        hub.add_rule(
+            Rule::new("Broken", "Lamp power becomes on".parse().unwrap()).action(Action::Command {
+                device: "Missing".to_string(),
+                command: Command::TurnOn,
+            }),
+        )
+        .unwrap();
+
+        hub.execute_device_command("Lamp", Command::TurnOn).unwrap();
+        assert_eq!(
+            events.borrow().last().unwrap(),
+            "Notification:Broken:failed to turn_on Missing: Device 'Missing' not found"
+        );
     }
 }
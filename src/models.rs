@@ -1,2 +1,14 @@
+pub mod argument;
+pub mod capability;
+pub mod color;
+pub mod command;
 pub mod device;
+pub mod energy;
 pub mod event;
+pub mod group;
+pub mod reading;
+pub mod rule;
+pub mod scene;
+pub mod state;
+pub mod temperature;
+pub mod topology;
@@ -0,0 +1,228 @@
+//! # Argument Module
+//!
+//! This module defines the value types carried as arguments by a
+//! [`Command`](crate::models::command::Command): lock credentials, fan speeds
+//! and directions, HVAC modes and media tracks.
+//!
+//! They live here rather than next to the devices that act on them so that
+//! the models layer never depends on concrete devices.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::argument::{FanSpeed, HvacMode};
+//!
+//! assert_eq!("40%".parse::<FanSpeed>().unwrap(), FanSpeed::Percent(40));
+//! assert_eq!("fan".parse::<HvacMode>().unwrap(), HvacMode::FanOnly);
+//! ```
+
+use crate::error::PulseHomeError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A credential presented when unlocking a lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credential {
+    /// A numeric PIN code typed on the keypad.
+    Pin(String),
+    /// A user authenticated by the app.
+    User(String),
+}
+
+impl fmt::Display for Credential {
+    /// Formats the credential without revealing PIN digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credential::Pin(_) => write!(f, "PIN code"),
+            Credential::User(user) => write!(f, "user:{}", user),
+        }
+    }
+}
+
+/// A requested fan speed: a preset or an exact percentage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanSpeed {
+    Off,
+    Low,
+    Medium,
+    High,
+    /// An exact speed between 0% and 100%.
+    Percent(u8),
+}
+
+impl FanSpeed {
+    /// Returns the speed in percent.
+    pub fn percent(self) -> u8 {
+        match self {
+            FanSpeed::Off => 0,
+            FanSpeed::Low => 33,
+            FanSpeed::Medium => 66,
+            FanSpeed::High => 100,
+            FanSpeed::Percent(percent) => percent,
+        }
+    }
+
+    /// Returns the preset covering a speed in percent.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::argument::FanSpeed;
+    ///
+    /// assert_eq!(FanSpeed::preset_for(50), FanSpeed::Medium);
+    /// assert_eq!(FanSpeed::preset_for(0), FanSpeed::Off);
+    /// ```
+    pub fn preset_for(percent: u8) -> FanSpeed {
+        match percent {
+            0 => FanSpeed::Off,
+            1..=33 => FanSpeed::Low,
+            34..=66 => FanSpeed::Medium,
+            _ => FanSpeed::High,
+        }
+    }
+}
+
+impl fmt::Display for FanSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FanSpeed::Off => write!(f, "off"),
+            FanSpeed::Low => write!(f, "low"),
+            FanSpeed::Medium => write!(f, "medium"),
+            FanSpeed::High => write!(f, "high"),
+            FanSpeed::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+impl FromStr for FanSpeed {
+    type Err = PulseHomeError;
+
+    /// Parses a preset (`off`, `low`, `medium`, `high`) or a percentage (`40`, `40%`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(FanSpeed::Off),
+            "low" => Ok(FanSpeed::Low),
+            "medium" | "med" => Ok(FanSpeed::Medium),
+            "high" => Ok(FanSpeed::High),
+            other => other
+                .trim_end_matches('%')
+                .parse()
+                .ok()
+                .filter(|percent| *percent <= 100)
+                .map(FanSpeed::Percent)
+                .ok_or_else(|| {
+                    PulseHomeError::InvalidArgument(format!(
+                        "invalid fan speed '{}' (expected off, low, medium, high or 0–100%)",
+                        s
+                    ))
+                }),
+        }
+    }
+}
+
+/// The direction a ceiling fan's blades turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanDirection {
+    /// Pushes air down (summer).
+    Forward,
+    /// Pulls air up, spreading warm air from the ceiling (winter).
+    Reverse,
+}
+
+impl fmt::Display for FanDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FanDirection::Forward => "forward",
+            FanDirection::Reverse => "reverse",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for FanDirection {
+    type Err = PulseHomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "forward" | "summer" => Ok(FanDirection::Forward),
+            "reverse" | "winter" => Ok(FanDirection::Reverse),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "unknown fan direction '{}' (expected forward or reverse)",
+                s
+            ))),
+        }
+    }
+}
+
+/// The operating mode selected on a thermostat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HvacMode {
+    Off,
+    Heat,
+    Cool,
+    /// Heats below the heating setpoint and cools above the cooling setpoint.
+    Auto,
+    /// Circulates air without heating or cooling.
+    FanOnly,
+}
+
+impl fmt::Display for HvacMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HvacMode::Off => "off",
+            HvacMode::Heat => "heat",
+            HvacMode::Cool => "cool",
+            HvacMode::Auto => "auto",
+            HvacMode::FanOnly => "fan_only",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for HvacMode {
+    type Err = PulseHomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(HvacMode::Off),
+            "heat" => Ok(HvacMode::Heat),
+            "cool" => Ok(HvacMode::Cool),
+            "auto" => Ok(HvacMode::Auto),
+            "fan_only" | "fan" => Ok(HvacMode::FanOnly),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "unknown HVAC mode '{}' (expected off, heat, cool, auto or fan_only)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Now-playing metadata for a queued track.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    pub title: String,
+    pub artist: Option<String>,
+}
+
+impl Track {
+    /// Creates a track with the given title and no artist.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            artist: None,
+        }
+    }
+
+    /// Returns the track with its artist set.
+    pub fn by(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+}
+
+impl fmt::Display for Track {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.artist {
+            Some(artist) => write!(f, "{} – {}", self.title, artist),
+            None => write!(f, "{}", self.title),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! # Capability Module
+//!
+//! Describes what a [`Device`](crate::models::device::Device) can do, so that
+//! clients do not have to hard-code it per device type.
+//!
+//! A device advertises its [`Capabilities`]: the commands it accepts (with a
+//! schema for each argument), the sensor readings it ingests and the
+//! attributes that make up its state. The [`HomeHub`](crate::mediator::HomeHub)
+//! checks commands and readings against them before dispatch, the CLI renders
+//! per-device help from them, and other UIs can build controls generically.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::capability::{ArgKind, AttributeKind, Capabilities, CommandSpec};
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::state::Unit;
+//!
+//! let capabilities = Capabilities::new()
+//!     .command(CommandSpec::new("turn_on", "Turn the light on"))
+//!     .command(
+//!         CommandSpec::new("dim", "Set the brightness")
+//!             .arg("level", ArgKind::integer(0, 100, Unit::Percent)),
+//!     )
+//!     .attribute("power", AttributeKind::Bool);
+//!
+//! assert!(capabilities.supports(&Command::TurnOn));
+//! assert!(!capabilities.supports(&Command::Lock));
+//! assert_eq!(capabilities.find("dim").unwrap().usage(), "dim <level:0-100%>");
+//! ```
+
+use crate::models::{command::Command, reading::SensorReading, state::Unit};
+use std::fmt;
+
+/// The kind of value a command argument takes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgKind {
+    /// A whole number within an inclusive range, optionally in a unit.
+    Integer {
+        min: i64,
+        max: i64,
+        unit: Option<Unit>,
+    },
+    /// A temperature, in the session unit unless suffixed (`22.5`, `72F`).
+    Temperature,
+    /// A length of time, counted in the given unit (seconds, minutes or hours).
+    Duration(Unit),
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+    /// An RGB color (`#rrggbb` or `hsv(h,s,v)`).
+    Color,
+    /// Free-form text.
+    Text,
+}
+
+impl ArgKind {
+    /// A whole number between `min` and `max` in `unit`.
+    pub fn integer(min: i64, max: i64, unit: Unit) -> Self {
+        ArgKind::Integer {
+            min,
+            max,
+            unit: Some(unit),
+        }
+    }
+
+    /// A unitless whole number between `min` and `max`.
+    pub fn count(min: i64, max: i64) -> Self {
+        ArgKind::Integer {
+            min,
+            max,
+            unit: None,
+        }
+    }
+}
+
+impl fmt::Display for ArgKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgKind::Integer { min, max, unit } => {
+                // `..` keeps a negative lower bound readable (`-90..90°`).
+                let separator = if *min < 0 { ".." } else { "-" };
+                write!(f, "{}{}{}", min, separator, max)?;
+                match unit {
+                    Some(unit) => write!(f, "{}", unit.symbol()),
+                    None => Ok(()),
+                }
+            }
+            ArgKind::Temperature => write!(f, "temperature"),
+            ArgKind::Duration(unit) => write!(f, "{}", unit.symbol()),
+            ArgKind::Choice(choices) => write!(f, "{}", choices.join("|")),
+            ArgKind::Color => write!(f, "#rrggbb|hsv(h,s,v)"),
+            ArgKind::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// A single argument of a command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    /// Word that introduces the argument on the command line (e.g. `over`).
+    pub keyword: Option<&'static str>,
+    pub optional: bool,
+}
+
+impl fmt::Display for ArgSpec {
+    /// Formats the argument as shown in usage lines, e.g. `[over <secs:s>]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self.kind {
+            ArgKind::Choice(_) => format!("<{}>", self.kind),
+            _ => format!("<{}:{}>", self.name, self.kind),
+        };
+        let arg = match self.keyword {
+            Some(keyword) => format!("{} {}", keyword, value),
+            None => value,
+        };
+        if self.optional {
+            write!(f, "[{}]", arg)
+        } else {
+            write!(f, "{}", arg)
+        }
+    }
+}
+
+/// A command a device accepts, named after its CLI verb (see [`Command::name`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub args: Vec<ArgSpec>,
+}
+
+impl CommandSpec {
+    /// Creates a command without arguments.
+    pub fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            args: Vec::new(),
+        }
+    }
+
+    /// Returns the command with a required argument appended.
+    pub fn arg(self, name: &'static str, kind: ArgKind) -> Self {
+        self.with_arg(name, kind, None, false)
+    }
+
+    /// Returns the command with an optional argument introduced by `keyword`.
+    pub fn optional_arg(self, keyword: &'static str, name: &'static str, kind: ArgKind) -> Self {
+        self.with_arg(name, kind, Some(keyword), true)
+    }
+
+    /// Returns the command with a required argument introduced by `keyword`.
+    pub fn keyword_arg(self, keyword: &'static str, name: &'static str, kind: ArgKind) -> Self {
+        self.with_arg(name, kind, Some(keyword), false)
+    }
+
+    fn with_arg(
+        mut self,
+        name: &'static str,
+        kind: ArgKind,
+        keyword: Option<&'static str>,
+        optional: bool,
+    ) -> Self {
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            keyword,
+            optional,
+        });
+        self
+    }
+
+    /// Returns the command's usage line, without the device name.
+    pub fn usage(&self) -> String {
+        std::iter::once(self.name.to_string())
+            .chain(self.args.iter().map(ArgSpec::to_string))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The kind of value an attribute of the device's state holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeKind {
+    Bool,
+    Int,
+    Float,
+    Measure(Unit),
+    /// A temperature, shown in the viewer's preferred unit.
+    Temperature,
+    Enum(&'static [&'static str]),
+    Text,
+}
+
+impl fmt::Display for AttributeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeKind::Bool => write!(f, "on|off"),
+            AttributeKind::Int => write!(f, "integer"),
+            AttributeKind::Float => write!(f, "number"),
+            AttributeKind::Measure(unit) => write!(f, "{}", unit.symbol()),
+            AttributeKind::Temperature => write!(f, "temperature"),
+            AttributeKind::Enum(values) => write!(f, "{}", values.join("|")),
+            AttributeKind::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// An attribute of a device's state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeSpec {
+    pub name: &'static str,
+    pub kind: AttributeKind,
+}
+
+/// Everything a device advertises about itself.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Capabilities {
+    /// Commands accepted through [`Device::execute_command`](crate::models::device::Device::execute_command).
+    pub commands: Vec<CommandSpec>,
+    /// Names of the readings accepted (see [`SensorReading::name`]).
+    pub readings: Vec<&'static str>,
+    /// Attributes that may appear in the device's state.
+    pub attributes: Vec<AttributeSpec>,
+}
+
+impl Capabilities {
+    /// Creates an empty set of capabilities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the capabilities with a command added.
+    pub fn command(mut self, spec: CommandSpec) -> Self {
+        self.commands.push(spec);
+        self
+    }
+
+    /// Returns the capabilities with a reading added.
+    pub fn reading(mut self, name: &'static str) -> Self {
+        self.readings.push(name);
+        self
+    }
+
+    /// Returns the capabilities with a state attribute added.
+    pub fn attribute(mut self, name: &'static str, kind: AttributeKind) -> Self {
+        self.attributes.push(AttributeSpec { name, kind });
+        self
+    }
+
+    /// Returns the spec of the command with the given name, if supported.
+    pub fn find(&self, name: &str) -> Option<&CommandSpec> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+
+    /// Returns whether the device accepts this command.
+    pub fn supports(&self, command: &Command) -> bool {
+        self.find(command.name()).is_some()
+    }
+
+    /// Returns whether the device ingests this kind of reading.
+    pub fn accepts(&self, reading: &SensorReading) -> bool {
+        self.readings.contains(&reading.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_lines() {
+        let spec = CommandSpec::new("dim", "Set the brightness")
+            .arg("level", ArgKind::integer(0, 100, Unit::Percent))
+            .optional_arg("over", "secs", ArgKind::Duration(Unit::Second));
+        assert_eq!(spec.usage(), "dim <level:0-100%> [over <secs:s>]");
+
+        let spec = CommandSpec::new("mode", "Set the HVAC mode")
+            .arg("mode", ArgKind::Choice(&["off", "heat", "cool"]));
+        assert_eq!(spec.usage(), "mode <off|heat|cool>");
+
+        let spec =
+            CommandSpec::new("source", "Switch input").keyword_arg("to", "source", ArgKind::Text);
+        assert_eq!(spec.usage(), "source to <source:text>");
+
+        let spec = CommandSpec::new("tilt", "Tilt the slats")
+            .arg("angle", ArgKind::integer(-90, 90, Unit::Degree));
+        assert_eq!(spec.usage(), "tilt <angle:-90..90°>");
+    }
+
+    #[test]
+    fn supports_commands_and_readings_by_name() {
+        let capabilities = Capabilities::new()
+            .command(CommandSpec::new("mute", "Mute"))
+            .reading("power");
+        assert!(capabilities.supports(&Command::SetMute(true)));
+        assert!(!capabilities.supports(&Command::SetMute(false)));
+        assert!(capabilities.accepts(&SensorReading::Power(10.0)));
+        assert!(!capabilities.accepts(&SensorReading::Voltage(230.0)));
+    }
+}
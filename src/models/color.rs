@@ -0,0 +1,146 @@
+//! # Color Module
+//!
+//! Defines [`Color`], an RGB color used by color-capable lights.
+//!
+//! Colors can be parsed from hex notation (`#ff8800`) or HSV notation
+//! (`hsv(30,100,100)`: hue in degrees, saturation and value in percent).
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::color::Color;
+//!
+//! let orange: Color = "#ff8800".parse().unwrap();
+//! assert_eq!(orange, Color::new(255, 136, 0));
+//! assert_eq!(orange.to_string(), "#ff8800");
+//!
+//! let red: Color = "hsv(0,100,100)".parse().unwrap();
+//! assert_eq!(red, Color::new(255, 0, 0));
+//! ```
+
+use crate::error::PulseHomeError;
+use std::fmt;
+use std::str::FromStr;
+
+/// An RGB color with 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// Creates a color from its red, green and blue channels.
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a color from hue (degrees), saturation and value (both 0–100%).
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let s = (saturation / 100.0).clamp(0.0, 1.0);
+        let v = (value / 100.0).clamp(0.0, 1.0);
+
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = v - c;
+        let channel = |n: f64| ((n + m) * 255.0).round() as u8;
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    /// Linearly interpolates between two colors; `t` is clamped to `0.0..=1.0`.
+    pub fn lerp(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = PulseHomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PulseHomeError::InvalidArgument(format!("invalid color '{}'", s));
+        let trimmed = s.trim();
+
+        if let Some(hex) = trimmed.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            return Ok(Color::new(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        if let Some(args) = trimmed
+            .strip_prefix("hsv(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let parts: Vec<f64> = args
+                .split(',')
+                .map(|p| p.trim().parse::<f64>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid())?;
+            if let [h, s, v] = parts[..]
+                && (0.0..=100.0).contains(&s)
+                && (0.0..=100.0).contains(&v)
+            {
+                return Ok(Color::from_hsv(h, s, v));
+            }
+        }
+
+        Err(invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_color() {
+        assert_eq!("#00FF7f".parse::<Color>().unwrap(), Color::new(0, 255, 127));
+        assert!("ff8800".parse::<Color>().is_err());
+        assert!("#ff88".parse::<Color>().is_err());
+        assert!("#gg8800".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn parse_hsv_color() {
+        assert_eq!(
+            "hsv(120,100,100)".parse::<Color>().unwrap(),
+            Color::new(0, 255, 0)
+        );
+        assert_eq!(
+            "hsv(240,100,50)".parse::<Color>().unwrap(),
+            Color::new(0, 0, 128)
+        );
+        assert!("hsv(0,150,100)".parse::<Color>().is_err());
+        assert!("hsv(0,100)".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn color_lerp() {
+        let black = Color::new(0, 0, 0);
+        let white = Color::new(255, 255, 255);
+        assert_eq!(black.lerp(white, 0.5), Color::new(128, 128, 128));
+        assert_eq!(black.lerp(white, 2.0), white);
+    }
+}
@@ -0,0 +1,351 @@
+//! # Command Module
+//!
+//! This module defines the [`Command`] enum: an instruction sent by a client
+//! (such as the CLI) through the [`HomeHub`](crate::mediator::HomeHub) to a
+//! [`Device`](crate::models::device::Device).
+//!
+//! Unlike [`EventType`], which only describes *what happened*, a `Command`
+//! carries the arguments needed to perform the action (e.g. the target
+//! temperature for a thermostat).
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::event::EventType;
+//! use pulsehome::models::temperature::Temperature;
+//!
+//! let command = Command::SetTemp(Temperature::from_celsius(24.0));
+//! assert_eq!(command.event_type(), EventType::SetTemp);
+//! ```
+
+use crate::models::{
+    argument::{Credential, FanDirection, FanSpeed, HvacMode, Track},
+    color::Color,
+    event::EventType,
+    temperature::Temperature,
+};
+use chrono::{DateTime, Local};
+use std::time::Duration;
+
+/// An instruction for a device, together with its arguments.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Command {
+    TurnOn,
+    TurnOff,
+    Lock,
+    /// Unlocks a lock, presenting a credential if the lock requires one.
+    Unlock(Option<Credential>),
+    /// Enrolls a PIN code on a lock under a label, optionally expiring at `expires`.
+    AddPin {
+        label: String,
+        pin: String,
+        expires: Option<DateTime<Local>>,
+    },
+    /// Revokes the PIN code with the given label.
+    RevokePin(String),
+    /// Authorizes a user to unlock a lock without a PIN code.
+    AuthorizeUser(String),
+    /// Revokes a user's authorization.
+    RevokeUser(String),
+    /// Sets the delay after which an unlocked lock locks itself (`None` disables).
+    SetAutoRelock(Option<Duration>),
+    /// Reports that a lock's bolt is jammed.
+    ReportJam,
+    /// Clears a lock's jammed state after it has been fixed.
+    ClearJam,
+    /// Fully opens a window covering or garage door.
+    Open,
+    /// Fully closes a window covering or garage door.
+    Close,
+    /// Halts a window covering or garage door wherever it currently is,
+    /// stops an irrigation controller and clears its queue, or stops playback.
+    Stop,
+    /// Moves a window covering to a position (0% closed – 100% open).
+    SetPosition(u8),
+    /// Tilts a window covering's slats to an angle in degrees (−90 to 90).
+    SetTilt(i16),
+    /// Sets a fan's speed to a preset or percentage (`FanSpeed::Off` stops it).
+    SetFanSpeed(FanSpeed),
+    /// Turns a standing fan's oscillation on or off.
+    SetOscillation(bool),
+    /// Sets the direction a ceiling fan's blades turn.
+    SetFanDirection(FanDirection),
+    /// Waters an irrigation zone for `duration`, after any runs already queued.
+    RunZone {
+        zone: u8,
+        duration: Duration,
+    },
+    /// Suspends an irrigation controller's watering for a while (`None` cancels).
+    SetRainDelay(Option<Duration>),
+    /// Starts or resumes playback on a media player.
+    Play,
+    /// Pauses a playing media player.
+    Pause,
+    /// Skips to the next queued track.
+    Next,
+    /// Goes back to the previous queued track.
+    Previous,
+    /// Sets a media player's volume (0–100%).
+    SetVolume(u8),
+    /// Mutes (`true`) or unmutes a media player, keeping its volume.
+    SetMute(bool),
+    /// Switches a media player's input source (e.g. `"HDMI 1"`, `"spotify"`).
+    SetSource(String),
+    /// Adds a track to the end of a media player's queue.
+    QueueTrack(Track),
+    /// Runs a smoke or CO detector's self-test.
+    Test,
+    /// Temporarily silences a sounding smoke or CO detector.
+    Silence,
+    /// Sets the target temperature.
+    ///
+    /// A thermostat in heat or cool mode updates the matching setpoint, moving
+    /// the other one along if needed to keep heating at or below cooling; in
+    /// any other mode both setpoints are set to this value.
+    SetTemp(Temperature),
+    /// Changes a thermostat's operating mode.
+    SetHvacMode(HvacMode),
+    /// Sets a thermostat's heating and cooling setpoints.
+    SetSetpoints {
+        heat: Temperature,
+        cool: Temperature,
+    },
+    /// Reports the ambient temperature measured by a thermostat's sensor.
+    ReportAmbient(Temperature),
+    /// Sets a light's brightness (0–100%), optionally fading over `transition`.
+    SetBrightness {
+        level: u8,
+        transition: Option<Duration>,
+    },
+    /// Sets a light's white color temperature in kelvin, optionally fading over `transition`.
+    SetColorTemp {
+        kelvin: u16,
+        transition: Option<Duration>,
+    },
+    /// Sets a light's RGB color, optionally fading over `transition`.
+    SetColor {
+        color: Color,
+        transition: Option<Duration>,
+    },
+}
+
+impl Command {
+    /// Returns the command's name as typed in the CLI (e.g. `"set_temp"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::TurnOn => "turn_on",
+            Command::TurnOff => "turn_off",
+            Command::Lock => "lock",
+            Command::Unlock(_) => "unlock",
+            Command::AddPin { .. } => "add_pin",
+            Command::RevokePin(_) => "revoke_pin",
+            Command::AuthorizeUser(_) => "authorize",
+            Command::RevokeUser(_) => "deauthorize",
+            Command::SetAutoRelock(_) => "auto_relock",
+            Command::ReportJam => "jam",
+            Command::ClearJam => "clear_jam",
+            Command::Open => "open",
+            Command::Close => "close",
+            Command::Stop => "stop",
+            Command::SetPosition(_) => "position",
+            Command::SetTilt(_) => "tilt",
+            Command::SetFanSpeed(_) => "fan_speed",
+            Command::SetOscillation(_) => "oscillate",
+            Command::SetFanDirection(_) => "fan_direction",
+            Command::RunZone { .. } => "run_zone",
+            Command::SetRainDelay(_) => "rain_delay",
+            Command::Play => "play",
+            Command::Pause => "pause",
+            Command::Next => "next",
+            Command::Previous => "previous",
+            Command::SetVolume(_) => "volume",
+            Command::SetMute(true) => "mute",
+            Command::SetMute(false) => "unmute",
+            Command::SetSource(_) => "source",
+            Command::QueueTrack(_) => "queue",
+            Command::Test => "test",
+            Command::Silence => "silence",
+            Command::SetTemp(_) => "set_temp",
+            Command::SetHvacMode(_) => "mode",
+            Command::SetSetpoints { .. } => "setpoints",
+            Command::ReportAmbient(_) => "ambient",
+            Command::SetBrightness { .. } => "dim",
+            Command::SetColorTemp { .. } => "color_temp",
+            Command::SetColor { .. } => "color",
+        }
+    }
+
+    /// Returns the values typed after the device name in the CLI (e.g. `"40"`
+    /// for `dim <light_name> 40`), or `None` for commands that take only a
+    /// device name. PIN codes are masked.
+    pub fn argument(&self) -> Option<String> {
+        let over = |transition: &Option<Duration>| {
+            transition.map_or_else(String::new, |t| format!(" over {}s", t.as_secs_f64()))
+        };
+        // Whole units print as a bare number, anything else in seconds
+        let amount = |duration: &Duration, secs_per_unit: u64| match duration.as_secs() {
+            secs if secs % secs_per_unit == 0 => (secs / secs_per_unit).to_string(),
+            secs => format!("{}s", secs),
+        };
+        match self {
+            Command::Unlock(Some(Credential::Pin(_))) => Some("pin ****".to_string()),
+            Command::Unlock(Some(Credential::User(user))) => Some(format!("as {}", user)),
+            Command::AddPin { label, .. } => Some(format!("{} ****", label)),
+            Command::RevokePin(value)
+            | Command::AuthorizeUser(value)
+            | Command::RevokeUser(value) => Some(value.clone()),
+            Command::SetAutoRelock(delay) => {
+                Some(delay.map_or_else(|| "off".to_string(), |d| d.as_secs().to_string()))
+            }
+            Command::SetPosition(position) => Some(position.to_string()),
+            Command::SetTilt(tilt) => Some(tilt.to_string()),
+            Command::RunZone { zone, duration } => {
+                Some(format!("{} {}", zone, amount(duration, 60)))
+            }
+            Command::SetRainDelay(delay) => {
+                Some(delay.map_or_else(|| "off".to_string(), |d| amount(&d, 3600)))
+            }
+            Command::SetVolume(volume) => Some(volume.to_string()),
+            Command::SetSource(source) => Some(format!("to {}", source)),
+            Command::QueueTrack(track) => Some(match &track.artist {
+                Some(artist) => format!("track {} by {}", track.title, artist),
+                None => format!("track {}", track.title),
+            }),
+            Command::SetTemp(temp) | Command::ReportAmbient(temp) => Some(temp.to_string()),
+            Command::SetHvacMode(mode) => Some(mode.to_string()),
+            Command::SetSetpoints { heat, cool } => Some(format!("{} {}", heat, cool)),
+            Command::SetFanSpeed(speed) => Some(speed.to_string()),
+            Command::SetOscillation(on) => Some(if *on { "on" } else { "off" }.to_string()),
+            Command::SetFanDirection(direction) => Some(direction.to_string()),
+            Command::SetBrightness { level, transition } => {
+                Some(format!("{}{}", level, over(transition)))
+            }
+            Command::SetColorTemp { kelvin, transition } => {
+                Some(format!("{}{}", kelvin, over(transition)))
+            }
+            Command::SetColor { color, transition } => {
+                Some(format!("{}{}", color, over(transition)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the [`EventType`] emitted when this command succeeds.
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Command::TurnOn => EventType::TurnOn,
+            Command::TurnOff => EventType::TurnOff,
+            Command::Lock => EventType::Lock,
+            Command::Unlock(_) => EventType::Unlock,
+            Command::AddPin { .. } | Command::AuthorizeUser(_) => EventType::AccessGranted,
+            Command::RevokePin(_) | Command::RevokeUser(_) => EventType::AccessRevoked,
+            Command::SetAutoRelock(_) => EventType::SetAutoRelock,
+            Command::ReportJam => EventType::LockJammed,
+            Command::ClearJam => EventType::JamCleared,
+            Command::Open => EventType::Open,
+            Command::Close => EventType::Close,
+            Command::Stop => EventType::Stop,
+            Command::SetPosition(_) => EventType::SetPosition,
+            Command::SetTilt(_) => EventType::SetTilt,
+            Command::SetFanSpeed(_) => EventType::SetFanSpeed,
+            Command::SetOscillation(_) => EventType::SetOscillation,
+            Command::SetFanDirection(_) => EventType::SetFanDirection,
+            Command::RunZone { .. } => EventType::RunZone,
+            Command::SetRainDelay(_) => EventType::SetRainDelay,
+            Command::Play => EventType::Play,
+            Command::Pause => EventType::Pause,
+            Command::Next | Command::Previous => EventType::TrackChanged,
+            Command::SetVolume(_) => EventType::SetVolume,
+            Command::SetMute(_) => EventType::SetMute,
+            Command::SetSource(_) => EventType::SetSource,
+            Command::QueueTrack(_) => EventType::TrackQueued,
+            Command::Test => EventType::AlarmTest,
+            Command::Silence => EventType::AlarmSilenced,
+            Command::SetTemp(_) => EventType::SetTemp,
+            Command::SetHvacMode(_) => EventType::SetHvacMode,
+            Command::SetSetpoints { .. } => EventType::SetSetpoints,
+            Command::ReportAmbient(_) => EventType::AmbientTemperatureChanged,
+            Command::SetBrightness { .. } => EventType::SetBrightness,
+            Command::SetColorTemp { .. } => EventType::SetColorTemp,
+            Command::SetColor { .. } => EventType::SetColor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_maps_to_event_type() {
+        assert_eq!(Command::TurnOn.event_type(), EventType::TurnOn);
+        assert_eq!(Command::Unlock(None).event_type(), EventType::Unlock);
+        assert_eq!(
+            Command::RevokeUser("alice".into()).event_type(),
+            EventType::AccessRevoked
+        );
+        assert_eq!(
+            Command::SetTemp(Temperature::from_celsius(18.0)).event_type(),
+            EventType::SetTemp
+        );
+    }
+
+    #[test]
+    fn command_name() {
+        assert_eq!(Command::TurnOff.name(), "turn_off");
+        assert_eq!(
+            Command::SetTemp(Temperature::from_celsius(18.0)).name(),
+            "set_temp"
+        );
+    }
+
+    #[test]
+    fn command_argument() {
+        assert_eq!(Command::TurnOn.argument(), None);
+        assert_eq!(
+            Command::SetBrightness {
+                level: 40,
+                transition: Some(Duration::from_secs(5))
+            }
+            .argument()
+            .as_deref(),
+            Some("40 over 5s")
+        );
+        assert_eq!(
+            Command::QueueTrack(Track::new("So What").by("Miles Davis"))
+                .argument()
+                .as_deref(),
+            Some("track So What by Miles Davis")
+        );
+        assert_eq!(
+            Command::SetRainDelay(None).argument().as_deref(),
+            Some("off")
+        );
+        assert_eq!(
+            Command::SetRainDelay(Some(Duration::from_secs(7200)))
+                .argument()
+                .as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            Command::SetRainDelay(Some(Duration::from_secs(1800)))
+                .argument()
+                .as_deref(),
+            Some("1800s")
+        );
+        for (secs, shown) in [(600, "3 10"), (90, "3 90s")] {
+            let duration = Duration::from_secs(secs);
+            assert_eq!(
+                Command::RunZone { zone: 3, duration }.argument().as_deref(),
+                Some(shown)
+            );
+        }
+        // PIN codes never appear in listings
+        assert_eq!(
+            Command::Unlock(Some(Credential::Pin("1234".into())))
+                .argument()
+                .as_deref(),
+            Some("pin ****")
+        );
+    }
+}
@@ -6,8 +6,50 @@
 //!
 //! The trait provides a common interface for executing commands and retrieving state,
 //! allowing the **HomeHub mediator** to interact uniformly with all devices.
+//! Sensors additionally accept measurements through [`Device::report_reading`].
+//! Every device advertises what it accepts through [`Device::capabilities`].
 
-use crate::models::event::{Event, EventType};
+use crate::error::PulseHomeError;
+use crate::models::{
+    capability::Capabilities, command::Command, energy::MeterReading, event::Event,
+    reading::SensorReading, state::DeviceState,
+};
+use std::fmt;
+use std::time::Duration;
+
+/// Stable identifier assigned to a device when it is registered with the
+/// [`HomeHub`](crate::mediator::HomeHub).
+///
+/// IDs are never reused, so they remain valid even if the device is renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeviceId(pub u32);
+
+impl fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A point-in-time view of a registered device, as reported by the hub.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceSnapshot {
+    pub id: DeviceId,
+    pub name: String,
+    pub device_type: String,
+    pub state: DeviceState,
+}
+
+impl DeviceSnapshot {
+    /// Captures the current name, type and state of a device.
+    pub fn of(id: DeviceId, device: &dyn Device) -> Self {
+        Self {
+            id,
+            name: device.get_name().to_string(),
+            device_type: device.get_type().to_string(),
+            state: device.get_state(),
+        }
+    }
+}
 
 /// Represents a generic smart device.
 ///
@@ -17,28 +59,78 @@ pub trait Device {
     /// Returns the unique name of the device.
     fn get_name(&self) -> &str;
 
+    /// Changes the device's name. Called by the hub when a device is renamed.
+    fn set_name(&mut self, name: &str);
+
     /// Returns a human-readable type of the device (e.g., `"Light"`).
     fn get_type(&self) -> &str;
 
     /// Executes a command on the device.
     ///
-    /// Returns an [`Event`] representing the result or an error message.
-    fn execute_command(&mut self, command: EventType) -> Result<Event, Box<dyn std::error::Error>>;
+    /// Returns an [`Event`] representing the result, or a [`PulseHomeError`]
+    /// describing why the command was rejected.
+    fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError>;
+
+    /// Returns the current structured state of the device.
+    fn get_state(&self) -> DeviceState;
+
+    /// Returns the commands, readings and state attributes the device supports.
+    ///
+    /// The hub rejects commands and readings that are not advertised here
+    /// before they reach [`Device::execute_command`] or [`Device::report_reading`].
+    fn capabilities(&self) -> Capabilities;
 
-    /// Returns the current state of the device as a string.
-    fn get_state(&self) -> String;
+    /// Ingests a measurement reported by the device's sensor.
+    ///
+    /// Returns an [`Event`] if the reading changed the device's state, or
+    /// `None` if it matched the previous reading. Defaults to rejecting the
+    /// reading with [`PulseHomeError::UnsupportedCommand`].
+    fn report_reading(&mut self, reading: SensorReading) -> Result<Option<Event>, PulseHomeError> {
+        Err(PulseHomeError::unsupported_reading(
+            self.get_type(),
+            &reading,
+        ))
+    }
+
+    /// Advances the device's internal clock by `elapsed`.
+    ///
+    /// Called periodically by [`HomeHub::tick`](crate::mediator::HomeHub::tick)
+    /// so devices can progress time-based behavior such as fades. Returns any
+    /// events produced along the way. Defaults to doing nothing.
+    fn tick(&mut self, _elapsed: Duration) -> Vec<Event> {
+        Vec::new()
+    }
+
+    /// Returns events raised as side effects of the last command, such as a
+    /// thermostat switching from idle to heating.
+    ///
+    /// The hub forwards these to observers right after the command's own
+    /// event. Defaults to none.
+    fn take_pending_events(&mut self) -> Vec<Event> {
+        Vec::new()
+    }
+
+    /// Returns the device's power and energy meter, if it has one.
+    ///
+    /// The hub samples metered devices to report consumption over time.
+    /// Defaults to `None`.
+    fn meter(&self) -> Option<MeterReading> {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::event::EventType;
+    use crate::models::state::AttributeValue;
+    use crate::models::temperature::Temperature;
 
     /// Dummy device for testing the Device trait
     struct DummyDevice {
         name: String,
         device_type: String,
-        state: String,
+        state: bool,
     }
 
     impl DummyDevice {
@@ -46,7 +138,7 @@ mod tests {
             Self {
                 name: name.to_string(),
                 device_type: device_type.to_string(),
-                state: "off".to_string(),
+                state: false,
             }
         }
     }
@@ -56,47 +148,86 @@ mod tests {
             &self.name
         }
 
+        fn set_name(&mut self, name: &str) {
+            self.name = name.to_string();
+        }
+
         fn get_type(&self) -> &str {
             &self.device_type
         }
 
-        fn execute_command(
-            &mut self,
-            command: EventType,
-        ) -> Result<Event, Box<dyn std::error::Error>> {
+        fn execute_command(&mut self, command: Command) -> Result<Event, PulseHomeError> {
             match command {
-                EventType::TurnOn => self.state = "on".to_string(),
-                EventType::TurnOff => self.state = "off".to_string(),
-                _ => return Err("Unsupported command for DummyDevice".into()),
+                Command::TurnOn => self.state = true,
+                Command::TurnOff => self.state = false,
+                _ => return Err(PulseHomeError::unsupported(&self.device_type, &command)),
             }
 
             Ok(Event::new(
                 self.name.clone(),
                 self.device_type.clone(),
-                command,
-                Some(self.state.clone()),
+                command.event_type(),
+                Some(self.get_state()),
             ))
         }
 
-        fn get_state(&self) -> String {
-            self.state.clone()
+        fn get_state(&self) -> DeviceState {
+            DeviceState::new().with("power", AttributeValue::Bool(self.state))
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            use crate::models::capability::{AttributeKind, CommandSpec};
+            Capabilities::new()
+                .command(CommandSpec::new("turn_on", "Turn on"))
+                .command(CommandSpec::new("turn_off", "Turn off"))
+                .attribute("power", AttributeKind::Bool)
         }
     }
 
+    #[test]
+    fn device_id_display() {
+        assert_eq!(DeviceId(7).to_string(), "#7");
+    }
+
+    #[test]
+    fn snapshot_captures_device() {
+        let device = DummyDevice::new("TestLight", "Light");
+        let snapshot = DeviceSnapshot::of(DeviceId(3), &device);
+        assert_eq!(snapshot.id, DeviceId(3));
+        assert_eq!(snapshot.name, "TestLight");
+        assert_eq!(snapshot.device_type, "Light");
+        assert_eq!(snapshot.state.to_string(), "off");
+    }
+
     #[test]
     fn dummy_device_turn_on() {
         let mut device = DummyDevice::new("TestLight", "Light");
-        let event = device.execute_command(EventType::TurnOn).unwrap();
-        assert_eq!(device.get_state(), "on");
+        let event = device.execute_command(Command::TurnOn).unwrap();
+        assert_eq!(device.get_state().to_string(), "on");
         assert_eq!(event.device_name, "TestLight");
         assert_eq!(event.event_type, EventType::TurnOn);
-        assert_eq!(event.payload.unwrap(), "on");
+        assert_eq!(
+            event.payload.unwrap().get("power"),
+            Some(&AttributeValue::Bool(true))
+        );
+    }
+
+    #[test]
+    fn dummy_device_capabilities() {
+        let device = DummyDevice::new("TestLight", "Light");
+        let capabilities = device.capabilities();
+        assert!(capabilities.supports(&Command::TurnOn));
+        assert!(!capabilities.supports(&Command::Lock));
+        assert!(capabilities.readings.is_empty());
     }
 
     #[test]
     fn dummy_device_invalid_command() {
         let mut device = DummyDevice::new("TestDevice", "Generic");
-        let result = device.execute_command(EventType::SetTemp);
-        assert!(result.is_err());
+        let result = device.execute_command(Command::SetTemp(Temperature::from_celsius(22.0)));
+        assert!(matches!(
+            result,
+            Err(PulseHomeError::UnsupportedCommand { .. })
+        ));
     }
 }
@@ -0,0 +1,203 @@
+//! # Energy Module
+//!
+//! Types used to meter and report electricity consumption.
+//!
+//! Metering devices expose a [`MeterReading`] (instantaneous power and a
+//! cumulative energy counter). The [`HomeHub`](crate::mediator::HomeHub)
+//! samples those counters into an [`EnergyLedger`] as time passes, so it can
+//! work out how much each device used over any recent window and build an
+//! [`EnergyReport`] for the whole home.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::device::DeviceId;
+//! use pulsehome::models::energy::EnergyLedger;
+//! use std::time::Duration;
+//!
+//! let mut ledger = EnergyLedger::new();
+//! let kettle = DeviceId(1);
+//! ledger.record(Duration::ZERO, kettle, 10.0);
+//! ledger.record(Duration::from_secs(3600), kettle, 12.5);
+//!
+//! // Usage during the last 30 minutes, given the counter now reads 12.5 kWh.
+//! // The counter is interpolated to 11.25 kWh at the 30 minute mark.
+//! let usage = ledger.usage_since(kettle, Duration::from_secs(1800), 12.5);
+//! assert_eq!(usage, 1.25);
+//! ```
+
+use crate::models::device::DeviceId;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// How long the ledger keeps energy samples.
+pub const HISTORY_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What a metering device currently reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterReading {
+    /// Instantaneous power draw in watts.
+    pub power_w: f64,
+    /// Cumulative energy counter in kilowatt-hours.
+    pub energy_kwh: f64,
+}
+
+/// History of cumulative energy counters, sampled against hub uptime.
+#[derive(Debug, Default)]
+pub struct EnergyLedger {
+    samples: HashMap<DeviceId, VecDeque<(Duration, f64)>>,
+}
+
+impl EnergyLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a device's cumulative energy counter at uptime `at`.
+    ///
+    /// A run of unchanged values is stored as its first and last sample only,
+    /// and samples older than [`HISTORY_RETENTION`] are pruned (keeping the
+    /// last one before the cutoff as a baseline).
+    pub fn record(&mut self, at: Duration, id: DeviceId, energy_kwh: f64) {
+        let samples = self.samples.entry(id).or_default();
+        let len = samples.len();
+        if len >= 2 && samples[len - 2].1 == energy_kwh && samples[len - 1].1 == energy_kwh {
+            samples[len - 1].0 = at;
+        } else {
+            samples.push_back((at, energy_kwh));
+        }
+
+        let cutoff = at.saturating_sub(HISTORY_RETENTION);
+        while samples.len() > 1 && samples[1].0 <= cutoff {
+            samples.pop_front();
+        }
+    }
+
+    /// Drops all samples for a device, e.g. when it is removed from the hub.
+    pub fn forget(&mut self, id: DeviceId) {
+        self.samples.remove(&id);
+    }
+
+    /// Returns the energy (kWh) a device used since uptime `since`, given
+    /// its counter currently reads `current_kwh`.
+    ///
+    /// The counter's value at `since` is interpolated between the samples
+    /// around it. If the device has no sample that old, usage is counted
+    /// from its earliest sample.
+    pub fn usage_since(&self, id: DeviceId, since: Duration, current_kwh: f64) -> f64 {
+        let Some(samples) = self.samples.get(&id) else {
+            return 0.0;
+        };
+        let baseline = match samples.iter().rposition(|(at, _)| *at <= since) {
+            Some(i) => {
+                let (t0, v0) = samples[i];
+                match samples.get(i + 1) {
+                    Some(&(t1, v1)) => {
+                        let t = (since - t0).as_secs_f64() / (t1 - t0).as_secs_f64();
+                        v0 + (v1 - v0) * t
+                    }
+                    None => v0,
+                }
+            }
+            None => samples.front().map_or(current_kwh, |(_, kwh)| *kwh),
+        };
+        (current_kwh - baseline).max(0.0)
+    }
+}
+
+/// Consumption of a single device, as reported by the hub.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceEnergy {
+    pub id: DeviceId,
+    pub name: String,
+    /// Instantaneous power draw in watts.
+    pub power_w: f64,
+    /// Energy used during the report window in kilowatt-hours.
+    pub energy_kwh: f64,
+}
+
+/// Per-device and whole-home consumption over a time window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyReport {
+    /// The window covered, or `None` for everything since the hub started.
+    pub window: Option<Duration>,
+    /// Metered devices, heaviest consumer first.
+    pub devices: Vec<DeviceEnergy>,
+}
+
+impl EnergyReport {
+    /// Returns the whole-home energy used during the window (kWh).
+    pub fn total_kwh(&self) -> f64 {
+        self.devices.iter().map(|d| d.energy_kwh).sum()
+    }
+
+    /// Returns the whole-home instantaneous power draw (W).
+    pub fn total_power_w(&self) -> f64 {
+        self.devices.iter().map(|d| d.power_w).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(h: u64) -> Duration {
+        Duration::from_secs(h * 3600)
+    }
+
+    #[test]
+    fn usage_over_window() {
+        let mut ledger = EnergyLedger::new();
+        let id = DeviceId(1);
+        ledger.record(hours(0), id, 1.0);
+        ledger.record(hours(1), id, 2.0);
+        ledger.record(hours(2), id, 4.0);
+
+        assert_eq!(ledger.usage_since(id, hours(1), 4.0), 2.0);
+        assert_eq!(ledger.usage_since(id, hours(0), 4.0), 3.0);
+        assert_eq!(ledger.usage_since(id, Duration::from_secs(5400), 4.0), 1.0);
+        assert_eq!(ledger.usage_since(DeviceId(9), hours(0), 4.0), 0.0);
+    }
+
+    #[test]
+    fn usage_counts_from_first_sample_for_new_devices() {
+        let mut ledger = EnergyLedger::new();
+        let id = DeviceId(2);
+        ledger.record(hours(5), id, 0.5);
+        assert_eq!(ledger.usage_since(id, hours(1), 1.5), 1.0);
+    }
+
+    #[test]
+    fn old_samples_are_pruned_but_keep_a_baseline() {
+        let mut ledger = EnergyLedger::new();
+        let id = DeviceId(3);
+        ledger.record(hours(0), id, 0.0);
+        ledger.record(hours(1), id, 1.0);
+        ledger.record(hours(2), id, 1.0);
+        ledger.record(hours(3), id, 1.0);
+        assert_eq!(ledger.samples[&id].len(), 3);
+        ledger.record(HISTORY_RETENTION + hours(4), id, 5.0);
+
+        assert_eq!(ledger.samples[&id].len(), 2);
+        assert_eq!(ledger.usage_since(id, hours(3), 5.0), 4.0);
+
+        ledger.forget(id);
+        assert_eq!(ledger.usage_since(id, hours(0), 5.0), 0.0);
+    }
+
+    #[test]
+    fn report_totals() {
+        let device = |id, power_w, energy_kwh| DeviceEnergy {
+            id: DeviceId(id),
+            name: format!("Plug {}", id),
+            power_w,
+            energy_kwh,
+        };
+        let report = EnergyReport {
+            window: None,
+            devices: vec![device(1, 1500.0, 0.75), device(2, 40.0, 0.25)],
+        };
+        assert_eq!(report.total_kwh(), 1.0);
+        assert_eq!(report.total_power_w(), 1540.0);
+    }
+}
@@ -9,6 +9,8 @@
 //! ## Design Role
 //! - Forms the **core communication unit** for the Mediator pattern.
 //! - Carries device action details (e.g., turning on a light, setting a temperature).
+//! - Has an [`EventPriority`]; the hub delivers urgent events (such as a smoke
+//!   alarm) ahead of normal ones and past any observer filtering.
 //!
 //! ## Example
 //! ```
@@ -24,7 +26,9 @@
 //! assert_eq!(event.event_type, EventType::TurnOn);
 //! ```
 
+use crate::models::state::{AttributeValue, DeviceState};
 use chrono::Local;
+use std::fmt;
 
 /// Represents the type of action triggered within the smart home system.
 ///
@@ -36,20 +40,146 @@ pub enum EventType {
     TurnOff,
     Lock,
     Unlock,
+    Open,
+    Close,
+    Stop,
+    SetPosition,
+    SetTilt,
+    /// A window covering moved one step toward its target position.
+    PositionChanged,
+    /// A window covering's slats moved one step toward their target tilt.
+    TiltChanged,
+    /// A safety beam reported an obstruction; a closing garage door reverses.
+    ObstructionDetected,
+    ObstructionCleared,
     SetTemp,
+    SetHvacMode,
+    SetSetpoints,
+    AmbientTemperatureChanged,
+    /// A thermostat started or stopped heating, cooling or running its fan.
+    HvacActionChanged,
+    SetBrightness,
+    SetColorTemp,
+    SetColor,
+    SetFanSpeed,
+    SetOscillation,
+    SetFanDirection,
+    Play,
+    Pause,
+    /// A media player skipped to another queued track.
+    TrackChanged,
+    SetVolume,
+    SetMute,
+    SetSource,
+    /// A track was added to a media player's queue.
+    TrackQueued,
+    RunZone,
+    SetRainDelay,
+    /// An irrigation zone started watering.
+    ZoneStarted,
+    /// An irrigation zone finished its run.
+    ZoneFinished,
+    /// An irrigation controller's rain delay ran out.
+    RainDelayEnded,
+    /// A PIN code or user was granted access to a lock; the event's actor names it.
+    AccessGranted,
+    /// A PIN code or user lost access to a lock; the event's actor names it.
+    AccessRevoked,
+    /// An unlock attempt was rejected because of a missing, wrong or expired credential.
+    AccessDenied,
+    SetAutoRelock,
+    /// A lock's bolt failed to move and the lock needs attention.
+    LockJammed,
+    /// A lock's jam was cleared.
+    JamCleared,
+    MotionDetected,
+    MotionCleared,
+    ContactOpened,
+    ContactClosed,
+    /// A sensor reported a new temperature.
+    TemperatureChanged,
+    /// A sensor reported a new relative humidity.
+    HumidityChanged,
+    /// A sensor reported a new light level.
+    IlluminanceChanged,
+    /// A metering device reported a new power draw.
+    PowerChanged,
+    /// A metering device reported a new supply voltage.
+    VoltageChanged,
+    /// A metering device reported a new cumulative energy counter.
+    EnergyChanged,
+    /// A smoke or CO detector reported a hazard building up.
+    AlarmWarning,
+    /// A smoke or CO detector is sounding its alarm.
+    AlarmTriggered,
+    /// A detector's hazard has cleared.
+    AlarmCleared,
+    /// A sounding detector was temporarily silenced.
+    AlarmSilenced,
+    /// A detector completed a self-test.
+    AlarmTest,
+    /// A device was registered with the hub.
+    DeviceAdded,
+    /// A device was removed from the hub.
+    DeviceRemoved,
+    /// A device was renamed; the payload's `previous_name` attribute holds the old name.
+    DeviceRenamed,
+    /// A scene was applied; the event's device name is the scene's name and the
+    /// payload counts the `devices` restored and any that `failed`.
+    SceneActivated,
+    /// A rule sent a notification; the event's device name is the rule's name
+    /// and the payload's `message` attribute holds the text.
+    Notification,
+}
+
+/// How urgently an event must reach the observers.
+///
+/// Ordered from least to most urgent, so events can be sorted by priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventPriority {
+    Normal,
+    /// Needs attention soon (e.g. smoke building up).
+    High,
+    /// A life-safety alarm.
+    Critical,
+}
+
+impl EventType {
+    /// Returns the priority of events of this type.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::event::{EventPriority, EventType};
+    ///
+    /// assert_eq!(EventType::AlarmTriggered.priority(), EventPriority::Critical);
+    /// assert_eq!(EventType::TurnOn.priority(), EventPriority::Normal);
+    /// ```
+    pub fn priority(&self) -> EventPriority {
+        match self {
+            EventType::AlarmTriggered => EventPriority::Critical,
+            EventType::AlarmWarning => EventPriority::High,
+            _ => EventPriority::Normal,
+        }
+    }
 }
 
 /// Represents a single event generated by user input or device activity.
 ///
 /// The `Event` struct carries contextual information like the name of
-/// the device, its type, the event type, and an optional payload (such as
-/// a temperature value or configuration string).
+/// the device, its type, the event type, and an optional payload holding
+/// the device's structured [`DeviceState`] after the event.
+///
+/// `actor` records who or what caused the event (e.g. the user or PIN code
+/// that unlocked a door), for audit trails.
 #[derive(Debug, Clone)]
 pub struct Event {
     pub device_name: String,
     pub device_type: String,
     pub event_type: EventType,
-    pub payload: Option<String>,
+    pub payload: Option<DeviceState>,
+    pub actor: Option<String>,
+    /// Room the device is in, filled in by the hub.
+    pub room: Option<String>,
     pub timestamp: String,
 }
 
@@ -60,7 +190,7 @@ impl Event {
     /// - `device_name` — The name of the device.
     /// - `device_type` — The category/type of the device.
     /// - `event_type` — The action represented by this event.
-    /// - `payload` — Optional data value (e.g., `"22"` for temperature).
+    /// - `payload` — Optional device state (e.g., the new target temperature).
     ///
     /// # Returns
     /// A fully constructed [`Event`] instance with a timestamp.
@@ -68,36 +198,169 @@ impl Event {
     /// # Example
     /// ```
     /// use pulsehome::models::event::{Event, EventType};
+    /// use pulsehome::models::state::{AttributeValue, DeviceState, Unit};
     ///
+    /// let state = DeviceState::new()
+    ///     .with("target_temperature", AttributeValue::Measure(24.0, Unit::Celsius));
     /// let event = Event::new(
     ///     "Bedroom Thermostat",
     ///     "Thermostat",
     ///     EventType::SetTemp,
-    ///     Some("24".into())
+    ///     Some(state.clone())
     /// );
     ///
     /// assert_eq!(event.device_type, "Thermostat");
-    /// assert_eq!(event.payload, Some("24".to_string()));
+    /// assert_eq!(event.payload, Some(state));
     /// ```
     pub fn new(
         device_name: impl Into<String>,
         device_type: impl Into<String>,
         event_type: EventType,
-        payload: Option<String>,
+        payload: Option<DeviceState>,
     ) -> Self {
         Event {
             device_name: device_name.into(),
             device_type: device_type.into(),
             event_type,
             payload,
+            actor: None,
+            room: None,
             timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         }
     }
+
+    /// Returns the event with its actor set.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::event::{Event, EventType};
+    ///
+    /// let event = Event::new("Front Door", "DoorLock", EventType::Unlock, None)
+    ///     .with_actor("user:alice");
+    /// assert_eq!(event.actor.as_deref(), Some("user:alice"));
+    /// ```
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Returns the event with the room its device is in.
+    ///
+    /// # Example
+    /// ```
+    /// use pulsehome::models::event::{Event, EventType};
+    ///
+    /// let event = Event::new("Kettle", "SmartPlug", EventType::TurnOn, None).with_room("Kitchen");
+    /// assert_eq!(event.to_string(), "[Kitchen] Device 'Kettle' (SmartPlug) state: unknown");
+    /// ```
+    pub fn with_room(mut self, room: impl Into<String>) -> Self {
+        self.room = Some(room.into());
+        self
+    }
+
+    /// Returns the event's priority, as given by its [`EventType`].
+    pub fn priority(&self) -> EventPriority {
+        self.event_type.priority()
+    }
+}
+
+impl fmt::Display for Event {
+    /// Formats the event as a human-readable line, as printed by observers.
+    ///
+    /// Urgent events are prefixed with `[ALARM]` or `[WARNING]`, followed by
+    /// the device's room, if any, so output can be grouped by room. The actor,
+    /// if any, is appended in brackets (e.g. `[by user:alice]`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let payload = self
+            .payload
+            .as_ref()
+            .map_or_else(|| "unknown".to_string(), DeviceState::to_string);
+        match self.priority() {
+            EventPriority::Critical => write!(f, "[ALARM] ")?,
+            EventPriority::High => write!(f, "[WARNING] ")?,
+            EventPriority::Normal => {}
+        }
+        if let Some(room) = &self.room {
+            write!(f, "[{}] ", room)?;
+        }
+        match self.event_type {
+            EventType::DeviceAdded => write!(
+                f,
+                "Device '{}' ({}) added, state: {}",
+                self.device_name, self.device_type, payload
+            ),
+            EventType::DeviceRemoved => write!(
+                f,
+                "Device '{}' ({}) removed",
+                self.device_name, self.device_type
+            ),
+            EventType::DeviceRenamed => write!(
+                f,
+                "Device '{}' ({}) renamed to '{}'",
+                self.payload
+                    .as_ref()
+                    .and_then(|p| p.get("previous_name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown"),
+                self.device_type,
+                self.device_name
+            ),
+            EventType::AccessDenied => write!(
+                f,
+                "Device '{}' ({}) denied access",
+                self.device_name, self.device_type
+            ),
+            EventType::SceneActivated => {
+                let count = |key| {
+                    self.payload
+                        .as_ref()
+                        .and_then(|p| p.get(key))
+                        .and_then(AttributeValue::as_f64)
+                        .unwrap_or(0.0)
+                };
+                write!(
+                    f,
+                    "Scene '{}' activated on {} devices",
+                    self.device_name,
+                    count("devices")
+                )?;
+                let failed = count("failed");
+                if failed > 0.0 {
+                    write!(f, " ({} failed)", failed)?;
+                }
+                Ok(())
+            }
+            EventType::Notification => write!(
+                f,
+                "Rule '{}': {}",
+                self.device_name,
+                self.payload
+                    .as_ref()
+                    .and_then(|p| p.get("message"))
+                    .map_or_else(String::new, ToString::to_string)
+            ),
+            EventType::AlarmTest => write!(
+                f,
+                "Device '{}' ({}) self-test passed",
+                self.device_name, self.device_type
+            ),
+            _ => write!(
+                f,
+                "Device '{}' ({}) state: {}",
+                self.device_name, self.device_type, payload
+            ),
+        }?;
+        match &self.actor {
+            Some(actor) => write!(f, " [by {}]", actor),
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::models::state::Unit;
 
     #[test]
     fn create_event_turn_on_light() {
@@ -112,9 +375,79 @@ mod test {
             "Bedroom thermostat",
             "ThermoStat",
             EventType::SetTemp,
-            Some("24".to_string()),
+            Some(DeviceState::new().with(
+                "target_temperature",
+                AttributeValue::Measure(24.0, Unit::Celsius),
+            )),
         );
         assert_eq!(event.device_name, "Bedroom thermostat");
-        assert_eq!(event.payload.as_deref(), Some("24"));
+        assert_eq!(
+            event
+                .payload
+                .unwrap()
+                .get("target_temperature")
+                .and_then(AttributeValue::as_f64),
+            Some(24.0)
+        );
+    }
+
+    #[test]
+    fn display_state_change() {
+        let state = DeviceState::new().with("power", AttributeValue::Bool(true));
+        let event = Event::new("Porch", "Light", EventType::TurnOn, Some(state));
+        assert_eq!(event.to_string(), "Device 'Porch' (Light) state: on");
+    }
+
+    #[test]
+    fn display_actor() {
+        let state = DeviceState::new().with("lock", AttributeValue::Enum("unlocked".into()));
+        let event = Event::new("Front Door", "DoorLock", EventType::Unlock, Some(state))
+            .with_actor("code:guest");
+        assert_eq!(
+            event.to_string(),
+            "Device 'Front Door' (DoorLock) state: unlocked [by code:guest]"
+        );
+    }
+
+    #[test]
+    fn display_alarm_prefix() {
+        let state = DeviceState::new().with("smoke", AttributeValue::Enum("alarm".into()));
+        let event = Event::new("Hall", "SmokeAlarm", EventType::AlarmTriggered, Some(state));
+        assert_eq!(
+            event.to_string(),
+            "[ALARM] Device 'Hall' (SmokeAlarm) state: alarm"
+        );
+        assert!(EventPriority::Critical > EventPriority::High);
+    }
+
+    #[test]
+    fn display_rename() {
+        let event = Event::new(
+            "Kitchen Pendant",
+            "Light",
+            EventType::DeviceRenamed,
+            Some(DeviceState::new().with("previous_name", AttributeValue::Text("Light 1".into()))),
+        );
+        assert_eq!(
+            event.to_string(),
+            "Device 'Light 1' (Light) renamed to 'Kitchen Pendant'"
+        );
+    }
+
+    #[test]
+    fn display_scene_activated() {
+        let counts = DeviceState::new()
+            .with("devices", AttributeValue::Int(3))
+            .with("failed", AttributeValue::Int(1));
+        let event = Event::new(
+            "Movie Night",
+            "Scene",
+            EventType::SceneActivated,
+            Some(counts),
+        );
+        assert_eq!(
+            event.to_string(),
+            "Scene 'Movie Night' activated on 3 devices (1 failed)"
+        );
     }
 }
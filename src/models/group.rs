@@ -0,0 +1,268 @@
+//! # Group Module
+//!
+//! Groups are user-defined sets of devices ("Downstairs Lights", "All Locks")
+//! that the [`HomeHub`](crate::mediator::HomeHub) treats like a single virtual
+//! device. A command sent to a group fans out to every member and the
+//! per-member outcomes are collected in a [`GroupResult`]; the group's state
+//! is the members' states combined by [`aggregate_state`].
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::group::aggregate_state;
+//! use pulsehome::models::state::{AttributeValue, DeviceState};
+//!
+//! let locked = DeviceState::new().with("lock", AttributeValue::Enum("locked".into()));
+//! let unlocked = DeviceState::new().with("lock", AttributeValue::Enum("unlocked".into()));
+//!
+//! assert_eq!(aggregate_state(&[locked.clone(), locked.clone()]).to_string(), "all locked");
+//! assert_eq!(aggregate_state(&[locked, unlocked]).to_string(), "any unlocked");
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::device::DeviceId;
+use crate::models::event::Event;
+use crate::models::state::{AttributeValue, DeviceState};
+
+/// A named set of devices, kept by ID so members survive being renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub members: Vec<DeviceId>, // in the order they were added
+}
+
+impl Group {
+    /// Creates a group with the given members, ignoring duplicates.
+    pub fn new(name: impl Into<String>, members: impl IntoIterator<Item = DeviceId>) -> Self {
+        let mut group = Self {
+            name: name.into(),
+            members: Vec::new(),
+        };
+        for id in members {
+            group.add(id);
+        }
+        group
+    }
+
+    /// Adds a member; returns `false` if it was already in the group.
+    pub fn add(&mut self, id: DeviceId) -> bool {
+        if self.members.contains(&id) {
+            return false;
+        }
+        self.members.push(id);
+        true
+    }
+
+    /// Removes a member; returns `false` if it was not in the group.
+    pub fn remove(&mut self, id: DeviceId) -> bool {
+        let before = self.members.len();
+        self.members.retain(|m| *m != id);
+        self.members.len() != before
+    }
+}
+
+/// The outcome of a command fanned out to several devices.
+///
+/// A failing member does not stop the others; each member's name is paired
+/// with its own result, in the order the command was sent.
+#[derive(Debug)]
+pub struct GroupResult {
+    /// Name of the group (or room) the command was sent to.
+    pub target: String,
+    pub outcomes: Vec<(String, Result<Event, PulseHomeError>)>,
+}
+
+impl GroupResult {
+    /// Creates an empty result for the named group.
+    pub fn new(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Returns the events of the members that accepted the command.
+    pub fn succeeded(&self) -> impl Iterator<Item = (&str, &Event)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(name, result)| result.as_ref().ok().map(|e| (name.as_str(), e)))
+    }
+
+    /// Returns the errors of the members that rejected the command.
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &PulseHomeError)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(name, result)| result.as_ref().err().map(|e| (name.as_str(), e)))
+    }
+
+    /// Returns `true` if every member accepted the command.
+    pub fn is_complete(&self) -> bool {
+        self.outcomes.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// Returns `true` if no member was sent the command.
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+}
+
+/// Enumeration values that call for attention, least safe first. When members
+/// disagree, the group reads `any` of the first of these among them; values
+/// not listed rank after these, alphabetically.
+const ATTENTION_VALUES: [&str; 10] = [
+    "jammed", "alarm", "warning", "unlocked", "open", "opening", "closing", "stopped", "detected",
+    "watering",
+];
+
+/// Combines the states of a group's members into one state.
+///
+/// Only attributes every member has are kept. Switches read `all on`,
+/// `all off` or `some on`; other enumerations read `all <value>` when the
+/// members agree and otherwise `any <value>` for the least safe value among
+/// them, whatever the order of the members (so locks read `all locked` or
+/// `any unlocked`). Any other
+/// attribute is kept when all members share its value, shown as a range
+/// when numeric, and reads `mixed` otherwise.
+pub fn aggregate_state(states: &[DeviceState]) -> DeviceState {
+    let Some((first, others)) = states.split_first() else {
+        return DeviceState::new();
+    };
+    let mut aggregate = DeviceState::new();
+    for (key, value) in first.attributes() {
+        let values: Vec<&AttributeValue> = std::iter::once(Some(value))
+            .chain(others.iter().map(|s| s.get(key)))
+            .collect::<Option<_>>()
+            .unwrap_or_default();
+        if values.is_empty() {
+            continue;
+        }
+        aggregate.set(key, combine(&values));
+    }
+    aggregate
+}
+
+/// Combines one attribute's values across members (see [`aggregate_state`]).
+fn combine(values: &[&AttributeValue]) -> AttributeValue {
+    let first = values[0];
+    let uniform = values.iter().all(|v| *v == first);
+    match first {
+        AttributeValue::Bool(_) => {
+            let on = values.iter().filter(|v| v.as_bool() == Some(true)).count();
+            let summary = match on {
+                0 => "all off",
+                n if n == values.len() => "all on",
+                _ => "some on",
+            };
+            AttributeValue::Enum(summary.to_string())
+        }
+        AttributeValue::Enum(value) if uniform => AttributeValue::Enum(format!("all {}", value)),
+        AttributeValue::Enum(_) => {
+            let least_safe = values
+                .iter()
+                .map(|v| v.to_string())
+                .min_by_key(|v| {
+                    let rank = ATTENTION_VALUES.iter().position(|a| a == v);
+                    (rank.unwrap_or(ATTENTION_VALUES.len()), v.clone())
+                })
+                .unwrap();
+            AttributeValue::Enum(format!("any {}", least_safe))
+        }
+        _ if uniform => first.clone(),
+        AttributeValue::Measure(_, unit)
+            if values
+                .iter()
+                .all(|v| matches!(v, AttributeValue::Measure(_, u) if u == unit)) =>
+        {
+            let numbers = values.iter().filter_map(|v| v.as_f64());
+            let min = numbers.clone().fold(f64::INFINITY, f64::min);
+            let max = numbers.fold(f64::NEG_INFINITY, f64::max);
+            AttributeValue::Text(format!(
+                "{}–{}",
+                AttributeValue::Measure(min, *unit),
+                AttributeValue::Measure(max, *unit)
+            ))
+        }
+        _ => AttributeValue::Text("mixed".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::state::Unit;
+
+    fn light(on: bool, brightness: f64) -> DeviceState {
+        DeviceState::new()
+            .with("power", AttributeValue::Bool(on))
+            .with(
+                "brightness",
+                AttributeValue::Measure(brightness, Unit::Percent),
+            )
+    }
+
+    #[test]
+    fn switches_read_all_or_some() {
+        assert_eq!(
+            aggregate_state(&[light(true, 40.0), light(true, 40.0)]).to_string(),
+            "all on (brightness=40%)"
+        );
+        assert_eq!(
+            aggregate_state(&[light(true, 20.0), light(false, 60.0)]).to_string(),
+            "some on (brightness=20%–60%)"
+        );
+        assert_eq!(
+            aggregate_state(&[light(false, 100.0), light(false, 100.0)]).to_string(),
+            "all off (brightness=100%)"
+        );
+    }
+
+    #[test]
+    fn only_shared_attributes_are_kept() {
+        let plain = DeviceState::new().with("power", AttributeValue::Bool(true));
+        let names = DeviceState::new()
+            .with("power", AttributeValue::Bool(true))
+            .with("source", AttributeValue::Text("HDMI 1".into()));
+        let other = names
+            .clone()
+            .with("source", AttributeValue::Text("TV".into()));
+        assert_eq!(
+            aggregate_state(&[plain, light(true, 10.0)]).to_string(),
+            "all on"
+        );
+        assert_eq!(
+            aggregate_state(&[names, other]).to_string(),
+            "all on (source=mixed)"
+        );
+        assert!(aggregate_state(&[]).is_empty());
+    }
+
+    #[test]
+    fn disagreeing_members_read_the_least_safe_value() {
+        let lock =
+            |value: &str| DeviceState::new().with("lock", AttributeValue::Enum(value.into()));
+        for states in [
+            [lock("unlocked"), lock("locked"), lock("locked")],
+            [lock("locked"), lock("locked"), lock("unlocked")],
+        ] {
+            assert_eq!(aggregate_state(&states).to_string(), "any unlocked");
+        }
+        assert_eq!(
+            aggregate_state(&[lock("unlocked"), lock("jammed"), lock("locked")]).to_string(),
+            "any jammed"
+        );
+        let mode =
+            |value: &str| DeviceState::new().with("mode", AttributeValue::Enum(value.into()));
+        assert_eq!(
+            aggregate_state(&[mode("heat"), mode("cool")]).to_string(),
+            aggregate_state(&[mode("cool"), mode("heat")]).to_string()
+        );
+    }
+
+    #[test]
+    fn members_are_unique() {
+        let mut group = Group::new("Downstairs", [DeviceId(1), DeviceId(2), DeviceId(1)]);
+        assert_eq!(group.members, vec![DeviceId(1), DeviceId(2)]);
+        assert!(!group.add(DeviceId(2)));
+        assert!(group.remove(DeviceId(1)));
+        assert!(!group.remove(DeviceId(1)));
+    }
+}
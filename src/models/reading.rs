@@ -0,0 +1,104 @@
+//! # Reading Module
+//!
+//! This module defines [`SensorReading`], a single measurement pushed into a
+//! device through
+//! [`Device::report_reading`](crate::models::device::Device::report_reading),
+//! and the [`AlarmLevel`] reported by smoke and carbon-monoxide detectors.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::reading::{AlarmLevel, SensorReading};
+//!
+//! let reading = SensorReading::Smoke("warning".parse().unwrap());
+//! assert_eq!(reading, SensorReading::Smoke(AlarmLevel::Warning));
+//! assert_eq!(reading.name(), "smoke");
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::temperature::Temperature;
+use std::fmt;
+use std::str::FromStr;
+
+/// The hazard level reported by a detector, from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlarmLevel {
+    Clear,
+    /// The hazard is building up but has not reached the alarm threshold.
+    Warning,
+    Alarm,
+}
+
+impl fmt::Display for AlarmLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AlarmLevel::Clear => "clear",
+            AlarmLevel::Warning => "warning",
+            AlarmLevel::Alarm => "alarm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for AlarmLevel {
+    type Err = PulseHomeError;
+
+    /// Parses `clear`, `warning` or `alarm` (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "clear" | "ok" => Ok(AlarmLevel::Clear),
+            "warning" | "warn" => Ok(AlarmLevel::Warning),
+            "alarm" => Ok(AlarmLevel::Alarm),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "invalid alarm level '{}' (expected clear, warning or alarm)",
+                s
+            ))),
+        }
+    }
+}
+
+/// A single measurement reported by a sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorReading {
+    /// Whether motion is currently detected.
+    Motion(bool),
+    /// Whether a door or window contact is open.
+    Contact {
+        open: bool,
+    },
+    Temperature(Temperature),
+    /// Relative humidity in percent (0–100).
+    Humidity(f64),
+    /// Light level in lux.
+    Illuminance(f64),
+    /// Power drawn by a metered appliance in watts.
+    Power(f64),
+    /// Supply voltage in volts.
+    Voltage(f64),
+    /// Cumulative energy counter in kilowatt-hours.
+    Energy(f64),
+    /// Whether a safety beam (e.g. a garage door's photo-eye) is blocked.
+    Obstruction(bool),
+    /// Smoke level reported by a smoke detector.
+    Smoke(AlarmLevel),
+    /// Carbon-monoxide level reported by a CO detector.
+    CarbonMonoxide(AlarmLevel),
+}
+
+impl SensorReading {
+    /// Returns the reading's name as typed in the CLI (e.g. `"humidity"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            SensorReading::Motion(_) => "motion",
+            SensorReading::Contact { .. } => "contact",
+            SensorReading::Temperature(_) => "temperature",
+            SensorReading::Humidity(_) => "humidity",
+            SensorReading::Illuminance(_) => "illuminance",
+            SensorReading::Power(_) => "power",
+            SensorReading::Voltage(_) => "voltage",
+            SensorReading::Energy(_) => "energy",
+            SensorReading::Obstruction(_) => "obstruction",
+            SensorReading::Smoke(_) => "smoke",
+            SensorReading::CarbonMonoxide(_) => "co",
+        }
+    }
+}
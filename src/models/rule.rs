@@ -0,0 +1,459 @@
+//! # Rule Module
+//!
+//! Automation rules of the form *when* a [`Trigger`] fires, *if* every
+//! [`Condition`] holds, *then* run the [`Action`]s.
+//!
+//! A rule can be triggered by a device event (`Front Door raises access_denied`),
+//! a change in a device's state (`Hall Motion motion becomes detected`) or the
+//! time of day (`at 07:30`). Conditions look at other devices' states, a time
+//! window or the home mode; actions send device commands, activate scenes or
+//! notify the observers.
+//!
+//! The types here only describe rules and decide whether they match; the
+//! [`HomeHub`](crate::mediator::HomeHub) runs them as events are dispatched.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::rule::{Action, Condition, Rule, Trigger};
+//!
+//! let rule = Rule::new(
+//!     "Night Light",
+//!     "Hall Motion motion becomes detected".parse::<Trigger>().unwrap(),
+//! )
+//! .condition("between 22:00 and 06:00".parse::<Condition>().unwrap())
+//! .action(Action::Command {
+//!     device: "Hall Light".to_string(),
+//!     command: Command::TurnOn,
+//! });
+//!
+//! assert_eq!(
+//!     rule.to_string(),
+//!     "when Hall Motion motion becomes detected; if between 22:00 and 06:00; then turn_on Hall Light"
+//! );
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::command::Command;
+use crate::models::event::{Event, EventType};
+use crate::models::state::DeviceState;
+use chrono::{NaiveDateTime, NaiveTime};
+use std::fmt;
+use std::str::FromStr;
+
+/// What makes a rule run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    /// A device raised an event of the given type, named in snake case (see [`event_name`]).
+    Event { device: String, event: String },
+    /// An attribute of a device's state changed, optionally to a given value.
+    StateChange {
+        device: String,
+        attribute: String,
+        value: Option<String>,
+    },
+    /// The clock reached a time of day.
+    Time(NaiveTime),
+}
+
+impl Trigger {
+    /// Returns whether an event fires this trigger, given the device's state
+    /// before the event. Time triggers never match events.
+    pub fn matches(&self, event: &Event, previous: Option<&DeviceState>) -> bool {
+        match self {
+            Trigger::Event {
+                device,
+                event: name,
+            } => event.device_name == *device && event_name(&event.event_type) == *name,
+            Trigger::StateChange {
+                device,
+                attribute,
+                value,
+            } => {
+                let Some(current) = event.payload.as_ref().and_then(|p| p.get(attribute)) else {
+                    return false;
+                };
+                let current = current.to_string();
+                let changed = previous
+                    .and_then(|p| p.get(attribute))
+                    .is_none_or(|before| before.to_string() != current);
+                event.device_name == *device
+                    && changed
+                    && value
+                        .as_ref()
+                        .is_none_or(|v| v.eq_ignore_ascii_case(&current))
+            }
+            Trigger::Time(_) => false,
+        }
+    }
+
+    /// Returns whether a time trigger's time of day passed after `last` and
+    /// up to `now`. Other triggers are never due.
+    pub fn is_due(&self, last: NaiveDateTime, now: NaiveDateTime) -> bool {
+        let Trigger::Time(time) = self else {
+            return false;
+        };
+        last.date()
+            .iter_days()
+            .take_while(|day| *day <= now.date())
+            .map(|day| day.and_time(*time))
+            .any(|at| last < at && at <= now)
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = PulseHomeError;
+
+    /// Parses `<device> <attribute> becomes <value>`, `<device> <attribute> changes`,
+    /// `<device> raises <event>` or `at <HH:MM>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let invalid = || {
+            PulseHomeError::InvalidArgument(format!(
+                "invalid trigger '{}' (expected '<device> <attribute> becomes <value>', \
+                 '<device> <attribute> changes', '<device> raises <event>' or 'at <HH:MM>')",
+                s.trim()
+            ))
+        };
+        match words.as_slice() {
+            ["at", time] => Ok(Trigger::Time(parse_time(time)?)),
+            [device @ .., attribute, "changes"] if !device.is_empty() => Ok(Trigger::StateChange {
+                device: device.join(" "),
+                attribute: attribute.to_string(),
+                value: None,
+            }),
+            [device @ .., "raises", event] if !device.is_empty() => Ok(Trigger::Event {
+                device: device.join(" "),
+                event: event.to_lowercase(),
+            }),
+            _ => {
+                let (device, attribute, value) =
+                    split_clause(&words, "becomes").ok_or_else(invalid)?;
+                Ok(Trigger::StateChange {
+                    device,
+                    attribute,
+                    value: Some(value),
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::Event { device, event } => write!(f, "{} raises {}", device, event),
+            Trigger::StateChange {
+                device,
+                attribute,
+                value: Some(value),
+            } => write!(f, "{} {} becomes {}", device, attribute, value),
+            Trigger::StateChange {
+                device, attribute, ..
+            } => write!(f, "{} {} changes", device, attribute),
+            Trigger::Time(time) => write!(f, "at {}", time.format("%H:%M")),
+        }
+    }
+}
+
+/// Something that must hold for a triggered rule to run its actions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// An attribute of a device's state has a value (compared as shown, ignoring case).
+    State {
+        device: String,
+        attribute: String,
+        value: String,
+    },
+    /// The time of day is within a window; `from` after `to` wraps past midnight.
+    TimeWindow { from: NaiveTime, to: NaiveTime },
+    /// The home is in the given mode (e.g. `away`).
+    Mode(String),
+}
+
+impl Condition {
+    /// Returns whether the condition holds, looking device states up by name.
+    pub fn holds(
+        &self,
+        state_of: impl Fn(&str) -> Option<DeviceState>,
+        now: NaiveTime,
+        mode: &str,
+    ) -> bool {
+        match self {
+            Condition::State {
+                device,
+                attribute,
+                value,
+            } => state_of(device)
+                .and_then(|state| state.get(attribute).map(ToString::to_string))
+                .is_some_and(|current| current.eq_ignore_ascii_case(value)),
+            Condition::TimeWindow { from, to } if from <= to => *from <= now && now < *to,
+            Condition::TimeWindow { from, to } => *from <= now || now < *to,
+            Condition::Mode(expected) => expected.eq_ignore_ascii_case(mode),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = PulseHomeError;
+
+    /// Parses `<device> <attribute> is <value>`, `between <HH:MM> and <HH:MM>`
+    /// or `mode is <mode>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["between", from, "and", to] => Ok(Condition::TimeWindow {
+                from: parse_time(from)?,
+                to: parse_time(to)?,
+            }),
+            ["mode", "is", mode] => Ok(Condition::Mode(mode.to_string())),
+            _ => {
+                let (device, attribute, value) = split_clause(&words, "is").ok_or_else(|| {
+                    PulseHomeError::InvalidArgument(format!(
+                        "invalid condition '{}' (expected '<device> <attribute> is <value>', \
+                         'between <HH:MM> and <HH:MM>' or 'mode is <mode>')",
+                        s.trim()
+                    ))
+                })?;
+                Ok(Condition::State {
+                    device,
+                    attribute,
+                    value,
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::State {
+                device,
+                attribute,
+                value,
+            } => write!(f, "{} {} is {}", device, attribute, value),
+            Condition::TimeWindow { from, to } => write!(
+                f,
+                "between {} and {}",
+                from.format("%H:%M"),
+                to.format("%H:%M")
+            ),
+            Condition::Mode(mode) => write!(f, "mode is {}", mode),
+        }
+    }
+}
+
+/// Something a rule does when it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Sends a command to a device.
+    Command { device: String, command: Command },
+    /// Activates a scene.
+    Scene(String),
+    /// Sends a [`EventType::Notification`] with a message to the observers.
+    Notify(String),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Command { device, command } => match command.argument() {
+                Some(argument) => write!(f, "{} {} {}", command.name(), device, argument),
+                None => write!(f, "{} {}", command.name(), device),
+            },
+            Action::Scene(scene) => write!(f, "scene {}", scene),
+            Action::Notify(message) => write!(f, "notify {}", message),
+        }
+    }
+}
+
+/// A named automation: a trigger, the conditions it needs and the actions it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub trigger: Trigger,
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,
+    pub enabled: bool,
+}
+
+impl Rule {
+    /// Creates an enabled rule without conditions or actions.
+    pub fn new(name: impl Into<String>, trigger: Trigger) -> Self {
+        Self {
+            name: name.into(),
+            trigger,
+            conditions: Vec::new(),
+            actions: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Returns the rule with a condition added.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Returns the rule with an action added.
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Formats the rule in the clause syntax read by the CLI's `rule add`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "when {}", self.trigger)?;
+        for condition in &self.conditions {
+            write!(f, "; if {}", condition)?;
+        }
+        for action in &self.actions {
+            write!(f, "; then {}", action)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of checking a rule's conditions without running it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCheck {
+    /// Each condition paired with whether it holds right now.
+    pub conditions: Vec<(Condition, bool)>,
+}
+
+impl RuleCheck {
+    /// Returns `true` if every condition holds, so the rule would run if triggered now.
+    pub fn passes(&self) -> bool {
+        self.conditions.iter().all(|(_, holds)| *holds)
+    }
+}
+
+/// Returns an event type's name in snake case, as used by [`Trigger::Event`]
+/// (e.g. `access_denied` for [`EventType::AccessDenied`]).
+pub fn event_name(event_type: &EventType) -> String {
+    let mut name = String::new();
+    for (i, c) in format!("{:?}", event_type).chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+/// Parses a time of day written as `HH:MM`.
+fn parse_time(s: &str) -> Result<NaiveTime, PulseHomeError> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .map_err(|_| PulseHomeError::InvalidArgument(format!("invalid time '{}' (use HH:MM)", s)))
+}
+
+/// Splits `<device words> <attribute> <keyword> <value words>` at the last
+/// occurrence of `keyword`.
+fn split_clause(words: &[&str], keyword: &str) -> Option<(String, String, String)> {
+    let split = words
+        .iter()
+        .rposition(|w| w.eq_ignore_ascii_case(keyword))?;
+    let (device, attribute) = match &words[..split] {
+        [device @ .., attribute] if !device.is_empty() => (device.join(" "), attribute),
+        _ => return None,
+    };
+    let value = words[split + 1..].join(" ");
+    if value.is_empty() {
+        return None;
+    }
+    Some((device, attribute.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::state::AttributeValue;
+
+    fn motion(detected: bool) -> DeviceState {
+        let value = if detected { "detected" } else { "clear" };
+        DeviceState::new().with("motion", AttributeValue::Enum(value.into()))
+    }
+
+    fn time(s: &str) -> NaiveTime {
+        parse_time(s).unwrap()
+    }
+
+    #[test]
+    fn parses_and_formats_clauses() {
+        for text in [
+            "Hall Motion motion becomes detected",
+            "Front Door lock changes",
+            "Front Door raises access_denied",
+            "at 07:30",
+        ] {
+            assert_eq!(text.parse::<Trigger>().unwrap().to_string(), text);
+        }
+        for text in [
+            "Living Room Lamp power is on",
+            "between 22:00 and 06:00",
+            "mode is away",
+        ] {
+            assert_eq!(text.parse::<Condition>().unwrap().to_string(), text);
+        }
+        assert!("Hall Motion".parse::<Trigger>().is_err());
+        assert!("at noon".parse::<Trigger>().is_err());
+        assert!("Lamp is on".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn state_triggers_fire_on_changes_only() {
+        let trigger: Trigger = "Hall Motion motion becomes detected".parse().unwrap();
+        let event = Event::new(
+            "Hall Motion",
+            "MotionSensor",
+            EventType::MotionDetected,
+            Some(motion(true)),
+        );
+        assert!(trigger.matches(&event, Some(&motion(false))));
+        assert!(trigger.matches(&event, None));
+        assert!(!trigger.matches(&event, Some(&motion(true))));
+
+        let cleared = Event::new(
+            "Hall Motion",
+            "MotionSensor",
+            EventType::MotionCleared,
+            Some(motion(false)),
+        );
+        assert!(!trigger.matches(&cleared, Some(&motion(true))));
+        let any: Trigger = "Hall Motion motion changes".parse().unwrap();
+        assert!(any.matches(&cleared, Some(&motion(true))));
+        let raised: Trigger = "Hall Motion raises motion_cleared".parse().unwrap();
+        assert!(raised.matches(&cleared, None));
+    }
+
+    #[test]
+    fn time_triggers_are_due_once_their_time_passes() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let trigger = Trigger::Time(time("07:30"));
+        assert!(trigger.is_due(at("2026-03-01 07:29"), at("2026-03-01 07:30")));
+        assert!(!trigger.is_due(at("2026-03-01 07:30"), at("2026-03-01 07:31")));
+        assert!(trigger.is_due(at("2026-03-01 23:00"), at("2026-03-02 08:00")));
+        assert!(!trigger.is_due(at("2026-03-01 08:00"), at("2026-03-02 07:00")));
+    }
+
+    #[test]
+    fn conditions_check_state_time_and_mode() {
+        let state_of = |name: &str| (name == "Hall Motion").then(|| motion(true));
+        let detected: Condition = "Hall Motion motion is Detected".parse().unwrap();
+        assert!(detected.holds(state_of, time("12:00"), "home"));
+        let missing: Condition = "Porch Motion motion is detected".parse().unwrap();
+        assert!(!missing.holds(state_of, time("12:00"), "home"));
+
+        let night: Condition = "between 22:00 and 06:00".parse().unwrap();
+        assert!(night.holds(state_of, time("23:15"), "home"));
+        assert!(night.holds(state_of, time("05:59"), "home"));
+        assert!(!night.holds(state_of, time("06:00"), "home"));
+
+        let away = Condition::Mode("away".into());
+        assert!(away.holds(state_of, time("12:00"), "Away"));
+        assert!(!away.holds(state_of, time("12:00"), "home"));
+    }
+}
@@ -0,0 +1,425 @@
+//! # Scene Module
+//!
+//! A [`Scene`] is a named set of target states for several devices ("Movie
+//! Night": living room lights at 20%, blinds closed, thermostat at 21 °C).
+//! The [`HomeHub`](crate::mediator::HomeHub) captures scenes from the devices'
+//! current states and restores them in one go.
+//!
+//! Only the attributes a command can restore are kept (see
+//! [`SCENE_ATTRIBUTES`]); readings such as power usage or ambient temperature
+//! are left out. [`restore_commands`] turns the difference between a device's
+//! current state and its target into the commands that close it.
+//!
+//! Scenes refer to devices by name, so they can be written to a text file
+//! with [`format_scenes`] and read back in a later run with [`parse_scenes`],
+//! before the devices themselves are added again.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::command::Command;
+//! use pulsehome::models::scene::{Scene, restore_commands};
+//! use pulsehome::models::state::{AttributeValue, DeviceState, Unit};
+//!
+//! let dimmed = DeviceState::new()
+//!     .with("power", AttributeValue::Bool(true))
+//!     .with("brightness", AttributeValue::Measure(20.0, Unit::Percent));
+//! let scene = Scene::new("Movie Night").with_target("Living Room Light", dimmed);
+//!
+//! let off = DeviceState::new()
+//!     .with("power", AttributeValue::Bool(false))
+//!     .with("brightness", AttributeValue::Measure(100.0, Unit::Percent));
+//! let target = scene.target("Living Room Light").unwrap();
+//! assert_eq!(
+//!     restore_commands(&off, target),
+//!     vec![
+//!         Command::SetBrightness { level: 20, transition: None },
+//!         Command::TurnOn,
+//!     ]
+//! );
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::command::Command;
+use crate::models::event::Event;
+use crate::models::state::{AttributeValue, DeviceState, Unit};
+use crate::models::temperature::Temperature;
+use std::path::Path;
+
+/// Attributes a scene captures, in the order they are restored.
+///
+/// `power` comes last so a light is dimmed before it is switched on or off.
+pub const SCENE_ATTRIBUTES: &[&str] = &[
+    "mode",
+    "heat_setpoint",
+    "cool_setpoint",
+    "color_temp",
+    "color",
+    "brightness",
+    "speed",
+    "oscillating",
+    "direction",
+    "position",
+    "tilt",
+    "door",
+    "lock",
+    "source",
+    "volume",
+    "muted",
+    "playback",
+    "power",
+];
+
+/// A named set of target states, one per device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub name: String,
+    /// Device names paired with their target states, in the order they were captured.
+    pub targets: Vec<(String, DeviceState)>,
+}
+
+impl Scene {
+    /// Creates a scene without targets.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Returns the scene with a target added (see [`Scene::set_target`]).
+    pub fn with_target(mut self, device_name: impl Into<String>, state: DeviceState) -> Self {
+        self.set_target(device_name, state);
+        self
+    }
+
+    /// Sets a device's target, keeping only the attributes in [`SCENE_ATTRIBUTES`].
+    ///
+    /// A device that already has a target keeps its place in the scene.
+    pub fn set_target(&mut self, device_name: impl Into<String>, state: DeviceState) {
+        let device_name = device_name.into();
+        let mut target = DeviceState::new();
+        for (key, value) in state.attributes() {
+            if SCENE_ATTRIBUTES.contains(&key) {
+                target.set(key, value.clone());
+            }
+        }
+        match self
+            .targets
+            .iter_mut()
+            .find(|(name, _)| *name == device_name)
+        {
+            Some((_, existing)) => *existing = target,
+            None => self.targets.push((device_name, target)),
+        }
+    }
+
+    /// Returns the target state of a device, if the scene includes it.
+    pub fn target(&self, device_name: &str) -> Option<&DeviceState> {
+        self.targets
+            .iter()
+            .find(|(name, _)| name == device_name)
+            .map(|(_, state)| state)
+    }
+
+    /// Points the scene at a device's new name.
+    pub fn rename_device(&mut self, old_name: &str, new_name: &str) {
+        for (name, _) in &mut self.targets {
+            if name == old_name {
+                *name = new_name.to_string();
+            }
+        }
+    }
+}
+
+/// The outcome of activating a scene.
+#[derive(Debug)]
+pub struct SceneActivation {
+    /// The single [`SceneActivated`](crate::models::event::EventType::SceneActivated)
+    /// event sent to the observers.
+    pub event: Event,
+    /// Devices that could not be brought to their target, with the reason.
+    pub failures: Vec<(String, PulseHomeError)>,
+}
+
+/// Returns the commands that bring a device from `current` to `target`.
+///
+/// Only attributes in [`SCENE_ATTRIBUTES`] whose values differ are considered,
+/// so restoring a device that already matches its target sends nothing.
+/// Commands the device may not support are included; the caller filters them
+/// by [`Capabilities`](crate::models::capability::Capabilities).
+pub fn restore_commands(current: &DeviceState, target: &DeviceState) -> Vec<Command> {
+    let mut commands = Vec::new();
+    for key in SCENE_ATTRIBUTES {
+        let Some(value) = target.get(key) else {
+            continue;
+        };
+        // Values are compared as shown, so a target read back from a file
+        // matches the state it was captured from.
+        if current.get(key).map(ToString::to_string) == Some(value.to_string()) {
+            continue;
+        }
+        if let Some(command) = command_for(key, value, target)
+            && !commands.contains(&command)
+        {
+            commands.push(command);
+        }
+    }
+    commands
+}
+
+/// Builds the command that restores one attribute, if there is one.
+fn command_for(key: &str, value: &AttributeValue, target: &DeviceState) -> Option<Command> {
+    let text = value.to_string();
+    let percent = || value.as_f64().map(|v| v.round().clamp(0.0, 100.0) as u8);
+    let command = match key {
+        "mode" => Command::SetHvacMode(text.parse().ok()?),
+        "heat_setpoint" | "cool_setpoint" => Command::SetSetpoints {
+            heat: Temperature::from_attribute(target.get("heat_setpoint")?)?,
+            cool: Temperature::from_attribute(target.get("cool_setpoint")?)?,
+        },
+        "color_temp" => Command::SetColorTemp {
+            kelvin: value.as_f64()?.round() as u16,
+            transition: None,
+        },
+        "color" => Command::SetColor {
+            color: text.parse().ok()?,
+            transition: None,
+        },
+        "brightness" => Command::SetBrightness {
+            level: percent()?,
+            transition: None,
+        },
+        "speed" => Command::SetFanSpeed(text.parse().ok()?),
+        "oscillating" => Command::SetOscillation(value.as_bool()?),
+        "direction" => Command::SetFanDirection(text.parse().ok()?),
+        "position" => Command::SetPosition(percent()?),
+        "tilt" => Command::SetTilt(value.as_f64()?.round() as i16),
+        "door" if text == "open" => Command::Open,
+        "door" if text == "closed" => Command::Close,
+        "lock" if text == "locked" => Command::Lock,
+        "lock" if text == "unlocked" => Command::Unlock(None),
+        "source" => Command::SetSource(text),
+        "volume" => Command::SetVolume(percent()?),
+        "muted" => Command::SetMute(value.as_bool()?),
+        "playback" if text == "playing" => Command::Play,
+        "playback" if text == "paused" => Command::Pause,
+        "playback" if text == "stopped" => Command::Stop,
+        "power" => match value.as_bool()? {
+            true => Command::TurnOn,
+            false => Command::TurnOff,
+        },
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// Writes scenes in the text format read by [`parse_scenes`].
+///
+/// Each scene starts with its name in brackets, followed by one line per
+/// device: `Living Room Light: power=on, brightness=20%`.
+pub fn format_scenes(scenes: &[Scene]) -> String {
+    let mut out = String::from("# PulseHome scenes\n");
+    for scene in scenes {
+        out.push_str(&format!("\n[{}]\n", scene.name));
+        for (device_name, state) in &scene.targets {
+            let attributes: Vec<String> = state
+                .attributes()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            out.push_str(&format!("{}: {}\n", device_name, attributes.join(", ")));
+        }
+    }
+    out
+}
+
+/// Reads scenes written by [`format_scenes`].
+///
+/// Blank lines and lines starting with `#` are skipped.
+///
+/// # Errors
+/// Returns [`PulseHomeError::Config`] for a device line outside a scene or
+/// a line that is not `<device>: key=value, ...`.
+pub fn parse_scenes(source: &str) -> Result<Vec<Scene>, PulseHomeError> {
+    let mut scenes: Vec<Scene> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |reason: &str| PulseHomeError::Config {
+            line: index + 1,
+            reason: reason.to_string(),
+        };
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            scenes.push(Scene::new(name.trim()));
+            continue;
+        }
+        let scene = scenes
+            .last_mut()
+            .ok_or_else(|| error("device listed before any [scene]"))?;
+        let (device_name, attributes) = line
+            .split_once(':')
+            .ok_or_else(|| error("expected '<device>: key=value, ...'"))?;
+        let mut state = DeviceState::new();
+        for attribute in attributes
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+        {
+            let (key, value) = attribute
+                .split_once('=')
+                .ok_or_else(|| error(&format!("expected key=value, got '{}'", attribute)))?;
+            state.set(key.trim(), parse_value(value.trim()));
+        }
+        scene.set_target(device_name.trim(), state);
+    }
+    Ok(scenes)
+}
+
+/// Parses an attribute value as shown by its [`Display`](std::fmt::Display)
+/// implementation. Words other than `on` and `off` are read as text.
+fn parse_value(s: &str) -> AttributeValue {
+    const UNITS: &[Unit] = &[
+        Unit::Celsius,
+        Unit::Fahrenheit,
+        Unit::KilowattHour,
+        Unit::PartsPerMillion,
+        Unit::Minute,
+        Unit::Percent,
+        Unit::Kelvin,
+        Unit::Degree,
+        Unit::Watt,
+        Unit::Volt,
+        Unit::Lux,
+        Unit::Second,
+        Unit::Hour,
+    ];
+    match s {
+        "on" => return AttributeValue::Bool(true),
+        "off" => return AttributeValue::Bool(false),
+        _ => {}
+    }
+    if let Ok(i) = s.parse::<i64>() {
+        return AttributeValue::Int(i);
+    }
+    if let Ok(f) = s.parse::<f64>() {
+        return AttributeValue::Float(f);
+    }
+    UNITS
+        .iter()
+        .find_map(|unit| {
+            let number = s.strip_suffix(unit.symbol())?.trim_end().parse().ok()?;
+            Some(AttributeValue::Measure(number, *unit))
+        })
+        .unwrap_or_else(|| AttributeValue::Text(s.to_string()))
+}
+
+/// Reads scenes from a file written by [`save_file`].
+///
+/// # Errors
+/// - [`PulseHomeError::Config`] if the file cannot be read (reported as line 0).
+/// - Any error returned by [`parse_scenes`].
+pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<Scene>, PulseHomeError> {
+    let source = std::fs::read_to_string(path.as_ref()).map_err(|e| PulseHomeError::Config {
+        line: 0,
+        reason: format!("cannot read {}: {}", path.as_ref().display(), e),
+    })?;
+    parse_scenes(&source)
+}
+
+/// Writes scenes to a file, replacing its contents.
+///
+/// # Errors
+/// Returns [`PulseHomeError::Config`] if the file cannot be written (reported as line 0).
+pub fn save_file(path: impl AsRef<Path>, scenes: &[Scene]) -> Result<(), PulseHomeError> {
+    std::fs::write(path.as_ref(), format_scenes(scenes)).map_err(|e| PulseHomeError::Config {
+        line: 0,
+        reason: format!("cannot write {}: {}", path.as_ref().display(), e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::argument::HvacMode;
+
+    fn thermostat(mode: &str, heat: f64) -> DeviceState {
+        DeviceState::new()
+            .with("mode", AttributeValue::Enum(mode.into()))
+            .with(
+                "heat_setpoint",
+                AttributeValue::Measure(heat, Unit::Celsius),
+            )
+            .with(
+                "cool_setpoint",
+                AttributeValue::Measure(24.0, Unit::Celsius),
+            )
+            .with(
+                "ambient_temperature",
+                AttributeValue::Measure(18.5, Unit::Celsius),
+            )
+    }
+
+    #[test]
+    fn targets_keep_only_restorable_attributes() {
+        let scene = Scene::new("Evening").with_target("Hall Thermostat", thermostat("heat", 21.0));
+        let target = scene.target("Hall Thermostat").unwrap();
+        assert_eq!(target.get("ambient_temperature"), None);
+        assert_eq!(
+            target.to_string(),
+            "heat (heat_setpoint=21°C, cool_setpoint=24°C)"
+        );
+    }
+
+    #[test]
+    fn only_differences_are_restored() {
+        let current = thermostat("off", 19.0);
+        let target = thermostat("heat", 21.0);
+        assert_eq!(
+            restore_commands(&current, &target),
+            vec![
+                Command::SetHvacMode(HvacMode::Heat),
+                Command::SetSetpoints {
+                    heat: Temperature::from_celsius(21.0),
+                    cool: Temperature::from_celsius(24.0),
+                },
+            ]
+        );
+        assert!(restore_commands(&target, &target).is_empty());
+    }
+
+    #[test]
+    fn scenes_round_trip_through_text() {
+        let blinds = DeviceState::new()
+            .with("covering", AttributeValue::Enum("closed".into()))
+            .with("position", AttributeValue::Measure(0.0, Unit::Percent));
+        let player = DeviceState::new()
+            .with("playback", AttributeValue::Enum("playing".into()))
+            .with("muted", AttributeValue::Bool(false))
+            .with("source", AttributeValue::Text("HDMI 1".into()));
+        let scenes = vec![
+            Scene::new("Movie Night")
+                .with_target("Blinds", blinds)
+                .with_target("TV", player)
+                .with_target("Hall Thermostat", thermostat("off", 21.5)),
+        ];
+
+        let text = format_scenes(&scenes);
+        assert!(text.contains("[Movie Night]\nBlinds: position=0%\n"));
+        let parsed = parse_scenes(&text).unwrap();
+        assert_eq!(parsed.len(), 1);
+        for (name, state) in &scenes[0].targets {
+            let restored = parsed[0].target(name).unwrap();
+            assert!(restore_commands(state, restored).is_empty(), "{}", name);
+        }
+
+        assert!(matches!(
+            parse_scenes("Blinds: position=0%"),
+            Err(PulseHomeError::Config { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_scenes("[Night]\n\nBlinds position"),
+            Err(PulseHomeError::Config { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+//! # State Module
+//!
+//! This module defines [`DeviceState`], the structured state reported by every
+//! [`Device`](crate::models::device::Device) and carried by every
+//! [`Event`](crate::models::event::Event).
+//!
+//! A state is an ordered map of named attributes. Each attribute holds a typed
+//! [`AttributeValue`] (boolean, integer, float, measurement with a [`Unit`],
+//! enumerated value or free text), so automations can read values directly
+//! instead of parsing strings like `"22°C"`.
+//!
+//! The first attribute is the device's *primary* attribute; the [`Display`]
+//! rendering shows it on its own and lists any others in parentheses.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::state::{AttributeValue, DeviceState, Unit};
+//!
+//! let state = DeviceState::new()
+//!     .with("power", AttributeValue::Bool(true))
+//!     .with("brightness", AttributeValue::Measure(40.0, Unit::Percent));
+//!
+//! assert_eq!(state.get("power"), Some(&AttributeValue::Bool(true)));
+//! assert_eq!(state.to_string(), "on (brightness=40%)");
+//! ```
+//!
+//! [`Display`]: std::fmt::Display
+
+use crate::models::temperature::{Temperature, TemperatureUnit};
+use std::fmt;
+
+/// Unit of measurement attached to an [`AttributeValue::Measure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+    Percent,
+    Kelvin,
+    Degree,
+    Watt,
+    KilowattHour,
+    Volt,
+    Lux,
+    Second,
+    Minute,
+    Hour,
+    PartsPerMillion,
+}
+
+impl Unit {
+    /// Returns the symbol used when rendering values in this unit.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Percent => "%",
+            Unit::Kelvin => "K",
+            Unit::Degree => "°",
+            Unit::Watt => "W",
+            Unit::KilowattHour => "kWh",
+            Unit::Volt => "V",
+            Unit::Lux => "lx",
+            Unit::Second => "s",
+            Unit::Minute => "min",
+            Unit::Hour => "h",
+            Unit::PartsPerMillion => "ppm",
+        }
+    }
+
+    /// Whether the symbol is written directly after the number (`22°C`)
+    /// rather than separated by a space (`40 W`).
+    fn is_attached(&self) -> bool {
+        matches!(
+            self,
+            Unit::Celsius | Unit::Fahrenheit | Unit::Percent | Unit::Degree
+        )
+    }
+}
+
+/// A single typed attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    /// A binary attribute, rendered as `on`/`off`.
+    Bool(bool),
+    /// A whole number without a unit.
+    Int(i64),
+    /// A fractional number without a unit.
+    Float(f64),
+    /// A number with a unit of measurement.
+    Measure(f64, Unit),
+    /// One of a fixed set of values (e.g. `"locked"`, `"heat"`).
+    Enum(String),
+    /// Free-form text.
+    Text(String),
+}
+
+impl AttributeValue {
+    /// Returns the value as a boolean, if it is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttributeValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a number, if it is numeric.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AttributeValue::Int(i) => Some(*i as f64),
+            AttributeValue::Float(f) | AttributeValue::Measure(f, _) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a string slice, if it is an enum or text value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::Enum(s) | AttributeValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Formats a number with at most two decimals and no trailing zeros.
+fn format_number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Bool(true) => write!(f, "on"),
+            AttributeValue::Bool(false) => write!(f, "off"),
+            AttributeValue::Int(i) => write!(f, "{}", i),
+            AttributeValue::Float(v) => write!(f, "{}", format_number(*v)),
+            AttributeValue::Measure(v, unit) if unit.is_attached() => {
+                write!(f, "{}{}", format_number(*v), unit.symbol())
+            }
+            AttributeValue::Measure(v, unit) => {
+                write!(f, "{} {}", format_number(*v), unit.symbol())
+            }
+            AttributeValue::Enum(s) | AttributeValue::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// The structured state of a device: an ordered list of named attributes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeviceState {
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl DeviceState {
+    /// Creates an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the state with the given attribute added or replaced.
+    pub fn with(mut self, key: impl Into<String>, value: AttributeValue) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Adds or replaces an attribute, preserving its original position.
+    pub fn set(&mut self, key: impl Into<String>, value: AttributeValue) {
+        let key = key.into();
+        match self.attributes.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.attributes.push((key, value)),
+        }
+    }
+
+    /// Returns the value of an attribute, if present.
+    pub fn get(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Iterates over the attributes in order.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &AttributeValue)> {
+        self.attributes.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Returns a copy of the state with every temperature expressed in `unit`.
+    ///
+    /// Attributes measured in °C or °F are converted; all others are unchanged.
+    pub fn in_temperature_unit(&self, unit: TemperatureUnit) -> DeviceState {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(k, v)| match Temperature::from_attribute(v) {
+                Some(t) => (k.clone(), t.to_attribute_in(unit)),
+                None => (k.clone(), v.clone()),
+            })
+            .collect();
+        DeviceState { attributes }
+    }
+
+    /// Returns `true` if the state has no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+impl fmt::Display for DeviceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut attributes = self.attributes.iter();
+        let Some((_, primary)) = attributes.next() else {
+            return write!(f, "unknown");
+        };
+        write!(f, "{}", primary)?;
+
+        let rest: Vec<String> = attributes.map(|(k, v)| format!("{}={}", k, v)).collect();
+        if !rest.is_empty() {
+            write!(f, " ({})", rest.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_value_display() {
+        assert_eq!(AttributeValue::Bool(true).to_string(), "on");
+        assert_eq!(AttributeValue::Int(3).to_string(), "3");
+        assert_eq!(AttributeValue::Float(0.1 + 0.2).to_string(), "0.3");
+        assert_eq!(
+            AttributeValue::Measure(22.0, Unit::Celsius).to_string(),
+            "22°C"
+        );
+        assert_eq!(
+            AttributeValue::Measure(1.25, Unit::KilowattHour).to_string(),
+            "1.25 kWh"
+        );
+        assert_eq!(AttributeValue::Enum("locked".into()).to_string(), "locked");
+    }
+
+    #[test]
+    fn attribute_value_accessors() {
+        assert_eq!(AttributeValue::Bool(false).as_bool(), Some(false));
+        assert_eq!(
+            AttributeValue::Measure(21.5, Unit::Celsius).as_f64(),
+            Some(21.5)
+        );
+        assert_eq!(AttributeValue::Int(4).as_f64(), Some(4.0));
+        assert_eq!(AttributeValue::Enum("heat".into()).as_str(), Some("heat"));
+        assert_eq!(AttributeValue::Int(4).as_str(), None);
+    }
+
+    #[test]
+    fn state_set_replaces_in_place() {
+        let mut state = DeviceState::new()
+            .with("power", AttributeValue::Bool(false))
+            .with("brightness", AttributeValue::Measure(10.0, Unit::Percent));
+        state.set("power", AttributeValue::Bool(true));
+
+        let keys: Vec<&str> = state.attributes().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["power", "brightness"]);
+        assert_eq!(state.get("power"), Some(&AttributeValue::Bool(true)));
+        assert_eq!(state.get("missing"), None);
+    }
+
+    #[test]
+    fn state_temperature_conversion() {
+        let state = DeviceState::new()
+            .with("setpoint", AttributeValue::Measure(20.0, Unit::Celsius))
+            .with("humidity", AttributeValue::Measure(40.0, Unit::Percent));
+
+        let converted = state.in_temperature_unit(TemperatureUnit::Fahrenheit);
+        assert_eq!(converted.to_string(), "68°F (humidity=40%)");
+        assert_eq!(
+            converted.in_temperature_unit(TemperatureUnit::Celsius),
+            state
+        );
+    }
+
+    #[test]
+    fn state_display() {
+        assert_eq!(DeviceState::new().to_string(), "unknown");
+
+        let state = DeviceState::new().with("lock", AttributeValue::Enum("locked".into()));
+        assert_eq!(state.to_string(), "locked");
+
+        let state = state
+            .with("battery", AttributeValue::Measure(80.0, Unit::Percent))
+            .with("jammed", AttributeValue::Bool(false));
+        assert_eq!(state.to_string(), "locked (battery=80%, jammed=off)");
+    }
+}
@@ -0,0 +1,237 @@
+//! # Temperature Module
+//!
+//! Defines [`Temperature`], a unit-safe temperature value, and
+//! [`TemperatureUnit`], the unit used to enter and display temperatures.
+//!
+//! Temperatures are stored internally in degrees Celsius with fractional
+//! precision and converted on demand, so a value entered as `72F` and one
+//! entered as `22.2C` can be compared directly.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::temperature::{Temperature, TemperatureUnit};
+//!
+//! let t = Temperature::parse("72F", TemperatureUnit::Celsius).unwrap();
+//! assert_eq!(t.in_unit(TemperatureUnit::Fahrenheit), 72.0);
+//! assert_eq!(t.display_in(TemperatureUnit::Celsius), "22.22°C");
+//!
+//! let t = Temperature::parse("22.5", TemperatureUnit::Celsius).unwrap();
+//! assert_eq!(t.celsius(), 22.5);
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::state::{AttributeValue, Unit};
+use std::fmt;
+use std::str::FromStr;
+
+/// A unit in which temperatures can be entered and displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Returns the matching [`Unit`] for state attributes.
+    pub fn unit(&self) -> Unit {
+        match self {
+            TemperatureUnit::Celsius => Unit::Celsius,
+            TemperatureUnit::Fahrenheit => Unit::Fahrenheit,
+        }
+    }
+}
+
+impl fmt::Display for TemperatureUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.unit().symbol())
+    }
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = PulseHomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().trim_start_matches('°').to_lowercase().as_str() {
+            "c" | "celsius" => Ok(TemperatureUnit::Celsius),
+            "f" | "fahrenheit" => Ok(TemperatureUnit::Fahrenheit),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "unknown temperature unit '{}' (expected C or F)",
+                s
+            ))),
+        }
+    }
+}
+
+/// A temperature, stored in degrees Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Temperature {
+    celsius: f64,
+}
+
+impl Temperature {
+    /// Creates a temperature from degrees Celsius.
+    pub const fn from_celsius(celsius: f64) -> Self {
+        Self { celsius }
+    }
+
+    /// Creates a temperature from degrees Fahrenheit.
+    pub fn from_fahrenheit(fahrenheit: f64) -> Self {
+        Self::from_celsius((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    /// Creates a temperature from a value in the given unit.
+    pub fn new(value: f64, unit: TemperatureUnit) -> Self {
+        match unit {
+            TemperatureUnit::Celsius => Self::from_celsius(value),
+            TemperatureUnit::Fahrenheit => Self::from_fahrenheit(value),
+        }
+    }
+
+    /// Returns the temperature in degrees Celsius.
+    pub fn celsius(&self) -> f64 {
+        self.celsius
+    }
+
+    /// Returns the temperature in degrees Fahrenheit.
+    pub fn fahrenheit(&self) -> f64 {
+        self.celsius * 9.0 / 5.0 + 32.0
+    }
+
+    /// Returns the temperature expressed in the given unit.
+    pub fn in_unit(&self, unit: TemperatureUnit) -> f64 {
+        match unit {
+            TemperatureUnit::Celsius => self.celsius(),
+            TemperatureUnit::Fahrenheit => self.fahrenheit(),
+        }
+    }
+
+    /// Formats the temperature in the given unit (e.g. `"71.6°F"`).
+    pub fn display_in(&self, unit: TemperatureUnit) -> String {
+        self.to_attribute_in(unit).to_string()
+    }
+
+    /// Returns the temperature as a state attribute in degrees Celsius.
+    pub fn to_attribute(&self) -> AttributeValue {
+        self.to_attribute_in(TemperatureUnit::Celsius)
+    }
+
+    /// Returns the temperature as a state attribute in the given unit.
+    pub fn to_attribute_in(&self, unit: TemperatureUnit) -> AttributeValue {
+        AttributeValue::Measure(self.in_unit(unit), unit.unit())
+    }
+
+    /// Reads a temperature back from a state attribute in °C or °F.
+    pub fn from_attribute(value: &AttributeValue) -> Option<Self> {
+        match value {
+            AttributeValue::Measure(v, Unit::Celsius) => Some(Self::from_celsius(*v)),
+            AttributeValue::Measure(v, Unit::Fahrenheit) => Some(Self::from_fahrenheit(*v)),
+            _ => None,
+        }
+    }
+
+    /// Parses a temperature such as `"72F"`, `"22.5C"`, `"21°C"` or `"22"`.
+    ///
+    /// Values without a unit suffix are interpreted in `default_unit`.
+    pub fn parse(s: &str, default_unit: TemperatureUnit) -> Result<Self, PulseHomeError> {
+        let invalid = || PulseHomeError::InvalidArgument(format!("invalid temperature '{}'", s));
+        let trimmed = s.trim();
+        let split = trimmed
+            .find(|c: char| c.is_alphabetic() || c == '°')
+            .unwrap_or(trimmed.len());
+        let (number, suffix) = trimmed.split_at(split);
+
+        let value = number
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(invalid)?;
+        let unit = if suffix.is_empty() {
+            default_unit
+        } else {
+            suffix.parse().map_err(|_| invalid())?
+        };
+        Ok(Self::new(value, unit))
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_in(TemperatureUnit::Celsius))
+    }
+}
+
+impl FromStr for Temperature {
+    type Err = PulseHomeError;
+
+    /// Parses a temperature, treating values without a unit as Celsius.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, TemperatureUnit::Celsius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_between_units() {
+        let t = Temperature::from_fahrenheit(212.0);
+        assert_eq!(t.celsius(), 100.0);
+        assert_eq!(Temperature::from_celsius(-40.0).fahrenheit(), -40.0);
+        assert_eq!(
+            Temperature::new(50.0, TemperatureUnit::Fahrenheit).celsius(),
+            10.0
+        );
+    }
+
+    #[test]
+    fn parse_with_and_without_units() {
+        let unit = TemperatureUnit::Fahrenheit;
+        assert_eq!(Temperature::parse("22.5C", unit).unwrap().celsius(), 22.5);
+        assert_eq!(Temperature::parse("21°C", unit).unwrap().celsius(), 21.0);
+        assert_eq!(Temperature::parse("50", unit).unwrap().celsius(), 10.0);
+        assert_eq!(
+            Temperature::parse("68 f", TemperatureUnit::Celsius)
+                .unwrap()
+                .celsius(),
+            20.0
+        );
+        assert!(Temperature::parse("warm", unit).is_err());
+        assert!(Temperature::parse("20K", unit).is_err());
+        assert!(Temperature::parse("", unit).is_err());
+    }
+
+    #[test]
+    fn display_in_unit() {
+        let t = Temperature::from_celsius(22.5);
+        assert_eq!(t.to_string(), "22.5°C");
+        assert_eq!(t.display_in(TemperatureUnit::Fahrenheit), "72.5°F");
+    }
+
+    #[test]
+    fn attribute_round_trip() {
+        let t = Temperature::from_celsius(20.0);
+        let value = t.to_attribute_in(TemperatureUnit::Fahrenheit);
+        assert_eq!(value, AttributeValue::Measure(68.0, Unit::Fahrenheit));
+        assert_eq!(Temperature::from_attribute(&value), Some(t));
+        assert_eq!(
+            Temperature::from_attribute(&AttributeValue::Measure(20.0, Unit::Percent)),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_unit() {
+        assert_eq!(
+            "F".parse::<TemperatureUnit>().unwrap(),
+            TemperatureUnit::Fahrenheit
+        );
+        assert_eq!(
+            "celsius".parse::<TemperatureUnit>().unwrap(),
+            TemperatureUnit::Celsius
+        );
+        assert!("K".parse::<TemperatureUnit>().is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! # Topology Module
+//!
+//! Describes the layout of the home: floors containing rooms, and the room
+//! each device is placed in.
+//!
+//! The [`Topology`] only knows devices by their [`DeviceId`], so assignments
+//! survive a device being renamed or replaced. The
+//! [`HomeHub`](crate::mediator::HomeHub) keeps one and resolves device names.
+//!
+//! ## Example
+//! ```
+//! use pulsehome::models::device::DeviceId;
+//! use pulsehome::models::topology::Topology;
+//!
+//! let mut home = Topology::new();
+//! home.add_room("Kitchen", Some("Ground Floor")).unwrap();
+//! home.add_room("Bedroom", Some("Upstairs")).unwrap();
+//! home.assign(DeviceId(1), "Kitchen").unwrap();
+//!
+//! assert_eq!(home.room_of(DeviceId(1)), Some("Kitchen"));
+//! assert_eq!(home.floors(), vec!["Ground Floor", "Upstairs"]);
+//! assert_eq!(home.devices_in("Kitchen").unwrap(), vec![DeviceId(1)]);
+//! ```
+
+use crate::error::PulseHomeError;
+use crate::models::device::DeviceId;
+use std::collections::BTreeMap;
+
+/// A room, optionally on a floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    pub name: String,
+    pub floor: Option<String>,
+}
+
+/// The floors and rooms of a home, and which room each device is in.
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+    rooms: Vec<Room>, // in creation order
+    assignments: BTreeMap<DeviceId, String>,
+}
+
+impl Topology {
+    /// Creates a home without rooms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a room, optionally on a floor. Floors exist as long as they have rooms.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::InvalidArgument`] if the room name is empty.
+    /// - [`PulseHomeError::DuplicateRoom`] if a room with that name exists.
+    pub fn add_room(&mut self, name: &str, floor: Option<&str>) -> Result<(), PulseHomeError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "room name must not be empty".to_string(),
+            ));
+        }
+        if self.room(name).is_some() {
+            return Err(PulseHomeError::DuplicateRoom(name.to_string()));
+        }
+        self.rooms.push(Room {
+            name: name.to_string(),
+            floor: floor
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(String::from),
+        });
+        Ok(())
+    }
+
+    /// Removes a room and returns the devices that were in it, now unassigned.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn remove_room(&mut self, name: &str) -> Result<Vec<DeviceId>, PulseHomeError> {
+        let devices = self.devices_in(name)?;
+        self.rooms.retain(|r| r.name != name);
+        for id in &devices {
+            self.assignments.remove(id);
+        }
+        Ok(devices)
+    }
+
+    /// Returns the room with the given name.
+    pub fn room(&self, name: &str) -> Option<&Room> {
+        self.rooms.iter().find(|r| r.name == name)
+    }
+
+    /// Returns every room, in creation order.
+    pub fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    /// Returns the names of all floors, in the order their first room was created.
+    pub fn floors(&self) -> Vec<&str> {
+        let mut floors: Vec<&str> = Vec::new();
+        for floor in self.rooms.iter().filter_map(|r| r.floor.as_deref()) {
+            if !floors.contains(&floor) {
+                floors.push(floor);
+            }
+        }
+        floors
+    }
+
+    /// Places a device in a room, moving it out of any previous room.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn assign(&mut self, id: DeviceId, room: &str) -> Result<(), PulseHomeError> {
+        let room = self
+            .room(room)
+            .ok_or_else(|| PulseHomeError::RoomNotFound(room.to_string()))?;
+        self.assignments.insert(id, room.name.clone());
+        Ok(())
+    }
+
+    /// Takes a device out of its room, returning the room it was in.
+    pub fn unassign(&mut self, id: DeviceId) -> Option<String> {
+        self.assignments.remove(&id)
+    }
+
+    /// Returns the room a device is in, if it has been assigned one.
+    pub fn room_of(&self, id: DeviceId) -> Option<&str> {
+        self.assignments.get(&id).map(String::as_str)
+    }
+
+    /// Returns the devices in a room, in ID order.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::RoomNotFound`] if there is no such room.
+    pub fn devices_in(&self, room: &str) -> Result<Vec<DeviceId>, PulseHomeError> {
+        if self.room(room).is_none() {
+            return Err(PulseHomeError::RoomNotFound(room.to_string()));
+        }
+        Ok(self
+            .assignments
+            .iter()
+            .filter(|(_, r)| *r == room)
+            .map(|(id, _)| *id)
+            .collect())
+    }
+
+    /// Returns the devices in every room on a floor, in ID order.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::FloorNotFound`] if no room is on that floor.
+    pub fn devices_on(&self, floor: &str) -> Result<Vec<DeviceId>, PulseHomeError> {
+        if !self.floors().contains(&floor) {
+            return Err(PulseHomeError::FloorNotFound(floor.to_string()));
+        }
+        Ok(self
+            .assignments
+            .iter()
+            .filter(|(_, r)| self.room(r).and_then(|r| r.floor.as_deref()) == Some(floor))
+            .map(|(id, _)| *id)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rooms_are_unique_and_floors_follow_their_rooms() {
+        let mut home = Topology::new();
+        home.add_room("Kitchen", Some("Ground Floor")).unwrap();
+        home.add_room("Garage", None).unwrap();
+        assert!(matches!(
+            home.add_room("Kitchen", None),
+            Err(PulseHomeError::DuplicateRoom(_))
+        ));
+        assert!(home.add_room("  ", None).is_err());
+        assert_eq!(home.floors(), vec!["Ground Floor"]);
+
+        home.remove_room("Kitchen").unwrap();
+        assert!(home.floors().is_empty());
+        assert!(matches!(
+            home.remove_room("Kitchen"),
+            Err(PulseHomeError::RoomNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn devices_move_between_rooms() {
+        let mut home = Topology::new();
+        home.add_room("Kitchen", Some("Ground Floor")).unwrap();
+        home.add_room("Hall", Some("Ground Floor")).unwrap();
+        home.add_room("Bedroom", Some("Upstairs")).unwrap();
+
+        home.assign(DeviceId(2), "Kitchen").unwrap();
+        home.assign(DeviceId(1), "Hall").unwrap();
+        home.assign(DeviceId(3), "Bedroom").unwrap();
+        assert!(home.assign(DeviceId(4), "Attic").is_err());
+        assert_eq!(
+            home.devices_on("Ground Floor").unwrap(),
+            vec![DeviceId(1), DeviceId(2)]
+        );
+
+        home.assign(DeviceId(2), "Bedroom").unwrap();
+        assert_eq!(home.devices_in("Kitchen").unwrap(), vec![]);
+        assert_eq!(
+            home.remove_room("Bedroom").unwrap(),
+            vec![DeviceId(2), DeviceId(3)]
+        );
+        assert_eq!(home.room_of(DeviceId(3)), None);
+        assert_eq!(home.unassign(DeviceId(1)), Some("Hall".to_string()));
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! Defines the `Observer` trait for the PulseHome system.
 //! Observers get notified by the `HomeHub` whenever a device emits an event.
+//! An observer may narrow down what it receives with [`Observer::accepts`];
+//! urgent events (see [`EventPriority`](crate::models::event::EventPriority))
+//! are delivered regardless, to observers that persist events first.
 
 use crate::models::event::Event;
 
@@ -12,4 +15,21 @@ pub mod logger_observer;
 pub trait Observer {
     /// Called by HomeHub whenever a device generates an event.
     fn on_event(&mut self, event: &Event);
+
+    /// Returns whether this observer wants to receive the given event.
+    ///
+    /// Only consulted for routine events; the hub delivers more urgent events
+    /// to every observer. Accepts everything by default.
+    fn accepts(&self, event: &Event) -> bool {
+        let _ = event;
+        true
+    }
+
+    /// Returns whether this observer records events durably (e.g. to a file).
+    ///
+    /// The hub hands urgent events to persisting observers before any other,
+    /// so they are recorded even if a later observer brings the process down.
+    fn persists(&self) -> bool {
+        false
+    }
 }
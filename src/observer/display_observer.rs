@@ -8,6 +8,12 @@ use crate::observer::Observer;
 /// Observer that displays events to the console.
 pub struct DisplayObserver;
 
+impl Default for DisplayObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DisplayObserver {
     /// Creates a new DisplayObserver.
     pub fn new() -> Self {
@@ -17,12 +23,7 @@ impl DisplayObserver {
 
 impl Observer for DisplayObserver {
     fn on_event(&mut self, event: &Event) {
-        println!(
-            "[DisplayObserver] Device '{}' ({}) state: {}",
-            event.device_name,
-            event.device_type,
-            event.payload.as_deref().unwrap_or("unknown")
-        );
+        println!("[DisplayObserver] {}", event);
     }
 }
 
@@ -31,11 +32,13 @@ mod tests {
     use super::*;
     use crate::models::event::Event;
     use crate::models::event::EventType;
+    use crate::models::state::{AttributeValue, DeviceState};
 
     #[test]
     fn display_observer_receives_event() {
         let mut observer = DisplayObserver::new();
-        let event = Event::new("Light", "Light", EventType::TurnOn, Some("on".to_string()));
+        let state = DeviceState::new().with("power", AttributeValue::Bool(true));
+        let event = Event::new("Light", "Light", EventType::TurnOn, Some(state));
         observer.on_event(&event);
     }
 }
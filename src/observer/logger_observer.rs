@@ -26,32 +26,30 @@ impl Observer for LoggerObserver {
             .create(true)
             .append(true)
             .open(&self.file_path)
-            .and_then(|mut f| {
-                writeln!(
-                    f,
-                    "Device '{}' ({}) state: {}",
-                    event.device_name,
-                    event.device_type,
-                    event.payload.as_deref().unwrap_or("unknown")
-                )
-            })
+            .and_then(|mut f| writeln!(f, "{}", event))
         {
             eprintln!("[LoggerObserver] Failed to write log: {}", e);
         }
     }
+
+    fn persists(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::event::{Event, EventType};
+    use crate::models::state::{AttributeValue, DeviceState};
     use std::fs;
 
     #[test]
     fn logger_observer_writes_file() {
         let log_file = "test_log.txt";
         let mut observer = LoggerObserver::new(log_file);
-        let event = Event::new("Light", "Light", EventType::TurnOn, Some("on".to_string()));
+        let state = DeviceState::new().with("power", AttributeValue::Bool(true));
+        let event = Event::new("Light", "Light", EventType::TurnOn, Some(state));
         observer.on_event(&event);
 
         let content = fs::read_to_string(log_file).unwrap();
@@ -0,0 +1,695 @@
+//! # Scheduler Module
+//!
+//! Runs device commands at scheduled times through the
+//! [`HomeHub`]: at a time of day on some days of
+//! the week (`at 23:00 on weekdays`), at sunrise or sunset (`at sunset -15m`),
+//! on a cron expression (`cron */15 * * * *`) or once (`once 2024-12-24 18:00`).
+//!
+//! Sunrise and sunset are computed offline from the scheduler's
+//! [`Location`]. The scheduler reads the time from a [`Clock`], resolving
+//! wall-clock times through its time zone so jobs keep their local times
+//! across daylight saving changes, and a
+//! [`ManualClock`](crate::clock::ManualClock) can stand in for the system
+//! clock to run schedules deterministically.
+//!
+//! ## Example
+//! ```
+//! use chrono::{DateTime, TimeDelta};
+//! use pulsehome::devices::light::Light;
+//! use pulsehome::models::command::Command;
+//! use pulsehome::scheduler::sun::Location;
+//! use pulsehome::clock::ManualClock;
+//! use pulsehome::scheduler::{Job, Scheduler};
+//! use pulsehome::HomeHub;
+//!
+//! let mut hub = HomeHub::new();
+//! hub.register_device(Box::new(Light::new("Porch Light"))).unwrap();
+//!
+//! let clock = ManualClock::new(DateTime::parse_from_rfc3339("2024-06-21T12:00:00+01:00").unwrap());
+//! let mut scheduler = Scheduler::with_clock(clock.clone());
+//! scheduler.set_location(Location::new(51.5074, -0.1278).unwrap());
+//! let schedule = "at sunset".parse().unwrap();
+//! let first_run = scheduler
+//!     .add(Job::new("Porch", schedule, "Porch Light", Command::TurnOn))
+//!     .unwrap();
+//! assert_eq!(first_run.format("%H:%M").to_string(), "21:21");
+//!
+//! clock.advance(TimeDelta::hours(10));
+//! let outcomes = scheduler.run_due(&mut hub);
+//! assert_eq!(outcomes.len(), 1);
+//! assert_eq!(hub.device_state("Porch Light").unwrap().state.to_string(), "on");
+//! ```
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::PulseHomeError;
+use crate::mediator::HomeHub;
+use crate::models::command::Command;
+use crate::models::event::Event;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use cron::CronExpr;
+use std::fmt;
+use std::str::FromStr;
+use sun::{Location, SunEvent};
+
+pub mod cron;
+pub mod sun;
+
+/// A set of days of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Days(u8); // Monday is bit 0
+
+impl Days {
+    pub const EVERY_DAY: Days = Days(0b111_1111);
+    pub const WEEKDAYS: Days = Days(0b001_1111);
+    pub const WEEKENDS: Days = Days(0b110_0000);
+
+    /// Returns whether the set includes the given day.
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_monday()) != 0
+    }
+}
+
+impl FromStr for Days {
+    type Err = PulseHomeError;
+
+    /// Parses `daily`, `every day`, `weekdays`, `weekends` or a comma-separated
+    /// list of days (`mon,wed,fri`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" | "every day" => return Ok(Days::EVERY_DAY),
+            "weekdays" => return Ok(Days::WEEKDAYS),
+            "weekends" => return Ok(Days::WEEKENDS),
+            _ => {}
+        }
+        let mut days = 0;
+        for day in s.split(',') {
+            let day = day.trim().parse::<Weekday>().map_err(|_| {
+                PulseHomeError::InvalidArgument(format!(
+                    "invalid days '{}' (expected daily, weekdays, weekends or e.g. mon,wed,fri)",
+                    s
+                ))
+            })?;
+            days |= 1 << day.num_days_from_monday();
+        }
+        Ok(Days(days))
+    }
+}
+
+impl fmt::Display for Days {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Days::EVERY_DAY => write!(f, "every day"),
+            Days::WEEKDAYS => write!(f, "weekdays"),
+            Days::WEEKENDS => write!(f, "weekends"),
+            _ => {
+                let days: Vec<String> = [
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                    Weekday::Sat,
+                    Weekday::Sun,
+                ]
+                .into_iter()
+                .filter(|d| self.contains(*d))
+                .map(|d| d.to_string().to_lowercase())
+                .collect();
+                write!(f, "{}", days.join(","))
+            }
+        }
+    }
+}
+
+/// When a job runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Every minute matching a cron expression, e.g. `cron 0 7 * * mon-fri`.
+    Cron(CronExpr),
+    /// A time of day on some days of the week, e.g. `at 23:00 on weekdays`.
+    Daily { time: NaiveTime, days: Days },
+    /// Sunrise or sunset, shifted by an offset, e.g. `at sunset -15m`.
+    Sun {
+        event: SunEvent,
+        offset: TimeDelta,
+        days: Days,
+    },
+    /// A single run, e.g. `once 2024-12-24 18:00`.
+    Once(NaiveDateTime),
+}
+
+impl Schedule {
+    /// Returns the first time strictly after `after` that the schedule is due,
+    /// or `None` if it never is again.
+    ///
+    /// Times are wall-clock times in `clock`'s time zone, so a job keeps its
+    /// time of day when daylight saving time starts or ends. Sunrise and
+    /// sunset are computed for `location` and never come round without one.
+    pub fn next_after(
+        &self,
+        after: NaiveDateTime,
+        location: Option<&Location>,
+        clock: &dyn Clock,
+    ) -> Option<NaiveDateTime> {
+        let days_from = |first: NaiveDateTime, count: i64| {
+            (0..count).map_while(move |i| first.date().checked_add_signed(TimeDelta::days(i)))
+        };
+        match self {
+            Schedule::Cron(expr) => expr.next_after(after),
+            Schedule::Daily { time, days } => days_from(after, 8)
+                .filter(|day| days.contains(day.weekday()))
+                .map(|day| day.and_time(*time))
+                .find(|at| *at > after),
+            Schedule::Sun {
+                event,
+                offset,
+                days,
+            } => {
+                let location = location?;
+                // Start a day early in case the offset carries yesterday's time past `after`
+                days_from(after - TimeDelta::days(1), 368)
+                    .filter(|day| days.contains(day.weekday()))
+                    .filter_map(|day| location.sun_time_in(day, *event, |utc| clock.offset_at(utc)))
+                    .map(|at| at + *offset)
+                    .find(|at| *at > after)
+            }
+            Schedule::Once(at) => (*at > after).then_some(*at),
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = PulseHomeError;
+
+    /// Parses `at <HH:MM> [on <days>]`, `at <sunrise|sunset> [+|-<n>m|h] [on <days>]`,
+    /// `cron <expression>` or `once <YYYY-MM-DD> <HH:MM>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || {
+            PulseHomeError::InvalidArgument(format!(
+                "invalid schedule '{}' (expected 'at <HH:MM|sunrise|sunset> [on <days>]', \
+                 'cron <expression>' or 'once <YYYY-MM-DD> <HH:MM>')",
+                s
+            ))
+        };
+        let (keyword, rest) = s.split_once(' ').ok_or_else(invalid)?;
+        match keyword.to_lowercase().as_str() {
+            "cron" => Ok(Schedule::Cron(rest.parse()?)),
+            "once" => NaiveDateTime::parse_from_str(rest.trim(), "%Y-%m-%d %H:%M")
+                .map(Schedule::Once)
+                .map_err(|_| invalid()),
+            "at" => {
+                let (when, days) = match rest.split_once(" on ") {
+                    Some((when, days)) => (when, days.parse()?),
+                    None => (rest, Days::EVERY_DAY),
+                };
+                let words: Vec<&str> = when.split_whitespace().collect();
+                match words[..] {
+                    [time] if time.contains(':') => Ok(Schedule::Daily {
+                        time: NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| invalid())?,
+                        days,
+                    }),
+                    [event] => Ok(Schedule::Sun {
+                        event: event.parse()?,
+                        offset: TimeDelta::zero(),
+                        days,
+                    }),
+                    [event, offset] => Ok(Schedule::Sun {
+                        event: event.parse()?,
+                        offset: parse_offset(offset)?,
+                        days,
+                    }),
+                    _ => Err(invalid()),
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    /// Formats the schedule in the syntax read by [`Schedule::from_str`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = match self {
+            Schedule::Cron(expr) => return write!(f, "cron {}", expr),
+            Schedule::Once(at) => return write!(f, "once {}", at.format("%Y-%m-%d %H:%M")),
+            Schedule::Daily { time, days } => {
+                write!(f, "at {}", time.format("%H:%M"))?;
+                days
+            }
+            Schedule::Sun {
+                event,
+                offset,
+                days,
+            } => {
+                write!(f, "at {}", event)?;
+                let minutes = offset.num_minutes();
+                match minutes {
+                    0 => {}
+                    _ if minutes % 60 == 0 => write!(f, " {:+}h", minutes / 60)?,
+                    _ => write!(f, " {:+}m", minutes)?,
+                }
+                days
+            }
+        };
+        if *days != Days::EVERY_DAY {
+            write!(f, " on {}", days)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a signed offset from sunrise or sunset, such as `-15m` or `+1h`.
+fn parse_offset(s: &str) -> Result<TimeDelta, PulseHomeError> {
+    let invalid =
+        || PulseHomeError::InvalidArgument(format!("invalid offset '{}' (e.g. -15m or +1h)", s));
+    let (number, minutes_per_unit) = match s.chars().last() {
+        Some('h') => (&s[..s.len() - 1], 60),
+        Some('m') => (&s[..s.len() - 1], 1),
+        _ => return Err(invalid()),
+    };
+    if !number.starts_with(['+', '-']) {
+        return Err(invalid());
+    }
+    let amount = number.parse::<i64>().map_err(|_| invalid())?;
+    amount
+        .checked_mul(minutes_per_unit)
+        .filter(|minutes| minutes.abs() < 24 * 60)
+        .map(TimeDelta::minutes)
+        .ok_or_else(invalid)
+}
+
+/// A named command sent to a device on a schedule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub name: String,
+    pub schedule: Schedule,
+    pub device: String,
+    pub command: Command,
+    next_run: Option<NaiveDateTime>,
+}
+
+impl Job {
+    /// Creates a job; it is given its first run time when added to a [`Scheduler`].
+    pub fn new(
+        name: impl Into<String>,
+        schedule: Schedule,
+        device: impl Into<String>,
+        command: Command,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            schedule,
+            device: device.into(),
+            command,
+            next_run: None,
+        }
+    }
+
+    /// Returns when the job runs next, in the scheduler clock's local time.
+    pub fn next_run(&self) -> Option<NaiveDateTime> {
+        self.next_run
+    }
+}
+
+impl fmt::Display for Job {
+    /// Formats the job as `<schedule>; <command> <device> [value]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}; {} {}",
+            self.schedule,
+            self.command.name(),
+            self.device
+        )?;
+        match self.command.argument() {
+            Some(argument) => write!(f, " {}", argument),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Keeps the scheduled jobs and runs the ones that are due.
+pub struct Scheduler {
+    clock: Box<dyn Clock>,
+    location: Option<Location>,
+    jobs: Vec<Job>, // in creation order
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    /// Creates a scheduler on the system clock, without a location.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+
+    /// Creates a scheduler that reads the time from the given clock.
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        Self {
+            clock: Box::new(clock),
+            location: None,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Returns the current local time of the scheduler's clock.
+    pub fn now(&self) -> NaiveDateTime {
+        self.clock.now().naive_local()
+    }
+
+    /// Returns the location sunrise and sunset are computed for, if set.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Sets the location sunrise and sunset are computed for, and moves the
+    /// next run of every sunrise and sunset job accordingly.
+    pub fn set_location(&mut self, location: Location) {
+        self.location = Some(location);
+        let now = self.clock.now().naive_local();
+        for job in &mut self.jobs {
+            if matches!(job.schedule, Schedule::Sun { .. }) {
+                job.next_run = job
+                    .schedule
+                    .next_after(now, Some(&location), self.clock.as_ref());
+            }
+        }
+    }
+
+    /// Adds a job and returns when it will first run.
+    ///
+    /// # Errors
+    /// - [`PulseHomeError::InvalidArgument`] if the name is empty, the job runs
+    ///   at sunrise or sunset and no location is set, or it would never run.
+    /// - [`PulseHomeError::DuplicateSchedule`] if a job with that name exists.
+    pub fn add(&mut self, mut job: Job) -> Result<NaiveDateTime, PulseHomeError> {
+        if job.name.trim().is_empty() {
+            return Err(PulseHomeError::InvalidArgument(
+                "schedule name must not be empty".to_string(),
+            ));
+        }
+        if self.job(&job.name).is_ok() {
+            return Err(PulseHomeError::DuplicateSchedule(job.name));
+        }
+        if matches!(job.schedule, Schedule::Sun { .. }) && self.location.is_none() {
+            return Err(PulseHomeError::InvalidArgument(
+                "sunrise and sunset schedules need a location".to_string(),
+            ));
+        }
+        let next_run = job
+            .schedule
+            .next_after(self.now(), self.location.as_ref(), self.clock.as_ref())
+            .ok_or_else(|| {
+                PulseHomeError::InvalidArgument(format!("schedule '{}' would never run", job.name))
+            })?;
+        job.next_run = Some(next_run);
+        self.jobs.push(job);
+        Ok(next_run)
+    }
+
+    /// Removes a job and returns it.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::ScheduleNotFound`] if there is no such job.
+    pub fn remove(&mut self, name: &str) -> Result<Job, PulseHomeError> {
+        let index = self
+            .jobs
+            .iter()
+            .position(|j| j.name == name)
+            .ok_or_else(|| PulseHomeError::ScheduleNotFound(name.to_string()))?;
+        Ok(self.jobs.remove(index))
+    }
+
+    /// Returns the job with the given name.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::ScheduleNotFound`] if there is no such job.
+    pub fn job(&self, name: &str) -> Result<&Job, PulseHomeError> {
+        self.jobs
+            .iter()
+            .find(|j| j.name == name)
+            .ok_or_else(|| PulseHomeError::ScheduleNotFound(name.to_string()))
+    }
+
+    /// Returns every job, in creation order.
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// Sends the command of every job that is due to its device and returns
+    /// each job's name with the outcome, in creation order.
+    ///
+    /// A job runs at most once per call, even if several of its runs were
+    /// missed, and is then given its next run time after now. Jobs that will
+    /// not run again, such as one-shot jobs, are removed.
+    pub fn run_due(&mut self, hub: &mut HomeHub) -> Vec<(String, Result<Event, PulseHomeError>)> {
+        let now = self.now();
+        let location = self.location.as_ref();
+        let mut outcomes = Vec::new();
+        for job in &mut self.jobs {
+            if job.next_run.is_none_or(|at| at > now) {
+                continue;
+            }
+            let result = hub.execute_device_command(&job.device, job.command.clone());
+            outcomes.push((job.name.clone(), result));
+            job.next_run = job.schedule.next_after(now, location, self.clock.as_ref());
+        }
+        self.jobs.retain(|j| j.next_run.is_some());
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::devices::light::Light;
+    use crate::devices::thermostat::Thermostat;
+    use crate::models::temperature::Temperature;
+    use chrono::{DateTime, FixedOffset};
+
+    fn clock(at: &str) -> ManualClock {
+        ManualClock::new(DateTime::parse_from_rfc3339(at).unwrap())
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// A clock on UK time in 2024: BST (UTC+1) until 01:00 UTC on 27 October,
+    /// GMT after. Clones share the same time.
+    #[derive(Clone)]
+    struct UkClock(ManualClock);
+
+    impl Clock for UkClock {
+        fn now(&self) -> DateTime<FixedOffset> {
+            let utc = self.0.now().naive_utc();
+            self.0.now().with_timezone(&self.offset_at(utc))
+        }
+
+        fn offset_at(&self, utc: NaiveDateTime) -> FixedOffset {
+            let bst = utc >= at("2024-03-31 01:00") && utc < at("2024-10-27 01:00");
+            FixedOffset::east_opt(if bst { 3600 } else { 0 }).unwrap()
+        }
+    }
+
+    #[test]
+    fn parses_and_formats_schedules() {
+        for schedule in [
+            "at 07:30",
+            "at 23:00 on weekdays",
+            "at 09:00 on mon,wed,fri",
+            "at sunset",
+            "at sunrise -30m on weekends",
+            "at sunset +1h",
+            "cron */15 8-18 * * mon-fri",
+            "once 2024-12-24 18:00",
+        ] {
+            assert_eq!(schedule.parse::<Schedule>().unwrap().to_string(), schedule);
+        }
+        assert_eq!(
+            "at 06:00 on daily".parse::<Schedule>().unwrap().to_string(),
+            "at 06:00"
+        );
+        for schedule in [
+            "at noon",
+            "at 25:00",
+            "at sunset 15m",
+            "at sunset -2d",
+            "at 07:00 on someday",
+            "once tomorrow",
+            "every hour",
+        ] {
+            assert!(schedule.parse::<Schedule>().is_err(), "{}", schedule);
+        }
+    }
+
+    #[test]
+    fn finds_the_next_run() {
+        let utc = clock("2024-06-01T00:00:00+00:00");
+        let weekdays: Schedule = "at 23:00 on weekdays".parse().unwrap();
+        // Friday 2024-06-07 after 23:00 moves on to Monday
+        assert_eq!(
+            weekdays.next_after(at("2024-06-07 23:00"), None, &utc),
+            Some(at("2024-06-10 23:00"))
+        );
+        assert_eq!(
+            weekdays.next_after(at("2024-06-07 22:59"), None, &utc),
+            Some(at("2024-06-07 23:00"))
+        );
+
+        let once: Schedule = "once 2024-06-07 18:00".parse().unwrap();
+        assert_eq!(
+            once.next_after(at("2024-06-07 17:00"), None, &utc),
+            Some(at("2024-06-07 18:00"))
+        );
+        assert_eq!(once.next_after(at("2024-06-07 18:00"), None, &utc), None);
+
+        let london = Location::new(51.5074, -0.1278).unwrap();
+        let before_sunset: Schedule = "at sunset -1h".parse().unwrap();
+        assert_eq!(
+            before_sunset.next_after(at("2024-06-21 12:00"), None, &utc),
+            None
+        );
+        assert_eq!(
+            before_sunset
+                .next_after(at("2024-06-21 12:00"), Some(&london), &utc)
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .as_deref(),
+            Some("2024-06-21 19:21")
+        );
+    }
+
+    #[test]
+    fn runs_due_jobs_on_the_clock() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        hub.register_device(Box::new(Thermostat::new(
+            "Thermostat",
+            Temperature::from_celsius(21.0),
+        )))
+        .unwrap();
+        // Friday noon in London summer time
+        let clock = clock("2024-06-21T12:00:00+01:00");
+        let mut scheduler = Scheduler::with_clock(clock.clone());
+
+        let porch = Job::new(
+            "Porch",
+            "at sunset".parse().unwrap(),
+            "Porch Light",
+            Command::TurnOn,
+        );
+        assert!(scheduler.add(porch.clone()).is_err());
+        scheduler.set_location(Location::new(51.5074, -0.1278).unwrap());
+        scheduler.add(porch).unwrap();
+        let night = Job::new(
+            "Night Heat",
+            "at 23:00 on weekdays".parse().unwrap(),
+            "Thermostat",
+            Command::SetTemp(Temperature::from_celsius(18.0)),
+        );
+        assert_eq!(
+            scheduler.add(night.clone()).unwrap(),
+            at("2024-06-21 23:00")
+        );
+        assert!(matches!(
+            scheduler.add(night),
+            Err(PulseHomeError::DuplicateSchedule(_))
+        ));
+        scheduler
+            .add(Job::new(
+                "Porch Off",
+                "once 2024-06-22 01:00".parse().unwrap(),
+                "Porch Light",
+                Command::TurnOff,
+            ))
+            .unwrap();
+        assert_eq!(
+            scheduler.job("Night Heat").unwrap().to_string(),
+            "at 23:00 on weekdays; set_temp Thermostat 18°C"
+        );
+
+        assert!(scheduler.run_due(&mut hub).is_empty());
+        clock.advance(TimeDelta::hours(10)); // 22:00
+        let outcomes = scheduler.run_due(&mut hub);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].0, "Porch");
+        assert_eq!(
+            hub.device_state("Porch Light").unwrap().state.to_string(),
+            "on"
+        );
+        assert_eq!(
+            scheduler.job("Porch").unwrap().next_run().unwrap().date(),
+            at("2024-06-22 00:00").date()
+        );
+
+        // Missed runs are made up once, and one-shot jobs are removed after running
+        clock.advance(TimeDelta::hours(4)); // 02:00 on Saturday
+        let outcomes = scheduler.run_due(&mut hub);
+        let names: Vec<&str> = outcomes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["Night Heat", "Porch Off"]);
+        assert!(outcomes.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(
+            hub.device_state("Porch Light").unwrap().state.to_string(),
+            "off"
+        );
+        assert!(scheduler.job("Porch Off").is_err());
+        assert_eq!(
+            scheduler.job("Night Heat").unwrap().next_run(),
+            Some(at("2024-06-24 23:00"))
+        );
+
+        scheduler.remove("Night Heat").unwrap();
+        assert!(matches!(
+            scheduler.remove("Night Heat"),
+            Err(PulseHomeError::ScheduleNotFound(_))
+        ));
+        assert_eq!(scheduler.jobs().len(), 1);
+    }
+
+    #[test]
+    fn keeps_wall_clock_times_across_daylight_saving_changes() {
+        let mut hub = HomeHub::new();
+        hub.register_device(Box::new(Light::new("Porch Light")))
+            .unwrap();
+        // Saturday noon, the day before the clocks go back
+        let clock = UkClock(clock("2024-10-26T11:00:00+00:00"));
+        let mut scheduler = Scheduler::with_clock(clock.clone());
+        scheduler.set_location(Location::new(51.5074, -0.1278).unwrap());
+        let sunset = Job::new(
+            "Porch",
+            "at sunset".parse().unwrap(),
+            "Porch Light",
+            Command::TurnOn,
+        );
+        let morning = Job::new(
+            "Morning",
+            "at 07:00".parse().unwrap(),
+            "Porch Light",
+            Command::TurnOff,
+        );
+        let minute = |t: NaiveDateTime| t.format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(minute(scheduler.add(sunset).unwrap()), "2024-10-26 17:43");
+        assert_eq!(scheduler.add(morning).unwrap(), at("2024-10-27 07:00"));
+
+        // After the change, sunset is an hour earlier on the clock, not on UTC
+        clock.0.advance(TimeDelta::hours(8)); // 20:00 BST
+        scheduler.run_due(&mut hub);
+        assert_eq!(
+            minute(scheduler.job("Porch").unwrap().next_run().unwrap()),
+            "2024-10-27 16:41"
+        );
+        clock.0.advance(TimeDelta::hours(12)); // 07:00 GMT
+        assert_eq!(scheduler.run_due(&mut hub).len(), 1);
+        assert_eq!(
+            scheduler.job("Morning").unwrap().next_run(),
+            Some(at("2024-10-28 07:00"))
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! # Cron Module
+//!
+//! Parses five-field cron expressions (`minute hour day-of-month month
+//! day-of-week`) and finds the times they match.
+//!
+//! Each field accepts `*`, single values, ranges (`1-5`), steps (`*/15`,
+//! `8-18/2`) and comma-separated lists of these. Months and days of the week
+//! may also be written as names (`jan`, `mon`); Sunday is `0` or `7`. As in
+//! classic cron, when both the day of the month and the day of the week are
+//! restricted, a day matching either one counts.
+//!
+//! ## Example
+//! ```
+//! use chrono::NaiveDateTime;
+//! use pulsehome::scheduler::cron::CronExpr;
+//!
+//! let weekday_mornings: CronExpr = "30 6 * * mon-fri".parse().unwrap();
+//! let friday = NaiveDateTime::parse_from_str("2024-06-07 07:00", "%Y-%m-%d %H:%M").unwrap();
+//!
+//! let next = weekday_mornings.next_after(friday).unwrap();
+//! assert_eq!(next.format("%a %H:%M").to_string(), "Mon 06:30");
+//! ```
+
+use crate::error::PulseHomeError;
+use chrono::{Datelike, NaiveDateTime, TimeDelta, Timelike};
+use std::fmt;
+use std::str::FromStr;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How many days ahead [`CronExpr::next_after`] looks before giving up
+/// (long enough to reach the next 29 February).
+const SEARCH_DAYS: i64 = 366 * 8;
+
+/// A parsed cron expression. Each field is kept as a bit set of the values it
+/// matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    fields: [String; 5], // as written, for display
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64, // Sunday is bit 0
+}
+
+impl CronExpr {
+    /// Returns whether the expression matches the minute containing `at`.
+    pub fn matches(&self, at: NaiveDateTime) -> bool {
+        self.matches_day(at) && has(self.hours, at.hour()) && has(self.minutes, at.minute())
+    }
+
+    /// Returns the first matching minute strictly after `after`, or `None` if
+    /// the expression never matches (e.g. `0 0 31 2 *`).
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        (0..SEARCH_DAYS)
+            .map_while(|offset| start.date().checked_add_signed(TimeDelta::days(offset)))
+            .filter(|day| self.matches_day(day.and_hms_opt(0, 0, 0).unwrap()))
+            .flat_map(|day| {
+                (0..24)
+                    .filter(|hour| has(self.hours, *hour))
+                    .flat_map(move |hour| {
+                        (0..60)
+                            .filter(|minute| has(self.minutes, *minute))
+                            .filter_map(move |minute| day.and_hms_opt(hour, minute, 0))
+                    })
+            })
+            .find(|candidate| *candidate >= start)
+    }
+
+    fn matches_day(&self, at: NaiveDateTime) -> bool {
+        if !has(self.months, at.month()) {
+            return false;
+        }
+        let day = has(self.days, at.day());
+        let weekday = has(self.weekdays, at.weekday().num_days_from_sunday());
+        match (self.fields[2] == "*", self.fields[4] == "*") {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = PulseHomeError;
+
+    /// Parses `<minute> <hour> <day-of-month> <month> <day-of-week>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "invalid cron expression '{}' (expected 5 fields: minute hour day month weekday)",
+                s
+            )));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAYS)?;
+        if has(weekdays, 7) {
+            weekdays = (weekdays | 1) & !(1 << 7); // 7 is also Sunday
+        }
+        Ok(Self {
+            fields: [minute, hour, day, month, weekday].map(|f| f.to_lowercase()),
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])?,
+            days: parse_field(day, 1, 31, &[])?,
+            months: parse_field(month, 1, 12, &MONTHS)?,
+            weekdays,
+        })
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.fields.join(" "))
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parses one field into a bit set of the values between `min` and `max` it
+/// matches. `names`, if given, name the values from the field's first value on.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, PulseHomeError> {
+    let invalid = || PulseHomeError::InvalidArgument(format!("invalid cron field '{}'", field));
+    let value = |text: &str| -> Result<u32, PulseHomeError> {
+        let text = text.to_lowercase();
+        let value = match names.iter().position(|n| *n == text) {
+            Some(index) => min + index as u32,
+            None => text.parse().map_err(|_| invalid())?,
+        };
+        (min..=max)
+            .contains(&value)
+            .then_some(value)
+            .ok_or_else(invalid)
+    };
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (from, to) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((from, to)) => (value(from)?, value(to)?),
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if from > to {
+            return Err(invalid());
+        }
+        for v in (from..=to).step_by(step as usize) {
+            set |= 1 << v;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        let expr: CronExpr = expr.parse().unwrap();
+        expr.next_after(at(after))
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    #[test]
+    fn finds_the_next_matching_minute() {
+        assert_eq!(
+            next("*/15 * * * *", "2024-06-01 10:07").as_deref(),
+            Some("2024-06-01 10:15")
+        );
+        assert_eq!(
+            next("0 23 * * 1-5", "2024-06-07 23:00").as_deref(),
+            Some("2024-06-10 23:00")
+        );
+        assert_eq!(
+            next("0 8-18/4 * * *", "2024-06-01 12:30").as_deref(),
+            Some("2024-06-01 16:00")
+        );
+        assert_eq!(
+            next("0 0 1 jan,jul *", "2024-06-15 00:00").as_deref(),
+            Some("2024-07-01 00:00")
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2024-03-01 00:00").as_deref(),
+            Some("2028-02-29 00:00")
+        );
+        assert_eq!(next("0 0 31 2 *", "2024-01-01 00:00"), None);
+    }
+
+    #[test]
+    fn either_day_field_may_match() {
+        // The 1st of the month or any Sunday (7 is also Sunday)
+        let expr: CronExpr = "0 9 1 * 7".parse().unwrap();
+        assert!(expr.matches(at("2024-06-01 09:00"))); // Saturday the 1st
+        assert!(expr.matches(at("2024-06-02 09:00"))); // Sunday
+        assert!(!expr.matches(at("2024-06-03 09:00")));
+        assert!(!expr.matches(at("2024-06-02 09:01")));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "0 0 0 * *",
+            "*/0 * * * *",
+        ] {
+            assert!(expr.parse::<CronExpr>().is_err(), "{}", expr);
+        }
+        assert!("5-1 * * * *".parse::<CronExpr>().is_err());
+        assert!("0 0 * * funday".parse::<CronExpr>().is_err());
+        assert_eq!(
+            "30 6 * * MON-FRI".parse::<CronExpr>().unwrap().to_string(),
+            "30 6 * * mon-fri"
+        );
+    }
+}
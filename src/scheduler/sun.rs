@@ -0,0 +1,238 @@
+//! # Sun Module
+//!
+//! Computes sunrise and sunset offline for a [`Location`], using the
+//! sunrise/sunset algorithm from the *Almanac for Computers*. Times are
+//! accurate to a minute or two outside the polar regions; on days when the
+//! sun never rises or never sets there is no time to return.
+//!
+//! ## Example
+//! ```
+//! use chrono::{FixedOffset, NaiveDate};
+//! use pulsehome::scheduler::sun::{Location, SunEvent};
+//!
+//! let london = Location::new(51.5074, -0.1278).unwrap();
+//! let midsummer = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+//! let bst = FixedOffset::east_opt(3600).unwrap();
+//!
+//! let sunset = london.sun_time(midsummer, SunEvent::Sunset, bst).unwrap();
+//! assert_eq!(sunset.format("%H:%M").to_string(), "21:21");
+//! ```
+
+use crate::error::PulseHomeError;
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta};
+use std::fmt;
+use std::str::FromStr;
+
+/// The sun's zenith angle at sunrise and sunset, allowing for refraction and
+/// the size of the sun's disc.
+const ZENITH: f64 = 90.833;
+
+/// Sunrise or sunset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+impl fmt::Display for SunEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SunEvent::Sunrise => write!(f, "sunrise"),
+            SunEvent::Sunset => write!(f, "sunset"),
+        }
+    }
+}
+
+impl FromStr for SunEvent {
+    type Err = PulseHomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sunrise" => Ok(SunEvent::Sunrise),
+            "sunset" => Ok(SunEvent::Sunset),
+            _ => Err(PulseHomeError::InvalidArgument(format!(
+                "invalid sun event '{}' (expected sunrise or sunset)",
+                s
+            ))),
+        }
+    }
+}
+
+/// A place on Earth, in decimal degrees (north and east are positive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Location {
+    /// Creates a location.
+    ///
+    /// # Errors
+    /// Returns [`PulseHomeError::InvalidArgument`] if the latitude is outside
+    /// −90..=90 or the longitude outside −180..=180.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, PulseHomeError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "invalid latitude '{}' (expected -90 to 90)",
+                latitude
+            )));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(PulseHomeError::InvalidArgument(format!(
+                "invalid longitude '{}' (expected -180 to 180)",
+                longitude
+            )));
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// Returns the wall-clock time of sunrise or sunset on a date, in the
+    /// given UTC offset, or `None` if the sun does not rise or set that day.
+    pub fn sun_time(
+        &self,
+        date: NaiveDate,
+        event: SunEvent,
+        offset: FixedOffset,
+    ) -> Option<NaiveDateTime> {
+        self.sun_time_in(date, event, |_| offset)
+    }
+
+    /// Returns the wall-clock time of sunrise or sunset on a date in a time
+    /// zone whose offset changes during the year, such as with daylight
+    /// saving time. `offset_at` returns the zone's offset at a UTC time.
+    pub fn sun_time_in(
+        &self,
+        date: NaiveDate,
+        event: SunEvent,
+        offset_at: impl Fn(NaiveDateTime) -> FixedOffset,
+    ) -> Option<NaiveDateTime> {
+        // The algorithm returns a UTC time of day that may belong to the day
+        // before or after in local time, so try the neighbouring days too.
+        [date.pred_opt()?, date, date.succ_opt()?]
+            .into_iter()
+            .filter_map(|day| self.utc_sun_time(day, event))
+            .map(|utc| utc + TimeDelta::seconds(offset_at(utc).local_minus_utc().into()))
+            .find(|local| local.date() == date)
+    }
+
+    /// Returns the UTC time of sunrise or sunset for a day of the year.
+    fn utc_sun_time(&self, date: NaiveDate, event: SunEvent) -> Option<NaiveDateTime> {
+        let (sin, cos, tan) = (
+            |deg: f64| deg.to_radians().sin(),
+            |deg: f64| deg.to_radians().cos(),
+            |deg: f64| deg.to_radians().tan(),
+        );
+        let lng_hour = self.longitude / 15.0;
+        let approx_hour = match event {
+            SunEvent::Sunrise => 6.0,
+            SunEvent::Sunset => 18.0,
+        };
+        let t = f64::from(date.ordinal()) + (approx_hour - lng_hour) / 24.0;
+
+        let mean_anomaly = 0.9856 * t - 3.289;
+        let true_longitude =
+            (mean_anomaly + 1.916 * sin(mean_anomaly) + 0.020 * sin(2.0 * mean_anomaly) + 282.634)
+                .rem_euclid(360.0);
+        let right_ascension = (0.91764 * tan(true_longitude))
+            .atan()
+            .to_degrees()
+            .rem_euclid(360.0);
+        // Put the right ascension in the same quadrant as the true longitude
+        let right_ascension = (right_ascension + (true_longitude / 90.0).floor() * 90.0
+            - (right_ascension / 90.0).floor() * 90.0)
+            / 15.0;
+
+        let sin_declination = 0.39782 * sin(true_longitude);
+        let cos_declination = sin_declination.asin().cos();
+        let cos_hour_angle = (cos(ZENITH) - sin_declination * sin(self.latitude))
+            / (cos_declination * cos(self.latitude));
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None; // the sun stays up, or down, all day
+        }
+        let hour_angle = match event {
+            SunEvent::Sunrise => 360.0 - cos_hour_angle.acos().to_degrees(),
+            SunEvent::Sunset => cos_hour_angle.acos().to_degrees(),
+        } / 15.0;
+
+        let local_mean_time = hour_angle + right_ascension - 0.06571 * t - 6.622;
+        let utc_hours = (local_mean_time - lng_hour).rem_euclid(24.0);
+        let seconds = (utc_hours * 3600.0).round() as i64;
+        Some(date.and_hms_opt(0, 0, 0)? + TimeDelta::seconds(seconds))
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.latitude, self.longitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time_of(location: &Location, date: &str, event: SunEvent, hours: i32) -> Option<String> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let offset = FixedOffset::east_opt(hours * 3600).unwrap();
+        location
+            .sun_time(date, event, offset)
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    #[test]
+    fn computes_sunrise_and_sunset() {
+        let london = Location::new(51.5074, -0.1278).unwrap();
+        assert_eq!(
+            time_of(&london, "2024-06-21", SunEvent::Sunrise, 1).as_deref(),
+            Some("2024-06-21 04:43")
+        );
+        assert_eq!(
+            time_of(&london, "2024-12-21", SunEvent::Sunset, 0).as_deref(),
+            Some("2024-12-21 15:53")
+        );
+
+        // Sunset in Los Angeles falls on the next day in UTC, sunrise in Sydney on the day before
+        let los_angeles = Location::new(34.0522, -118.2437).unwrap();
+        assert_eq!(
+            time_of(&los_angeles, "2024-06-21", SunEvent::Sunset, -7).as_deref(),
+            Some("2024-06-21 20:07")
+        );
+        let sydney = Location::new(-33.8688, 151.2093).unwrap();
+        assert_eq!(
+            time_of(&sydney, "2024-06-21", SunEvent::Sunrise, 10).as_deref(),
+            Some("2024-06-21 06:59")
+        );
+    }
+
+    #[test]
+    fn polar_days_have_no_sunrise_or_sunset() {
+        let tromso = Location::new(69.6492, 18.9553).unwrap();
+        assert_eq!(time_of(&tromso, "2024-06-21", SunEvent::Sunset, 2), None);
+        assert_eq!(time_of(&tromso, "2024-12-21", SunEvent::Sunrise, 1), None);
+        assert!(time_of(&tromso, "2024-03-21", SunEvent::Sunrise, 1).is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_coordinates() {
+        assert!(Location::new(91.0, 0.0).is_err());
+        assert!(Location::new(0.0, -180.5).is_err());
+        assert_eq!(
+            Location::new(51.5, -0.13).unwrap().to_string(),
+            "51.5, -0.13"
+        );
+        assert_eq!("Sunset".parse::<SunEvent>().unwrap(), SunEvent::Sunset);
+        assert!("noon".parse::<SunEvent>().is_err());
+    }
+}